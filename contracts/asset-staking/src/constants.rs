@@ -1,2 +1,4 @@
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const MAX_PENDING_UNBONDS: usize = 30;
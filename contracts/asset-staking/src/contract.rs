@@ -1,10 +1,15 @@
 use std::cmp::min;
 
-use crate::constants::{CONTRACT_NAME, CONTRACT_VERSION};
+use crate::constants::{CONTRACT_NAME, CONTRACT_VERSION, MAX_PENDING_UNBONDS};
 use crate::error::ContractError;
 use crate::state::{
-  ASSET_BRIBES, ASSET_CONFIG, ASSET_REWARD_DISTRIBUTION, ASSET_REWARD_RATE, CONFIG, SHARES, TOTAL,
-  UNCLAIMED_REWARDS, USER_ASSET_REWARD_RATE, WHITELIST,
+  get_total_working_balance, get_working_balance, ASSET_BRIBES, ASSET_CONFIG,
+  ASSET_EXTRA_REWARD_RATE, ASSET_EXTRA_REWARD_STREAM, ASSET_LAST_REWARD_UPDATE_S,
+  ASSET_REWARD_DISTRIBUTION, ASSET_REWARD_RATE, ASSET_REWARD_STREAM, ASSET_REWARD_TAKE,
+  ASSET_UNBONDING, CONFIG, LAST_REWARD_UPDATE_S, PAUSED, PENDING_UNBONDS, RECEIPT_DENOMS,
+  RECEIPT_DENOM_INDEX, SHARES, TOTAL, TOTAL_WORKING_BALANCE, UNCLAIMED_EXTRA_REWARDS,
+  UNCLAIMED_REWARDS, USER_ASSET_EXTRA_REWARD_RATE, USER_ASSET_REWARD_RATE, WHITELIST,
+  WORKING_BALANCE,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
@@ -14,21 +19,24 @@ use cosmwasm_std::{
 };
 use cw2::set_contract_version;
 use cw20::Cw20ReceiveMsg;
-use cw_asset::{Asset, AssetError, AssetInfo};
+use cw_asset::{Asset, AssetInfo};
 use ve3_shared::adapters::global_config_adapter::ConfigExt;
 use ve3_shared::constants::{
-  AT_ASSET_GAUGE, AT_ASSET_WHITELIST_CONTROLLER, AT_TAKE_RECIPIENT, SECONDS_PER_YEAR,
+  AT_ASSET_GAUGE, AT_ASSET_WHITELIST_CONTROLLER, AT_TAKE_RECIPIENT, AT_VE_GUARDIAN,
 };
 use ve3_shared::error::SharedError;
+use ve3_shared::extensions::asset_ext::AssetExt;
 use ve3_shared::extensions::asset_info_ext::AssetInfoExt;
 use ve3_shared::extensions::env_ext::EnvExt;
 use ve3_shared::helpers::assets::Assets;
+use ve3_shared::helpers::denom::{Coin, MsgBurn, MsgCreateDenom, MsgMint};
 use ve3_shared::helpers::general::addr_opt_fallback;
 use ve3_shared::helpers::take::{compute_balance_amount, compute_share_amount};
 use ve3_shared::msgs_asset_staking::{
-  AssetConfig, AssetConfigRuntime, AssetDistribution, AssetInfoWithConfig, CallbackMsg, Config,
-  Cw20HookMsg, ExecuteMsg, InstantiateMsg,
+  AssetConfig, AssetConfigField, AssetConfigRuntime, AssetDistribution, AssetInfoWithConfig,
+  CallbackMsg, Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PendingUnbond,
 };
+use ve3_shared::msgs_zapper::PostActionCreate;
 use ve3_shared::stake_config::StakeConfig;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -85,14 +93,58 @@ pub fn execute(
       recipient,
     } => {
       let recipient = addr_opt_fallback(deps.api, &recipient, info.sender.clone())?;
-      claim_rewards(deps, info, Some(vec![asset]), recipient)
+      claim_rewards(deps, env, info, Some(vec![asset]), recipient)
     },
     ExecuteMsg::ClaimRewards {
       assets,
       recipient,
     } => {
       let recipient = addr_opt_fallback(deps.api, &recipient, info.sender.clone())?;
-      claim_rewards(deps, info, assets, recipient)
+      claim_rewards(deps, env, info, assets, recipient)
+    },
+    ExecuteMsg::ClaimAndCompound {
+      assets,
+      into,
+      min_received,
+    } => claim_and_compound(deps, env, info, assets, into, min_received),
+    ExecuteMsg::WithdrawUnbonded {
+      limit,
+    } => withdraw_unbonded(deps, env, info, limit),
+    ExecuteMsg::Kick {
+      user,
+    } => {
+      let user = deps.api.addr_validate(&user)?;
+      kick(deps, env, user)
+    },
+    ExecuteMsg::AddRewards {
+      asset,
+      rewards,
+      duration,
+    } => add_rewards(deps, env, info, asset, rewards, duration),
+    ExecuteMsg::EmergencyUnstake {
+      asset,
+    } => emergency_unstake(deps, env, info, asset),
+    ExecuteMsg::TransferStake {
+      asset,
+      shares,
+      recipient,
+    } => {
+      let recipient = deps.api.addr_validate(&recipient)?;
+      transfer_stake(deps, env, info, asset, shares, recipient)
+    },
+    ExecuteMsg::WrapStake {
+      asset,
+      shares,
+      recipient,
+    } => {
+      let recipient = addr_opt_fallback(deps.api, &recipient, info.sender.clone())?;
+      wrap_stake(deps, env, info, asset, shares, recipient)
+    },
+    ExecuteMsg::UnwrapStake {
+      recipient,
+    } => {
+      let recipient = addr_opt_fallback(deps.api, &recipient, info.sender.clone())?;
+      unwrap_stake(deps, env, info, recipient)
     },
 
     // bot
@@ -115,6 +167,15 @@ pub fn execute(
       set_asset_reward_distribution(deps, info, asset_reward_distribution)
     },
 
+    // guardian
+    ExecuteMsg::SetAssetPaused {
+      asset,
+      paused,
+    } => set_asset_paused(deps, info, asset, paused),
+    ExecuteMsg::EmergencyWithdrawFromExternal {
+      asset,
+    } => emergency_withdraw_from_external(deps, env, info, asset),
+
     // contract
     ExecuteMsg::Callback(msg) => callback(deps, env, info, msg),
   }
@@ -146,6 +207,9 @@ fn callback(
       stake_config,
       recipient,
     } => distribute_take_callback(deps, env, info, stake_config, take_asset, recipient),
+    CallbackMsg::MigrateStake {
+      asset,
+    } => migrate_stake_callback(deps, env, info, asset),
   }
 }
 
@@ -225,23 +289,117 @@ fn _update_asset_config(
   update: &AssetInfoWithConfig<Addr>,
   config: &Config,
 ) -> Result<Vec<CosmosMsg>, ContractError> {
+  // settle the take with the previous config before it is replaced
+  let (balance, _) = TOTAL.may_load(deps.storage, &update.info)?.unwrap_or_default();
+  _take(deps, env, &update.info, balance, true)?;
+
   let current = ASSET_CONFIG.may_load(deps.storage, &update.info)?.unwrap_or_default();
   let mut updated = current.clone();
-  let new_config = update.config.clone().unwrap_or(AssetConfig {
-    yearly_take_rate: Some(config.default_yearly_take_rate),
-    stake_config: StakeConfig::Default,
-  });
+  let new_config = match update.config.clone() {
+    Some(new_config) => new_config,
+    None => {
+      // without a config the asset is reset to the defaults
+      updated.reward_stream_seconds = 0;
+      updated.unbonding_seconds = 0;
+      updated.max_boost = None;
+      updated.take_tiers = vec![];
+      updated.apr_cap = None;
+      updated.take_on_rewards = None;
+      updated.max_total_stake = None;
+      updated.max_stake_per_user = None;
+
+      AssetConfig {
+        yearly_take_rate: Some(config.default_yearly_take_rate),
+        stake_config: StakeConfig::Default,
+        reward_stream_seconds: None,
+        unbonding_seconds: None,
+        max_boost: None,
+        tokenized: None,
+        take_tiers: None,
+        take_on_rewards: None,
+        max_total_stake: None,
+        max_stake_per_user: None,
+        apr_cap: None,
+        clear: None,
+      }
+    },
+  };
   updated.stake_config = new_config.stake_config;
   updated.yearly_take_rate = new_config.yearly_take_rate.unwrap_or(config.default_yearly_take_rate);
 
-  if updated.yearly_take_rate > Decimal::percent(50) {
+  // not provided values keep the current config
+  if let Some(take_on_rewards) = new_config.take_on_rewards {
+    updated.take_on_rewards = Some(take_on_rewards);
+  }
+  if let Some(max_total_stake) = new_config.max_total_stake {
+    updated.max_total_stake = Some(max_total_stake);
+  }
+  if let Some(max_stake_per_user) = new_config.max_stake_per_user {
+    updated.max_stake_per_user = Some(max_stake_per_user);
+  }
+  if let Some(reward_stream_seconds) = new_config.reward_stream_seconds {
+    updated.reward_stream_seconds = reward_stream_seconds;
+  }
+  if let Some(unbonding_seconds) = new_config.unbonding_seconds {
+    updated.unbonding_seconds = unbonding_seconds;
+  }
+  if let Some(max_boost) = new_config.max_boost {
+    updated.max_boost = Some(max_boost);
+  }
+  if let Some(take_tiers) = new_config.take_tiers {
+    updated.take_tiers = take_tiers;
+  }
+  if let Some(apr_cap) = new_config.apr_cap {
+    updated.apr_cap = Some(apr_cap);
+  }
+  for field in new_config.clear.unwrap_or_default() {
+    match field {
+      AssetConfigField::TakeOnRewards => updated.take_on_rewards = None,
+      AssetConfigField::MaxTotalStake => updated.max_total_stake = None,
+      AssetConfigField::MaxStakePerUser => updated.max_stake_per_user = None,
+      AssetConfigField::MaxBoost => updated.max_boost = None,
+      AssetConfigField::AprCap => updated.apr_cap = None,
+    }
+  }
+
+  if updated.yearly_take_rate > Decimal::percent(50)
+    || updated.take_tiers.iter().any(|tier| tier.yearly_take_rate > Decimal::percent(50))
+    || updated.take_on_rewards.is_some_and(|rate| rate > Decimal::percent(50))
+  {
     return Err(ContractError::TakeRateLessOrEqual50);
   }
 
+  if updated.max_boost.is_some_and(|max_boost| max_boost < Decimal::one()) {
+    return Err(ContractError::InvalidMaxBoost);
+  }
+
+  let mut msgs = vec![];
+  match (new_config.tokenized, &current.receipt_denom) {
+    (Some(true), None) => {
+      let index = RECEIPT_DENOM_INDEX.may_load(deps.storage)?.unwrap_or_default();
+      RECEIPT_DENOM_INDEX.save(deps.storage, &(index + 1))?;
+
+      let subdenom = format!("{index}/stake");
+      let receipt_denom = format!("factory/{0}/{1}", env.contract.address, subdenom);
+      RECEIPT_DENOMS.save(deps.storage, &receipt_denom, &update.info)?;
+      updated.receipt_denom = Some(receipt_denom);
+
+      msgs.push(
+        MsgCreateDenom {
+          sender: env.contract.address.to_string(),
+          subdenom,
+        }
+        .into(),
+      );
+    },
+    (Some(false), Some(_)) => return Err(ContractError::ReceiptDenomCannotBeRemoved),
+    _ => (),
+  }
+
   assert_reward_not_stake_denom(update)?;
+  assert_white_whale_not_shared(deps, &update.info, &updated.stake_config)?;
 
   ASSET_CONFIG.save(deps.storage, &update.info, &updated)?;
-  let mut msgs = vec![];
 
   let changed_provider = match (&current.stake_config, &updated.stake_config) {
     (StakeConfig::Default, StakeConfig::Default) => false,
@@ -265,6 +423,26 @@ fn _update_asset_config(
         ..
       },
     ) => contract != contract_new,
+    (
+      StakeConfig::WhiteWhale {
+        contract,
+        ..
+      },
+      StakeConfig::WhiteWhale {
+        contract: contract_new,
+        ..
+      },
+    ) => contract != contract_new,
+    (
+      StakeConfig::Custom {
+        contract,
+        ..
+      },
+      StakeConfig::Custom {
+        contract: contract_new,
+        ..
+      },
+    ) => contract != contract_new,
     _ => true,
   };
 
@@ -276,15 +454,74 @@ fn _update_asset_config(
 
     let mut unstake_msgs =
       current.stake_config.unstake_check_received_msg(deps, env, asset.clone())?;
-    let mut stake_msgs = updated.stake_config.stake_check_received_msg(deps, env, asset)?;
-
     msgs.append(&mut unstake_msgs);
-    msgs.append(&mut stake_msgs);
+
+    // the deposit is created in a callback, so that its bribe tracking only starts after the bribes received
+    // from the withdrawal are tracked. Otherwise shared reward assets would be tracked twice.
+    if !asset.amount.is_zero() {
+      msgs.push(env.callback_msg(ExecuteMsg::Callback(CallbackMsg::MigrateStake {
+        asset,
+      }))?);
+    }
   }
 
   Ok(msgs)
 }
 
+fn set_asset_paused(
+  deps: DepsMut,
+  info: MessageInfo,
+  asset: AssetInfo,
+  paused: bool,
+) -> Result<Response, ContractError> {
+  let config = CONFIG.load(deps.storage)?;
+  assert_guardian(&deps, &info, &config)?;
+
+  if paused {
+    PAUSED.save(deps.storage, &asset, &true)?;
+  } else {
+    PAUSED.remove(deps.storage, &asset);
+  }
+
+  Ok(Response::new().add_attributes(vec![
+    ("action", "asset/set_asset_paused"),
+    ("asset", &asset.to_string()),
+    ("paused", &paused.to_string()),
+  ]))
+}
+
+fn emergency_withdraw_from_external(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  asset: AssetInfo,
+) -> Result<Response, ContractError> {
+  let config = CONFIG.load(deps.storage)?;
+  assert_guardian(&deps, &info, &config)?;
+  assert_asset_paused(&deps, &asset)?;
+
+  let mut asset_config = ASSET_CONFIG.may_load(deps.storage, &asset)?.unwrap_or_default();
+  let (balance, _) = TOTAL.may_load(deps.storage, &asset)?.unwrap_or_default();
+  let in_contract = asset.with_balance(balance - asset_config.harvested);
+
+  let msgs =
+    asset_config.stake_config.unstake_check_received_msg(&deps, &env, in_contract.clone())?;
+
+  // funds stay in the contract until the stake config is updated again
+  asset_config.stake_config = StakeConfig::Default;
+  ASSET_CONFIG.save(deps.storage, &asset, &asset_config)?;
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        ("action", "asset/emergency_withdraw_from_external"),
+        ("asset", &asset.to_string()),
+        ("amount", &in_contract.amount.to_string()),
+      ])
+      .add_messages(msgs),
+  )
+}
+
 fn whitelist_assets(
   mut deps: DepsMut,
   env: Env,
@@ -294,8 +531,10 @@ fn whitelist_assets(
   let config = CONFIG.load(deps.storage)?;
   assert_asset_whitelist_controller(&deps, &info, &config)?;
 
-  let asset_configs =
-    asset_configs.into_iter().map(|a| a.check(deps.api)).collect::<Result<Vec<_>, AssetError>>()?;
+  let asset_configs = asset_configs
+    .into_iter()
+    .map(|a| a.check(deps.api))
+    .collect::<Result<Vec<_>, SharedError>>()?;
 
   let assets_str =
     asset_configs.iter().map(|asset| asset.info.to_string()).collect::<Vec<String>>().join(",");
@@ -351,8 +590,9 @@ fn stake(
   recipient: Addr,
 ) -> Result<Response, ContractError> {
   // assert_asset_whitelisted(&deps, &asset)?;
+  assert_asset_not_paused(&deps, &asset)?;
 
-  let rewards = _calc_reward_share(deps.storage, recipient.clone(), asset.clone())?;
+  let rewards = _calc_reward_share(deps.storage, &env, recipient.clone(), asset.clone())?;
   if !rewards.is_zero() {
     UNCLAIMED_REWARDS.update(
       deps.storage,
@@ -365,6 +605,20 @@ fn stake(
   let (asset_config, asset_available) = _take(&mut deps, &env, &asset, balance, true)?;
   let share_amount = compute_share_amount(shares, amount, asset_available);
 
+  if let Some(max_total_stake) = asset_config.max_total_stake {
+    if asset_available.checked_add(amount)? > max_total_stake {
+      return Err(ContractError::MaxTotalStakeExceeded(max_total_stake));
+    }
+  }
+  if let Some(max_stake_per_user) = asset_config.max_stake_per_user {
+    let user_shares =
+      SHARES.may_load(deps.storage, (recipient.clone(), &asset))?.unwrap_or_default();
+    let user_balance = compute_balance_amount(shares, user_shares, asset_available);
+    if user_balance.checked_add(amount)? > max_stake_per_user {
+      return Err(ContractError::MaxStakePerUserExceeded(max_stake_per_user));
+    }
+  }
+
   SHARES.update(
     deps.storage,
     (recipient.clone(), &asset),
@@ -379,6 +633,8 @@ fn stake(
     &(balance.checked_add(amount)?, shares.checked_add(share_amount)?),
   )?;
 
+  _update_working_balance(&mut deps, &env, &recipient, &asset)?;
+
   Ok(
     Response::new()
       .add_attributes(vec![
@@ -407,26 +663,16 @@ fn _take(
   let config = ASSET_CONFIG.may_load(deps.storage, asset)?;
 
   if let Some(mut config) = config {
-    if config.yearly_take_rate.is_zero() {
-      let available = balance.checked_sub(config.taken)?;
-      return Ok((config, available));
-    }
-
     // only take if last taken set
-    if config.last_taken_s != 0 {
-      let take_diff_s = Uint128::new((env.block.time.seconds() - config.last_taken_s).into());
-      // total_balance * yearly_take_rate * taken_diff_s / SECONDS_PER_YEAR
-
-      let relevant_balance = balance.saturating_sub(config.taken);
-      let take_amount = config.yearly_take_rate
-        * relevant_balance.multiply_ratio(
-          min(take_diff_s, Uint128::new(SECONDS_PER_YEAR.into())),
-          SECONDS_PER_YEAR,
-        );
+    if config.takes_principal() && config.last_taken_s != 0 {
+      // total_balance * take_rate * taken_diff_s / SECONDS_PER_YEAR
+      let take_amount = config.take_amount(balance, env.block.time.seconds() - config.last_taken_s);
 
       config.taken = config.taken.checked_add(take_amount)?;
     }
 
+    // also moved forward when the principal is not taken, so that a later switch to a principal take
+    // does not charge the idle period retroactively.
     config.last_taken_s = env.block.time.seconds();
 
     if save_config {
@@ -451,8 +697,9 @@ fn unstake(
   if asset.amount.is_zero() {
     return Err(ContractError::AmountCannotBeZero {});
   }
+  assert_asset_not_paused(&deps, &asset.info)?;
 
-  let rewards = _calc_reward_share(deps.storage, sender.clone(), asset.info.clone())?;
+  let rewards = _calc_reward_share(deps.storage, &env, sender.clone(), asset.info.clone())?;
   if !rewards.is_zero() {
     UNCLAIMED_REWARDS.update(
       deps.storage,
@@ -484,9 +731,13 @@ fn unstake(
 
   let new_value = current_user_share - share_amount;
   if new_value.is_zero() {
-    SHARES.remove(deps.storage, (sender, &asset.info));
+    SHARES.remove(deps.storage, (sender.clone(), &asset.info));
   } else {
-    SHARES.save(deps.storage, (sender, &asset.info), &(current_user_share - share_amount))?;
+    SHARES.save(
+      deps.storage,
+      (sender.clone(), &asset.info),
+      &(current_user_share - share_amount),
+    )?;
   }
 
   TOTAL.save(
@@ -502,36 +753,292 @@ fn unstake(
     ),
   )?;
 
-  let msg = asset.info.with_balance(withdraw_amount).transfer_msg(&recipient)?;
+  _update_working_balance(&mut deps, &env, &sender, &asset.info)?;
+
+  let withdrawn = asset.info.with_balance(withdraw_amount);
+  let response = Response::new()
+    .add_attributes(vec![
+      ("action", "asset/unstake"),
+      ("user", info.sender.as_ref()),
+      ("recipient", recipient.as_ref()),
+      ("asset", &asset.info.to_string()),
+      ("amount", &withdraw_amount.to_string()),
+      ("share", &share_amount.to_string()),
+    ])
+    .add_messages(asset_config.stake_config.unstake_check_received_msg(
+      &deps,
+      &env,
+      withdrawn.clone(),
+    )?);
+
+  if asset_config.unbonding_seconds > 0 {
+    // the shares are already removed, so the unbonding amount does not earn rewards anymore
+    let release_at_s = env.block.time.seconds() + asset_config.unbonding_seconds;
+    ASSET_UNBONDING.update(deps.storage, &withdrawn.info, |unbonding| -> StdResult<_> {
+      Ok(unbonding.unwrap_or_default().checked_add(withdrawn.amount)?)
+    })?;
+    // stored for the sender, so that others can't grow the pending unbonds of a user
+    let unbond_recipient = if recipient == sender {
+      None
+    } else {
+      Some(recipient)
+    };
+    let mut unbonds = PENDING_UNBONDS.may_load(deps.storage, &sender)?.unwrap_or_default();
+    match unbonds.iter_mut().find(|unbond| {
+      unbond.asset.info == withdrawn.info
+        && unbond.release_at_s == release_at_s
+        && unbond.recipient == unbond_recipient
+    }) {
+      Some(unbond) => unbond.asset.amount = unbond.asset.amount.checked_add(withdrawn.amount)?,
+      None => {
+        if unbonds.len() >= MAX_PENDING_UNBONDS {
+          return Err(ContractError::TooManyPendingUnbonds(MAX_PENDING_UNBONDS));
+        }
+        unbonds.push(PendingUnbond {
+          asset: withdrawn,
+          release_at_s,
+          recipient: unbond_recipient,
+        });
+      },
+    }
+    PENDING_UNBONDS.save(deps.storage, &sender, &unbonds)?;
+
+    Ok(response.add_attribute("release_at_s", release_at_s.to_string()))
+  } else {
+    Ok(response.add_message(withdrawn.transfer_msg(&recipient)?))
+  }
+}
+
+fn emergency_unstake(
+  mut deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  asset: AssetInfo,
+) -> Result<Response, ContractError> {
+  assert_asset_paused(&deps, &asset)?;
+  let sender = info.sender;
 
+  // settles the reward rates of the user, the pending rewards themselves are forfeited.
+  _calc_reward_share(deps.storage, &env, sender.clone(), asset.clone())?;
+  UNCLAIMED_REWARDS.remove(deps.storage, (sender.clone(), &asset));
+  UNCLAIMED_EXTRA_REWARDS.remove(deps.storage, (sender.clone(), &asset));
+
+  let share_amount = SHARES.may_load(deps.storage, (sender.clone(), &asset))?.unwrap_or_default();
+  if share_amount.is_zero() {
+    return Err(ContractError::NothingStaked {});
+  }
+
+  let (balance, shares) = TOTAL.may_load(deps.storage, &asset)?.unwrap_or_default();
+  let (asset_config, asset_available) = _take(&mut deps, &env, &asset, balance, true)?;
+  let withdraw_amount = compute_balance_amount(shares, share_amount, asset_available);
+
+  // nothing is withdrawn from the external staker, it needs to be pulled back through EmergencyWithdrawFromExternal
+  let contract_balance = asset.query_balance(&deps.querier, env.contract.address.clone())?;
+
+  // pending unbonds and the not yet distributed take are held by the contract, but owed to others
+  let unbonding = ASSET_UNBONDING.may_load(deps.storage, &asset)?.unwrap_or_default();
+  let reward_take = ASSET_REWARD_TAKE
+    .may_load(deps.storage, &asset)?
+    .unwrap_or_default()
+    .get(&asset)
+    .map(|take| take.amount)
+    .unwrap_or_default();
+  let reserved = unbonding
+    .checked_add(asset_config.taken.checked_sub(asset_config.harvested)?)?
+    .checked_add(reward_take)?;
+
+  if contract_balance.saturating_sub(reserved) < withdraw_amount {
+    return Err(SharedError::InsufficientBalance("contract balance".to_string()).into());
+  }
+
+  SHARES.remove(deps.storage, (sender.clone(), &asset));
+  TOTAL.save(
+    deps.storage,
+    &asset,
+    &(
+      balance
+        .checked_sub(withdraw_amount)
+        .map_err(|_| SharedError::InsufficientBalance("total balance".to_string()))?,
+      shares
+        .checked_sub(share_amount)
+        .map_err(|_| SharedError::InsufficientBalance("total shares".to_string()))?,
+    ),
+  )?;
+
+  _update_working_balance(&mut deps, &env, &sender, &asset)?;
+
+  let withdrawn = asset.with_balance(withdraw_amount);
   Ok(
     Response::new()
       .add_attributes(vec![
-        ("action", "asset/unstake"),
-        ("user", info.sender.as_ref()),
-        ("recipient", recipient.as_ref()),
-        ("asset", &asset.info.to_string()),
+        ("action", "asset/emergency_unstake"),
+        ("user", sender.as_ref()),
+        ("asset", &asset.to_string()),
         ("amount", &withdraw_amount.to_string()),
         ("share", &share_amount.to_string()),
       ])
-      .add_messages(asset_config.stake_config.unstake_check_received_msg(
-        &deps,
-        &env,
-        asset.info.with_balance(withdraw_amount),
-      )?)
-      .add_message(msg),
+      .add_message(withdrawn.transfer_msg(&sender)?),
+  )
+}
+
+fn withdraw_unbonded(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  limit: Option<u32>,
+) -> Result<Response, ContractError> {
+  let user = info.sender;
+  let now = env.block.time.seconds();
+  let limit = limit.map(|limit| limit as usize).unwrap_or(usize::MAX);
+  let unbonds = PENDING_UNBONDS.may_load(deps.storage, &user)?.unwrap_or_default();
+
+  let mut released = vec![];
+  let mut pending = vec![];
+  for unbond in unbonds {
+    if unbond.release_at_s <= now && released.len() < limit {
+      released.push(unbond);
+    } else {
+      pending.push(unbond);
+    }
+  }
+
+  if released.is_empty() {
+    return Err(ContractError::NothingUnbonded {});
+  }
+
+  if pending.is_empty() {
+    PENDING_UNBONDS.remove(deps.storage, &user);
+  } else {
+    PENDING_UNBONDS.save(deps.storage, &user, &pending)?;
+  }
+
+  for unbond in released.iter() {
+    ASSET_UNBONDING.update(deps.storage, &unbond.asset.info, |unbonding| -> StdResult<_> {
+      Ok(unbonding.unwrap_or_default().checked_sub(unbond.asset.amount)?)
+    })?;
+  }
+
+  let mut withdrawn = Assets::default();
+  let mut transfers: Vec<(Addr, Assets)> = vec![];
+  for unbond in released {
+    withdrawn.add(&unbond.asset);
+    let to = unbond.recipient.unwrap_or_else(|| user.clone());
+    match transfers.iter_mut().find(|(recipient, _)| *recipient == to) {
+      Some((_, assets)) => assets.add(&unbond.asset),
+      None => transfers.push((to, vec![unbond.asset].into())),
+    }
+  }
+  let withdrawn_str =
+    withdrawn.0.iter().map(|asset| asset.to_string()).collect::<Vec<String>>().join(",");
+
+  let mut msgs = vec![];
+  for (recipient, assets) in transfers {
+    msgs.extend(assets.transfer_msgs(&recipient)?);
+  }
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        ("action", "asset/withdraw_unbonded"),
+        ("user", user.as_ref()),
+        ("withdrawn", &withdrawn_str),
+      ])
+      .add_messages(msgs),
   )
 }
 
 fn claim_rewards(
   deps: DepsMut,
+  env: Env,
   info: MessageInfo,
   assets: Option<Vec<AssetInfo>>,
   recipient: Addr,
 ) -> Result<Response, ContractError> {
   let user = info.sender;
   let config = CONFIG.load(deps.storage)?;
+  let (assets_str, total_rewards, extra_rewards) = _claim_rewards(deps, &env, &user, assets)?;
 
+  let mut response = Response::new().add_attributes(vec![
+    ("action", "asset/claim_rewards"),
+    ("user", user.as_ref()),
+    ("assets", &assets_str),
+    ("reward_amount", &total_rewards.to_string()),
+  ]);
+  if !total_rewards.is_zero() {
+    let rewards_asset = config.reward_info.with_balance(total_rewards);
+    response = response.add_message(rewards_asset.transfer_msg(&recipient)?);
+  }
+  if !extra_rewards.is_empty() {
+    let extra_str =
+      extra_rewards.0.iter().map(|asset| asset.to_string()).collect::<Vec<String>>().join(",");
+    response = response
+      .add_attribute("extra_rewards", extra_str)
+      .add_messages(extra_rewards.transfer_msgs(&recipient)?);
+  }
+  Ok(response)
+}
+
+fn claim_and_compound(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  assets: Option<Vec<AssetInfo>>,
+  into: AssetInfo,
+  min_received: Option<Uint128>,
+) -> Result<Response, ContractError> {
+  let user = info.sender;
+  let config = CONFIG.load(deps.storage)?;
+
+  if !WHITELIST.has(deps.storage, &into) {
+    return Err(ContractError::AssetNotWhitelisted);
+  }
+  assert_asset_not_paused(&deps, &into)?;
+
+  let zapper = config.zapper(&deps.querier)?;
+  let (assets_str, total_rewards, extra_rewards) = _claim_rewards(deps, &env, &user, assets)?;
+
+  if total_rewards.is_zero() {
+    return Err(ContractError::NothingToCompound {});
+  }
+
+  let rewards_asset = config.reward_info.with_balance(total_rewards);
+  let mut response = Response::new()
+    .add_attributes(vec![
+      ("action", "asset/claim_and_compound"),
+      ("user", user.as_ref()),
+      ("assets", &assets_str),
+      ("into", &into.to_string()),
+      ("reward_amount", &total_rewards.to_string()),
+    ])
+    // zaps the rewards into the target asset and stakes it for the user
+    .add_message(rewards_asset.transfer_msg(&zapper.0)?)
+    .add_message(zapper.zap(
+      into.into(),
+      vec![config.reward_info],
+      min_received,
+      Some(PostActionCreate::Stake {
+        asset_staking: env.contract.address,
+        receiver: Some(user.to_string()),
+      }),
+    )?);
+
+  if !extra_rewards.is_empty() {
+    let extra_str =
+      extra_rewards.0.iter().map(|asset| asset.to_string()).collect::<Vec<String>>().join(",");
+    response = response
+      .add_attribute("extra_rewards", extra_str)
+      .add_messages(extra_rewards.transfer_msgs(&user)?);
+  }
+  Ok(response)
+}
+
+/// Settles and removes the rewards of the user for the assets (all staked assets if none)
+fn _claim_rewards(
+  mut deps: DepsMut,
+  env: &Env,
+  user: &Addr,
+  assets: Option<Vec<AssetInfo>>,
+) -> Result<(String, Uint128, Assets), ContractError> {
   let assets = if let Some(assets) = assets {
     assets
   } else {
@@ -544,40 +1051,279 @@ fn claim_rewards(
   let assets_str = assets.iter().map(|asset| asset.to_string()).collect::<Vec<String>>().join(",");
 
   let mut total_rewards = Uint128::zero();
+  let mut extra_rewards = Assets::default();
   for asset in assets {
-    let rewards = _calc_reward_share(deps.storage, user.clone(), asset.clone())?;
+    let rewards = _calc_reward_share(deps.storage, env, user.clone(), asset.clone())?;
     let unclaimed_rewards =
       UNCLAIMED_REWARDS.load(deps.storage, (user.clone(), &asset)).unwrap_or(Uint128::zero());
     UNCLAIMED_REWARDS.remove(deps.storage, (user.clone(), &asset));
 
     total_rewards += rewards;
     total_rewards += unclaimed_rewards;
+
+    if let Some(unclaimed_extra) =
+      UNCLAIMED_EXTRA_REWARDS.may_load(deps.storage, (user.clone(), &asset))?
+    {
+      extra_rewards.add_multi(&unclaimed_extra.0);
+      UNCLAIMED_EXTRA_REWARDS.remove(deps.storage, (user.clone(), &asset));
+    }
+
+    _update_working_balance(&mut deps, env, user, &asset)?;
   }
 
-  let response = Response::new().add_attributes(vec![
-    ("action", "asset/claim_rewards"),
-    ("user", user.as_ref()),
-    ("assets", &assets_str),
-    ("reward_amount", &total_rewards.to_string()),
-  ]);
-  if !total_rewards.is_zero() {
-    let rewards_asset = config.reward_info.with_balance(total_rewards);
-    Ok(response.add_message(rewards_asset.transfer_msg(&recipient)?))
+  Ok((assets_str, total_rewards, extra_rewards))
+}
+
+fn transfer_stake(
+  mut deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  asset: AssetInfo,
+  shares: Uint128,
+  recipient: Addr,
+) -> Result<Response, ContractError> {
+  _move_shares(&mut deps, &env, &info.sender, &recipient, &asset, shares)?;
+  assert_max_stake_per_user(deps.storage, &recipient, &asset)?;
+
+  Ok(Response::new().add_attributes(vec![
+    ("action", "asset/transfer_stake"),
+    ("user", info.sender.as_ref()),
+    ("recipient", recipient.as_ref()),
+    ("asset", &asset.to_string()),
+    ("share", &shares.to_string()),
+  ]))
+}
+
+fn wrap_stake(
+  mut deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  asset: AssetInfo,
+  shares: Uint128,
+  recipient: Addr,
+) -> Result<Response, ContractError> {
+  let receipt_denom = ASSET_CONFIG
+    .may_load(deps.storage, &asset)?
+    .and_then(|config| config.receipt_denom)
+    .ok_or_else(|| ContractError::AssetNotTokenized(asset.to_string()))?;
+
+  _move_shares(&mut deps, &env, &info.sender, &env.contract.address, &asset, shares)?;
+
+  let mint_msg: CosmosMsg = MsgMint {
+    sender: env.contract.address.to_string(),
+    amount: Some(Coin {
+      denom: receipt_denom.clone(),
+      amount: shares.to_string(),
+    }),
+    // not working on terra
+    mint_to_address: env.contract.address.to_string(),
+  }
+  .into();
+  let receipt = AssetInfo::native(receipt_denom).with_balance(shares);
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        ("action", "asset/wrap_stake"),
+        ("user", info.sender.as_ref()),
+        ("recipient", recipient.as_ref()),
+        ("asset", &asset.to_string()),
+        ("share", &shares.to_string()),
+      ])
+      .add_message(mint_msg)
+      .add_message(receipt.transfer_msg(&recipient)?),
+  )
+}
+
+fn unwrap_stake(
+  mut deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  recipient: Addr,
+) -> Result<Response, ContractError> {
+  if info.funds.len() != 1 {
+    return Err(ContractError::OnlySingleAssetAllowed {});
+  }
+  let receipt = info.funds[0].clone();
+  if receipt.amount.is_zero() {
+    return Err(ContractError::AmountCannotBeZero {});
+  }
+
+  let asset = RECEIPT_DENOMS
+    .may_load(deps.storage, &receipt.denom)?
+    .ok_or_else(|| ContractError::AssetNotTokenized(receipt.denom.clone()))?;
+
+  _move_shares(&mut deps, &env, &env.contract.address, &recipient, &asset, receipt.amount)?;
+  assert_max_stake_per_user(deps.storage, &recipient, &asset)?;
+
+  let burn_msg: CosmosMsg = MsgBurn {
+    sender: env.contract.address.to_string(),
+    amount: Some(Coin {
+      denom: receipt.denom,
+      amount: receipt.amount.to_string(),
+    }),
+    burn_from_address: env.contract.address.to_string(),
+  }
+  .into();
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        ("action", "asset/unwrap_stake"),
+        ("user", info.sender.as_ref()),
+        ("recipient", recipient.as_ref()),
+        ("asset", &asset.to_string()),
+        ("share", &receipt.amount.to_string()),
+      ])
+      .add_message(burn_msg),
+  )
+}
+
+/// Checks that shares moved to the user don't exceed the max stake per user of the asset.
+fn assert_max_stake_per_user(
+  storage: &dyn Storage,
+  user: &Addr,
+  asset: &AssetInfo,
+) -> Result<(), ContractError> {
+  let asset_config = ASSET_CONFIG.may_load(storage, asset)?.unwrap_or_default();
+  if let Some(max_stake_per_user) = asset_config.max_stake_per_user {
+    let (balance, total_shares) = TOTAL.may_load(storage, asset)?.unwrap_or_default();
+    let asset_available = balance.checked_sub(asset_config.taken)?;
+    let user_shares = SHARES.may_load(storage, (user.clone(), asset))?.unwrap_or_default();
+    let user_balance = compute_balance_amount(total_shares, user_shares, asset_available);
+    if user_balance > max_stake_per_user {
+      return Err(ContractError::MaxStakePerUserExceeded(max_stake_per_user));
+    }
+  }
+  Ok(())
+}
+
+/// Moves shares between two positions after settling the rewards of both.
+fn _move_shares(
+  deps: &mut DepsMut,
+  env: &Env,
+  from: &Addr,
+  to: &Addr,
+  asset: &AssetInfo,
+  shares: Uint128,
+) -> Result<(), ContractError> {
+  if shares.is_zero() {
+    return Err(ContractError::AmountCannotBeZero {});
+  }
+
+  for user in [from, to] {
+    let rewards = _calc_reward_share(deps.storage, env, user.clone(), asset.clone())?;
+    if !rewards.is_zero() {
+      UNCLAIMED_REWARDS.update(
+        deps.storage,
+        (user.clone(), asset),
+        |balance| -> Result<_, ContractError> { Ok(balance.unwrap_or_default() + rewards) },
+      )?;
+    }
+  }
+
+  let from_shares = SHARES.may_load(deps.storage, (from.clone(), asset))?.unwrap_or_default();
+  let remaining = from_shares
+    .checked_sub(shares)
+    .map_err(|_| SharedError::InsufficientBalance("shares".to_string()))?;
+  if remaining.is_zero() {
+    SHARES.remove(deps.storage, (from.clone(), asset));
   } else {
-    Ok(response)
+    SHARES.save(deps.storage, (from.clone(), asset), &remaining)?;
   }
+  SHARES.update(deps.storage, (to.clone(), asset), |share| -> Result<_, ContractError> {
+    Ok(share.unwrap_or_default().checked_add(shares)?)
+  })?;
+
+  _update_working_balance(deps, env, from, asset)?;
+  _update_working_balance(deps, env, to, asset)?;
+  Ok(())
+}
+
+fn add_rewards(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  asset: AssetInfo,
+  rewards: Asset,
+  duration: u64,
+) -> Result<Response, ContractError> {
+  let config = CONFIG.load(deps.storage)?;
+  assert_asset_whitelisted(&deps, &asset)?;
+
+  if rewards.amount.is_zero() {
+    return Err(ContractError::AmountCannotBeZero {});
+  }
+  if rewards.info == asset {
+    return Err(ContractError::AssetInfoCannotEqualReward {});
+  }
+
+  // every reward asset is updated on each stake change, so new ones can't be added by everyone
+  if !ASSET_EXTRA_REWARD_RATE.has(deps.storage, (&asset, &rewards.info)) {
+    assert_asset_whitelist_controller(&deps, &info, &config)?;
+  }
+
+  let total_working = get_total_working_balance(deps.storage, &asset)?;
+  if total_working.is_zero() {
+    return Err(ContractError::NothingStaked {});
+  }
+
+  let mut msgs = vec![];
+  match &rewards.info {
+    AssetInfo::Native(_) => rewards.assert_sent(&info)?,
+    AssetInfo::Cw20(_) => {
+      if !info.funds.is_empty() {
+        Err(SharedError::NoFundsAllowed {})?;
+      }
+      msgs.push(rewards.transfer_from_msg(&info.sender, &env.contract.address)?)
+    },
+    _ => Err(SharedError::NotSupported("asset type".to_string()))?,
+  }
+
+  let rate = _checkpoint_extra_stream(deps.storage, &env, &asset, &rewards.info)?;
+  let amount = Decimal::from_atomics(rewards.amount, 0)?;
+
+  if duration == 0 {
+    let rate_to_update = amount / Decimal::from_atomics(total_working, 0)?;
+    ASSET_EXTRA_REWARD_RATE.save(
+      deps.storage,
+      (&asset, &rewards.info),
+      &(rate + rate_to_update),
+    )?;
+  } else {
+    let key = (&asset, &rewards.info);
+    let mut stream = ASSET_EXTRA_REWARD_STREAM.may_load(deps.storage, key)?.unwrap_or_default();
+    stream.add(env.block.time.seconds(), amount, duration);
+    ASSET_EXTRA_REWARD_STREAM.save(deps.storage, key, &stream)?;
+  }
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        ("action", "asset/add_rewards"),
+        ("asset", &asset.to_string()),
+        ("rewards", &rewards.to_string()),
+        ("duration", &duration.to_string()),
+      ])
+      .add_messages(msgs),
+  )
 }
 
 fn _calc_reward_share(
   storage: &mut dyn Storage,
+  env: &Env,
   user: Addr,
   asset: AssetInfo,
 ) -> Result<Uint128, ContractError> {
+  _materialize_working_balance(storage, &user, &asset)?;
+  _checkpoint_stream(storage, env, &asset)?;
+  _calc_extra_reward_share(storage, env, &user, &asset)?;
+
   let user_reward_rate = USER_ASSET_REWARD_RATE.load(storage, (user.clone(), &asset));
   let asset_reward_rate = ASSET_REWARD_RATE.load(storage, &asset)?;
 
   if let Ok(user_reward_rate) = user_reward_rate {
-    let user_staked = SHARES.may_load(storage, (user.clone(), &asset))?.unwrap_or_default();
+    let user_staked = get_working_balance(storage, &user, &asset)?;
 
     if user_staked.is_zero() {
       USER_ASSET_REWARD_RATE.save(storage, (user, &asset), &asset_reward_rate)?;
@@ -601,6 +1347,193 @@ fn _calc_reward_share(
   }
 }
 
+/// Moves the rewards released by the stream since the last update into the asset reward rate.
+fn _checkpoint_stream(
+  storage: &mut dyn Storage,
+  env: &Env,
+  asset: &AssetInfo,
+) -> Result<(), ContractError> {
+  if let Some(mut stream) = ASSET_REWARD_STREAM.may_load(storage, asset)? {
+    let now = env.block.time.seconds();
+    let total_working = get_total_working_balance(storage, asset)?;
+
+    // same as for instant rewards, without shares the released rewards are not directed to any stakers.
+    let rate_to_update = stream.released_per_share(now, total_working);
+    if !rate_to_update.is_zero() {
+      ASSET_REWARD_RATE.update(storage, asset, |rate| -> StdResult<_> {
+        Ok(rate.unwrap_or(Decimal::zero()) + rate_to_update)
+      })?;
+    }
+
+    stream.last_update_s = now;
+    ASSET_REWARD_STREAM.save(storage, asset, &stream)?;
+  }
+  Ok(())
+}
+
+/// Moves the released additional rewards into their asset reward rate and returns the updated rate.
+fn _checkpoint_extra_stream(
+  storage: &mut dyn Storage,
+  env: &Env,
+  asset: &AssetInfo,
+  reward_info: &AssetInfo,
+) -> Result<Decimal, ContractError> {
+  let key = (asset, reward_info);
+  let mut rate = ASSET_EXTRA_REWARD_RATE.may_load(storage, key)?.unwrap_or_default();
+
+  if let Some(mut stream) = ASSET_EXTRA_REWARD_STREAM.may_load(storage, key)? {
+    let now = env.block.time.seconds();
+    let total_working = get_total_working_balance(storage, asset)?;
+    rate += stream.released_per_share(now, total_working);
+
+    stream.last_update_s = now;
+    ASSET_EXTRA_REWARD_STREAM.save(storage, key, &stream)?;
+  }
+
+  ASSET_EXTRA_REWARD_RATE.save(storage, key, &rate)?;
+  Ok(rate)
+}
+
+/// Moves the additional rewards earned by the user for the asset into UNCLAIMED_EXTRA_REWARDS.
+fn _calc_extra_reward_share(
+  storage: &mut dyn Storage,
+  env: &Env,
+  user: &Addr,
+  asset: &AssetInfo,
+) -> Result<(), ContractError> {
+  let reward_infos = ASSET_EXTRA_REWARD_RATE
+    .prefix(asset)
+    .keys(storage, None, None, Order::Ascending)
+    .collect::<StdResult<Vec<_>>>()?;
+
+  if reward_infos.is_empty() {
+    return Ok(());
+  }
+
+  let user_staked = get_working_balance(storage, user, asset)?;
+  let mut unclaimed =
+    UNCLAIMED_EXTRA_REWARDS.may_load(storage, (user.clone(), asset))?.unwrap_or_default();
+
+  for reward_info in reward_infos {
+    let asset_reward_rate = _checkpoint_extra_stream(storage, env, asset, &reward_info)?;
+    let key = (user.clone(), asset, &reward_info);
+    // users staked before the reward asset was added start at zero
+    let user_reward_rate =
+      USER_ASSET_EXTRA_REWARD_RATE.may_load(storage, key.clone())?.unwrap_or_default();
+
+    let rewards = ((asset_reward_rate - user_reward_rate) * Decimal::from_atomics(user_staked, 0)?)
+      .to_uint_floor();
+    if !rewards.is_zero() {
+      unclaimed.add(&reward_info.with_balance(rewards));
+    }
+
+    USER_ASSET_EXTRA_REWARD_RATE.save(storage, key, &asset_reward_rate)?;
+  }
+
+  if !unclaimed.is_empty() {
+    UNCLAIMED_EXTRA_REWARDS.save(storage, (user.clone(), asset), &unclaimed)?;
+  }
+  Ok(())
+}
+
+/// Stores the working balances of positions from before boosting, so that later share changes
+/// adjust the total by the difference of the user.
+fn _materialize_working_balance(
+  storage: &mut dyn Storage,
+  user: &Addr,
+  asset: &AssetInfo,
+) -> Result<(), ContractError> {
+  if !TOTAL_WORKING_BALANCE.has(storage, asset) {
+    let total_working = get_total_working_balance(storage, asset)?;
+    TOTAL_WORKING_BALANCE.save(storage, asset, &total_working)?;
+  }
+  if !WORKING_BALANCE.has(storage, (user.clone(), asset)) {
+    let working = get_working_balance(storage, user, asset)?;
+    WORKING_BALANCE.save(storage, (user.clone(), asset), &working)?;
+  }
+  Ok(())
+}
+
+/// Recalculates the working balance of the user based on the current ve voting power.
+/// Needs to be called after the rewards of the user have been checkpointed.
+fn _update_working_balance(
+  deps: &mut DepsMut,
+  env: &Env,
+  user: &Addr,
+  asset: &AssetInfo,
+) -> Result<Uint128, ContractError> {
+  let shares = SHARES.may_load(deps.storage, (user.clone(), asset))?.unwrap_or_default();
+  let max_boost = ASSET_CONFIG.may_load(deps.storage, asset)?.unwrap_or_default().max_boost;
+
+  let working = match max_boost {
+    // shares wrapped into the receipt denom do not earn rewards
+    _ if *user == env.contract.address => Uint128::zero(),
+    Some(max_boost) if max_boost > Decimal::one() && !shares.is_zero() => {
+      let config = CONFIG.load(deps.storage)?;
+      let (_, total_shares) = TOTAL.may_load(deps.storage, asset)?.unwrap_or_default();
+      let user_info = config.asset_gauge(&deps.querier)?.query_user_info(
+        &deps.querier,
+        user.to_string(),
+        None,
+      )?;
+      let user_vp = user_info.voting_power + user_info.fixed_amount;
+      let total_vp = config.voting_escrow(&deps.querier)?.query_total_vp(&deps.querier, None)?.vp;
+
+      // without voting power a position earns 1 / max_boost of its shares
+      let base_ratio = Decimal::one() / max_boost;
+      let base = shares.mul_floor(base_ratio);
+      let boosted = if total_vp.is_zero() {
+        Uint128::zero()
+      } else {
+        total_shares.multiply_ratio(user_vp, total_vp).mul_floor(Decimal::one() - base_ratio)
+      };
+      min(base + boosted, shares)
+    },
+    _ => shares,
+  };
+
+  let current = get_working_balance(deps.storage, user, asset)?;
+  let total_working = get_total_working_balance(deps.storage, asset)?;
+  TOTAL_WORKING_BALANCE.save(
+    deps.storage,
+    asset,
+    &total_working.checked_add(working)?.checked_sub(current)?,
+  )?;
+
+  if shares.is_zero() {
+    WORKING_BALANCE.remove(deps.storage, (user.clone(), asset));
+  } else {
+    WORKING_BALANCE.save(deps.storage, (user.clone(), asset), &working)?;
+  }
+
+  Ok(working)
+}
+
+fn kick(mut deps: DepsMut, env: Env, user: Addr) -> Result<Response, ContractError> {
+  let assets = SHARES
+    .prefix(user.clone())
+    .keys(deps.storage, None, None, Order::Ascending)
+    .collect::<StdResult<Vec<_>>>()?;
+
+  let mut attrs = vec![attr("action", "asset/kick"), attr("user", user.as_ref())];
+  for asset in assets {
+    let rewards = _calc_reward_share(deps.storage, &env, user.clone(), asset.clone())?;
+    if !rewards.is_zero() {
+      UNCLAIMED_REWARDS.update(
+        deps.storage,
+        (user.clone(), &asset),
+        |balance| -> Result<_, ContractError> { Ok(balance.unwrap_or_default() + rewards) },
+      )?;
+    }
+
+    let working = _update_working_balance(&mut deps, &env, &user, &asset)?;
+    attrs.push(attr("asset", asset.to_string()));
+    attrs.push(attr("working_balance", working.to_string()));
+  }
+
+  Ok(Response::new().add_attributes(attrs))
+}
+
 fn distribute_take_rate(
   mut deps: DepsMut,
   env: Env,
@@ -632,6 +1565,15 @@ fn distribute_take_rate(
       ASSET_CONFIG.may_load(deps.storage, &asset)?.unwrap_or_default()
     };
 
+    // harvested rewards taken through take_on_rewards are already held by the contract
+    if let Some(reward_take) = ASSET_REWARD_TAKE.may_load(deps.storage, &asset)? {
+      ASSET_REWARD_TAKE.remove(deps.storage, &asset);
+      for take in reward_take.0.iter() {
+        attrs.push(attr("take", take.to_string()));
+      }
+      msgs.extend(reward_take.transfer_msgs(&recipient)?);
+    }
+
     let take_amount = config.taken.checked_sub(config.harvested)?;
     if take_amount.is_zero() {
       response = response.add_attribute("skip", asset.to_string());
@@ -760,6 +1702,24 @@ fn distribute_take_callback(
   )
 }
 
+fn migrate_stake_callback(
+  deps: DepsMut,
+  env: Env,
+  _info: MessageInfo,
+  asset: Asset,
+) -> Result<Response, ContractError> {
+  let asset_config = ASSET_CONFIG.load(deps.storage, &asset.info)?;
+  let stake_msgs =
+    asset_config.stake_config.stake_check_received_msg(&deps, &env, asset.clone())?;
+
+  Ok(
+    Response::new()
+      .add_messages(stake_msgs)
+      .add_attributes(vec![("action", "asset/migrate_stake_callback")])
+      .add_attribute("amount", asset.to_string()),
+  )
+}
+
 fn update_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
   if !info.funds.is_empty() {
     Err(SharedError::NoFundsAllowed {})?;
@@ -792,7 +1752,8 @@ fn update_reward_callback(
   }
 
   let previous_balance = initial_balance.amount;
-  let current_balance = initial_balance.info.query_balance(&deps.querier, env.contract.address)?;
+  let current_balance =
+    initial_balance.info.query_balance(&deps.querier, env.contract.address.clone())?;
 
   let rewards_collected = current_balance - previous_balance;
   let rewards = initial_balance.info.with_balance(rewards_collected);
@@ -803,30 +1764,132 @@ fn update_reward_callback(
     .map(|a| a.distribution)
     .fold(Decimal::zero(), |acc, v| acc + v);
 
+  let now = env.block.time.seconds();
+  let last_update_s = LAST_REWARD_UPDATE_S.may_load(deps.storage)?;
+  LAST_REWARD_UPDATE_S.save(deps.storage, &now)?;
+
+  let mut asset_rewards = vec![];
+  let mut redistribute = Decimal::zero();
+  let mut excess = Uint128::zero();
+  let mut oracle_errors = vec![];
+  let mut msgs = vec![];
+
   for asset_distribution in asset_reward_distribution {
-    let total_reward_distributed = Decimal::from_atomics(rewards_collected, 0)?
+    let mut total_reward_distributed = Decimal::from_atomics(rewards_collected, 0)?
       * asset_distribution.distribution
       / total_distribution;
 
+    // the apr cap can only be applied when the duration covered by the rewards is known.
+    // It is tracked by asset, as assets without working balance are skipped.
+    let elapsed_s = ASSET_LAST_REWARD_UPDATE_S
+      .may_load(deps.storage, &asset_distribution.asset)?
+      .or(last_update_s)
+      .map(|last| now.saturating_sub(last));
+    ASSET_LAST_REWARD_UPDATE_S.save(deps.storage, &asset_distribution.asset, &now)?;
+
     // If there are no shares, we stop updating the rate. This means that the emissions are not directed to any stakers.
-    let (_, total_shares) =
-      TOTAL.may_load(deps.storage, &asset_distribution.asset)?.unwrap_or_default();
-    if !total_shares.is_zero() {
-      let rate_to_update = total_reward_distributed / Decimal::from_atomics(total_shares, 0)?;
+    let total_working = get_total_working_balance(deps.storage, &asset_distribution.asset)?;
+    if total_working.is_zero() || total_reward_distributed.is_zero() {
+      continue;
+    }
+
+    let asset = asset_distribution.asset;
+    let asset_config = ASSET_CONFIG.may_load(deps.storage, &asset)?.unwrap_or_default();
+
+    // a failing oracle leaves the asset uncapped, so that it does not block the emissions of all assets
+    let price = match (&asset_config.apr_cap, elapsed_s) {
+      (Some(apr_cap), Some(_)) => match apr_cap.oracle.query_price(&deps.querier, &asset) {
+        Ok(price) => Some(price),
+        Err(_) => {
+          oracle_errors.push(asset.to_string());
+          None
+        },
+      },
+      _ => None,
+    };
+
+    let capped = match (&asset_config.apr_cap, elapsed_s, price) {
+      (Some(apr_cap), Some(elapsed_s), Some(price)) => {
+        let (balance, _) = TOTAL.may_load(deps.storage, &asset)?.unwrap_or_default();
+        let max_rewards = Decimal::from_atomics(apr_cap.max_rewards(price, balance, elapsed_s), 0)?;
+
+        if total_reward_distributed > max_rewards {
+          let asset_excess = total_reward_distributed - max_rewards;
+          total_reward_distributed = max_rewards;
+
+          match &apr_cap.excess_recipient {
+            Some(recipient) => {
+              let amount = asset_excess.to_uint_floor();
+              excess += amount;
+              msgs.push(rewards.info.with_balance(amount).transfer_msg(recipient)?);
+            },
+            None => redistribute += asset_excess,
+          }
+        }
+        true
+      },
+      _ => false,
+    };
+
+    asset_rewards.push((
+      asset,
+      asset_config.reward_stream_seconds,
+      asset_distribution.distribution,
+      total_reward_distributed,
+      total_working,
+      capped,
+    ));
+  }
+
+  // emissions above the cap without an excess recipient are split between the uncapped assets.
+  // Without any uncapped asset they are not directed to any stakers.
+  let uncapped_distribution = asset_rewards
+    .iter()
+    .filter(|(.., capped)| !capped)
+    .map(|(_, _, distribution, ..)| *distribution)
+    .fold(Decimal::zero(), |acc, v| acc + v);
+
+  for (asset, stream_seconds, distribution, mut total_reward_distributed, total_working, capped) in
+    asset_rewards
+  {
+    if !capped && !redistribute.is_zero() {
+      total_reward_distributed += redistribute * distribution / uncapped_distribution;
+    }
+
+    if total_reward_distributed.is_zero() {
+      continue;
+    }
+
+    if stream_seconds > 0 {
+      // release the new rewards linearly, so that staking right before an update does not pay off.
+      _checkpoint_stream(deps.storage, &env, &asset)?;
+      let mut stream = ASSET_REWARD_STREAM.may_load(deps.storage, &asset)?.unwrap_or_default();
+      stream.add(env.block.time.seconds(), total_reward_distributed, stream_seconds);
+      ASSET_REWARD_STREAM.save(deps.storage, &asset, &stream)?;
+    } else {
+      let rate_to_update = total_reward_distributed / Decimal::from_atomics(total_working, 0)?;
       if rate_to_update > Decimal::zero() {
-        ASSET_REWARD_RATE.update(
-          deps.storage,
-          &asset_distribution.asset,
-          |rate| -> StdResult<_> { Ok(rate.unwrap_or(Decimal::zero()) + rate_to_update) },
-        )?;
+        ASSET_REWARD_RATE.update(deps.storage, &asset, |rate| -> StdResult<_> {
+          Ok(rate.unwrap_or(Decimal::zero()) + rate_to_update)
+        })?;
       }
     }
   }
 
-  Ok(Response::new().add_attributes(vec![
+  let mut response = Response::new().add_messages(msgs).add_attributes(vec![
     ("action", "asset/update_rewards_callback"),
     ("rewards", &rewards.to_string()),
-  ]))
+  ]);
+
+  if !excess.is_zero() {
+    response = response.add_attribute("excess", rewards.info.with_balance(excess).to_string());
+  }
+
+  if !oracle_errors.is_empty() {
+    response = response.add_attribute("apr_cap_oracle_error", oracle_errors.join(","));
+  }
+
+  Ok(response)
 }
 
 fn track_bribes_callback(
@@ -837,6 +1900,9 @@ fn track_bribes_callback(
   initial_balances: Vec<Asset>,
 ) -> Result<Response, ContractError> {
   let mut bribes = ASSET_BRIBES.may_load(deps.storage, &asset)?.unwrap_or_default();
+  let take_on_rewards =
+    ASSET_CONFIG.may_load(deps.storage, &asset)?.unwrap_or_default().take_on_rewards;
+  let mut reward_take = ASSET_REWARD_TAKE.may_load(deps.storage, &asset)?.unwrap_or_default();
 
   // this just adds the newly received staking / claiming rewards to the accounting for the corresponding LP.
 
@@ -847,7 +1913,16 @@ fn track_bribes_callback(
     let new_balance = bribe_info.query_balance(&deps.querier, env.contract.address.clone())?;
 
     if new_balance > old_balance.amount {
-      let added_amount = new_balance - old_balance.amount;
+      let mut added_amount = new_balance - old_balance.amount;
+      if let Some(take_rate) = take_on_rewards {
+        let take = bribe_info.with_balance(added_amount.mul_floor(take_rate));
+        if !take.amount.is_zero() {
+          added_amount -= take.amount;
+          reward_take.add(&take);
+          attrs.push(attr("reward_take", take.to_string()));
+        }
+      }
+
       let added = bribe_info.with_balance(added_amount);
       bribes.add(&added);
       attrs.push(attr("asset", asset.to_string()));
@@ -856,6 +1931,9 @@ fn track_bribes_callback(
   }
 
   ASSET_BRIBES.save(deps.storage, &asset, &bribes)?;
+  if !reward_take.is_empty() {
+    ASSET_REWARD_TAKE.save(deps.storage, &asset, &reward_take)?;
+  }
 
   Ok(
     Response::new()
@@ -868,6 +1946,33 @@ fn assert_asset_whitelisted(deps: &DepsMut, asset: &AssetInfo) -> Result<bool, C
   WHITELIST.load(deps.storage, asset).map_err(|_| ContractError::AssetNotWhitelisted)
 }
 
+fn assert_asset_paused(deps: &DepsMut, asset: &AssetInfo) -> Result<(), ContractError> {
+  if !PAUSED.has(deps.storage, asset) {
+    return Err(ContractError::AssetNotPaused(asset.to_string()));
+  }
+  Ok(())
+}
+
+fn assert_asset_not_paused(deps: &DepsMut, asset: &AssetInfo) -> Result<(), ContractError> {
+  if PAUSED.has(deps.storage, asset) {
+    return Err(ContractError::AssetPaused(asset.to_string()));
+  }
+  Ok(())
+}
+
+fn assert_guardian(
+  deps: &DepsMut,
+  info: &MessageInfo,
+  config: &Config,
+) -> Result<(), ContractError> {
+  config.global_config().assert_owner_or_address_type(
+    &deps.querier,
+    AT_VE_GUARDIAN,
+    &info.sender,
+  )?;
+  Ok(())
+}
+
 // Only governance (through a on-chain prop) can change the whitelisted assets
 fn assert_asset_whitelist_controller(
   deps: &DepsMut,
@@ -892,6 +1997,38 @@ fn assert_distribution_controller(
   Ok(())
 }
 
+/// White Whale claims the rewards of all positions at once, which can't be attributed to a single asset.
+fn assert_white_whale_not_shared(
+  deps: &DepsMut,
+  asset: &AssetInfo,
+  stake_config: &StakeConfig<Addr>,
+) -> Result<(), ContractError> {
+  if let StakeConfig::WhiteWhale {
+    contract,
+    ..
+  } = stake_config
+  {
+    for other in WHITELIST.keys(deps.storage, None, None, Order::Ascending) {
+      let other = other?;
+      if other == *asset {
+        continue;
+      }
+
+      let other_config = ASSET_CONFIG.may_load(deps.storage, &other)?.unwrap_or_default();
+      if let StakeConfig::WhiteWhale {
+        contract: other_contract,
+        ..
+      } = other_config.stake_config
+      {
+        if other_contract == *contract {
+          return Err(ContractError::WhiteWhaleContractShared(contract.to_string()));
+        }
+      }
+    }
+  }
+  Ok(())
+}
+
 fn assert_reward_not_stake_denom(update: &AssetInfoWithConfig<Addr>) -> Result<(), ContractError> {
   match &update.config {
     Some(config) => match &config.stake_config {
@@ -909,6 +2046,14 @@ fn assert_reward_not_stake_denom(update: &AssetInfoWithConfig<Addr>) -> Result<(
       StakeConfig::Ve3 {
         reward_infos,
         ..
+      }
+      | StakeConfig::WhiteWhale {
+        reward_infos,
+        ..
+      }
+      | StakeConfig::Custom {
+        reward_infos,
+        ..
       } => {
         if reward_infos.contains(&update.info) {
           Err(ContractError::AssetInfoCannotEqualStakingReward {})
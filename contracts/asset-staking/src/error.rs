@@ -1,4 +1,4 @@
-use cosmwasm_std::{DecimalRangeExceeded, OverflowError, StdError};
+use cosmwasm_std::{DecimalRangeExceeded, OverflowError, StdError, Uint128};
 use cw_asset::AssetError;
 use thiserror::Error;
 use ve3_shared::error::SharedError;
@@ -43,4 +43,40 @@ pub enum ContractError {
 
   #[error("Yearly take rate needs to be less or equal 50%")]
   TakeRateLessOrEqual50,
+
+  #[error("Nothing staked for the asset")]
+  NothingStaked {},
+
+  #[error("Too many pending unbonds, max {0}")]
+  TooManyPendingUnbonds(usize),
+
+  #[error("No unbonded assets to withdraw")]
+  NothingUnbonded {},
+
+  #[error("No rewards to compound")]
+  NothingToCompound {},
+
+  #[error("Max boost needs to be at least 1")]
+  InvalidMaxBoost,
+
+  #[error("Receipt denom of an asset can't be removed")]
+  ReceiptDenomCannotBeRemoved,
+
+  #[error("Asset is not tokenized: {0}")]
+  AssetNotTokenized(String),
+
+  #[error("Max total stake of the asset exceeded: {0}")]
+  MaxTotalStakeExceeded(Uint128),
+
+  #[error("Max stake per user of the asset exceeded: {0}")]
+  MaxStakePerUserExceeded(Uint128),
+
+  #[error("Asset is paused: {0}")]
+  AssetPaused(String),
+
+  #[error("Asset is not paused: {0}")]
+  AssetNotPaused(String),
+
+  #[error("White Whale incentive contract is already used by another asset: {0}")]
+  WhiteWhaleContractShared(String),
 }
@@ -1,7 +1,7 @@
 use crate::{
   constants::{CONTRACT_NAME, CONTRACT_VERSION},
   error::ContractError,
-  state::ASSET_BRIBES,
+  state::{ASSET_BRIBES, LAST_REWARD_UPDATE_S},
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
@@ -24,6 +24,11 @@ pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, Co
     );
   }
 
+  // without a known start the first reward update after the upgrade would not be capped
+  if LAST_REWARD_UPDATE_S.may_load(deps.storage)?.is_none() {
+    LAST_REWARD_UPDATE_S.save(deps.storage, &env.block.time.seconds())?;
+  }
+
   if CONTRACT_VERSION == "1.0.2" {
     let astro =
       AssetInfo::native("ibc/8D8A7F7253615E5F76CB6252A1E1BD921D5EDB7BBAAF8913FB1C77FF125D9995")
@@ -1,24 +1,27 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdResult, Uint128};
+use cosmwasm_std::{to_json_binary, Addr, Binary, Decimal, Deps, Env, Order, StdResult, Uint128};
+use cw_asset::{Asset, AssetInfo};
 use cw_storage_plus::Bound;
-use std::cmp::min;
 use std::collections::HashMap;
 use ve3_shared::{
-  constants::{DEFAULT_LIMIT, MAX_LIMIT_HIGH, SECONDS_PER_YEAR},
+  constants::{DEFAULT_LIMIT, MAX_LIMIT_HIGH},
   extensions::asset_info_ext::AssetInfoExt,
   helpers::take::compute_balance_amount,
   msgs_asset_staking::{
     AllPendingRewardsQuery, AllStakedBalancesQuery, AllStakersQuery, AssetInfoWithRuntime,
-    AssetQuery, PendingRewardsDetailRes, PendingRewardsRes, PoolStakersQuery, QueryMsg,
-    StakedBalanceRes, UserStakedAssetRes, UserStakedBalanceRes, WhitelistedAssetsDetailsResponse,
-    WhitelistedAssetsResponse,
+    AssetQuery, PausedAssetsResponse, PendingRewardsDetailRes, PendingRewardsRes,
+    PendingUnbondsResponse, PoolStakersQuery, QueryMsg, SimulateTakeResponse, StakedBalanceRes,
+    UserStakedAssetRes, UserStakedBalanceRes, WhitelistedAssetsDetailsResponse,
+    WhitelistedAssetsResponse, WorkingBalanceRes,
   },
 };
 
 use crate::state::{
-  ASSET_CONFIG, ASSET_REWARD_DISTRIBUTION, ASSET_REWARD_RATE, CONFIG, SHARES, TOTAL,
-  UNCLAIMED_REWARDS, USER_ASSET_REWARD_RATE, WHITELIST,
+  get_total_working_balance, get_working_balance, ASSET_CONFIG, ASSET_EXTRA_REWARD_RATE,
+  ASSET_EXTRA_REWARD_STREAM, ASSET_REWARD_DISTRIBUTION, ASSET_REWARD_RATE, ASSET_REWARD_STREAM,
+  ASSET_REWARD_TAKE, CONFIG, PAUSED, PENDING_UNBONDS, SHARES, TOTAL, UNCLAIMED_EXTRA_REWARDS,
+  UNCLAIMED_REWARDS, USER_ASSET_EXTRA_REWARD_RATE, USER_ASSET_REWARD_RATE, WHITELIST,
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -27,15 +30,24 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     QueryMsg::Config {} => get_config(deps)?,
     QueryMsg::WhitelistedAssets {} => get_whitelisted_assets(deps)?,
     QueryMsg::WhitelistedAssetDetails {} => get_whitelisted_assets_details(deps)?,
+    QueryMsg::PausedAssets {} => get_paused_assets(deps)?,
     QueryMsg::RewardDistribution {} => get_rewards_distribution(deps)?,
     QueryMsg::StakedBalance(asset_query) => get_staked_balance(deps, env, asset_query)?,
-    QueryMsg::PendingRewards(asset_query) => get_pending_rewards(deps, asset_query)?,
+    QueryMsg::PendingRewards(asset_query) => get_pending_rewards(deps, env, asset_query)?,
     QueryMsg::AllStakedBalances(query) => get_all_staked_balances(deps, env, query)?,
-    QueryMsg::AllPendingRewards(query) => get_all_pending_rewards(deps, query)?,
+    QueryMsg::AllPendingRewards(query) => get_all_pending_rewards(deps, env, query)?,
     QueryMsg::AllPendingRewardsDetail(query) => get_all_pending_rewards_detail(deps, env, query)?,
     QueryMsg::TotalStakedBalances {} => get_total_staked_balances(deps, env)?,
     QueryMsg::PoolStakers(query) => get_pool_stakers(deps, env, query)?,
     QueryMsg::AllStakers(query) => get_all_stakers(deps, env, query)?,
+    QueryMsg::PendingUnbonds {
+      address,
+    } => get_pending_unbonds(deps, address)?,
+    QueryMsg::WorkingBalance(asset_query) => get_working_balance_res(deps, asset_query)?,
+    QueryMsg::SimulateTake {
+      asset,
+      seconds,
+    } => get_simulate_take(deps, asset, seconds)?,
   })
 }
 
@@ -53,6 +65,14 @@ fn get_whitelisted_assets(deps: Deps) -> StdResult<Binary> {
   to_json_binary(&res)
 }
 
+fn get_paused_assets(deps: Deps) -> StdResult<Binary> {
+  let paused =
+    PAUSED.keys(deps.storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
+
+  let res: PausedAssetsResponse = paused;
+  to_json_binary(&res)
+}
+
 fn get_whitelisted_assets_details(deps: Deps) -> StdResult<Binary> {
   let whitelist =
     WHITELIST.keys(deps.storage, None, None, Order::Ascending).collect::<StdResult<Vec<_>>>()?;
@@ -73,6 +93,37 @@ fn get_whitelisted_assets_details(deps: Deps) -> StdResult<Binary> {
   to_json_binary(&res)
 }
 
+fn get_pending_unbonds(deps: Deps, address: String) -> StdResult<Binary> {
+  let addr = deps.api.addr_validate(&address)?;
+  let res: PendingUnbondsResponse =
+    PENDING_UNBONDS.may_load(deps.storage, &addr)?.unwrap_or_default();
+
+  to_json_binary(&res)
+}
+
+fn get_working_balance_res(deps: Deps, asset_query: AssetQuery) -> StdResult<Binary> {
+  let addr = deps.api.addr_validate(&asset_query.address)?;
+  let asset = asset_query.asset;
+
+  to_json_binary(&WorkingBalanceRes {
+    shares: SHARES.may_load(deps.storage, (addr.clone(), &asset))?.unwrap_or_default(),
+    working_balance: get_working_balance(deps.storage, &addr, &asset)?,
+    total_working_balance: get_total_working_balance(deps.storage, &asset)?,
+  })
+}
+
+fn get_simulate_take(deps: Deps, asset: AssetInfo, seconds: u64) -> StdResult<Binary> {
+  let config = ASSET_CONFIG.may_load(deps.storage, &asset)?.unwrap_or_default();
+  let (balance, _) = TOTAL.may_load(deps.storage, &asset)?.unwrap_or_default();
+  let reward_take = ASSET_REWARD_TAKE.may_load(deps.storage, &asset)?.unwrap_or_default();
+
+  to_json_binary(&SimulateTakeResponse {
+    yearly_take_rate: config.take_rate(balance.saturating_sub(config.taken)),
+    take: asset.with_balance(config.take_amount(balance, seconds)),
+    reward_take: reward_take.0,
+  })
+}
+
 fn get_rewards_distribution(deps: Deps) -> StdResult<Binary> {
   let asset_rewards_distr = ASSET_REWARD_DISTRIBUTION.load(deps.storage)?;
 
@@ -87,11 +138,7 @@ fn get_staked_balance(deps: Deps, env: Env, asset_query: AssetQuery) -> StdResul
   let mut config = ASSET_CONFIG.may_load(deps.storage, &asset_query.asset)?.unwrap_or_default();
 
   if config.last_taken_s != 0 {
-    let take_diff_s = Uint128::new((env.block.time.seconds() - config.last_taken_s).into());
-    let relevant_balance = balance.saturating_sub(config.taken);
-    let take_amount = config.yearly_take_rate
-      * relevant_balance
-        .multiply_ratio(min(take_diff_s, Uint128::new(SECONDS_PER_YEAR.into())), SECONDS_PER_YEAR);
+    let take_amount = config.take_amount(balance, env.block.time.seconds() - config.last_taken_s);
     config.last_taken_s = env.block.time.seconds();
     config.taken = config.taken.checked_add(take_amount)?
   };
@@ -124,13 +171,8 @@ fn get_all_staked_balances(
     let (balance, shares) = TOTAL.load(deps.storage, &asset_info).unwrap_or_default();
 
     if asset_config.last_taken_s != 0 {
-      let take_diff_s = Uint128::new((env.block.time.seconds() - asset_config.last_taken_s).into());
-      let relevant_balance = balance.saturating_sub(asset_config.taken);
-      let take_amount = asset_config.yearly_take_rate
-        * relevant_balance.multiply_ratio(
-          min(take_diff_s, Uint128::new(SECONDS_PER_YEAR.into())),
-          SECONDS_PER_YEAR,
-        );
+      let take_amount =
+        asset_config.take_amount(balance, env.block.time.seconds() - asset_config.last_taken_s);
       asset_config.last_taken_s = env.block.time.seconds();
       asset_config.taken = asset_config.taken.checked_add(take_amount)?
     };
@@ -217,24 +259,78 @@ pub fn get_all_stakers(deps: Deps, _env: Env, query: AllStakersQuery) -> StdResu
   to_json_binary(&result)
 }
 
-fn get_pending_rewards(deps: Deps, asset_query: AssetQuery) -> StdResult<Binary> {
+// asset reward rate including the rewards already released by the stream
+fn get_asset_reward_rate(deps: Deps, env: &Env, asset: &AssetInfo) -> StdResult<Decimal> {
+  let rate = ASSET_REWARD_RATE.load(deps.storage, asset)?;
+
+  if let Some(stream) = ASSET_REWARD_STREAM.may_load(deps.storage, asset)? {
+    let total_working = get_total_working_balance(deps.storage, asset)?;
+    return Ok(rate + stream.released_per_share(env.block.time.seconds(), total_working));
+  }
+
+  Ok(rate)
+}
+
+// unclaimed and pending additional rewards of the user for the asset
+fn get_pending_extra_rewards(
+  deps: Deps,
+  env: &Env,
+  addr: &Addr,
+  asset: &AssetInfo,
+) -> StdResult<Vec<Asset>> {
+  let mut rewards =
+    UNCLAIMED_EXTRA_REWARDS.may_load(deps.storage, (addr.clone(), asset))?.unwrap_or_default();
+  let user_working = get_working_balance(deps.storage, addr, asset)?;
+  let total_working = get_total_working_balance(deps.storage, asset)?;
+
+  let reward_rates = ASSET_EXTRA_REWARD_RATE
+    .prefix(asset)
+    .range(deps.storage, None, None, Order::Ascending)
+    .collect::<StdResult<Vec<_>>>()?;
+
+  for (reward_info, mut asset_reward_rate) in reward_rates {
+    let key = (asset, &reward_info);
+    if let Some(stream) = ASSET_EXTRA_REWARD_STREAM.may_load(deps.storage, key)? {
+      asset_reward_rate += stream.released_per_share(env.block.time.seconds(), total_working);
+    }
+
+    let user_reward_rate = USER_ASSET_EXTRA_REWARD_RATE
+      .may_load(deps.storage, (addr.clone(), asset, &reward_info))?
+      .unwrap_or_default();
+    let pending = (asset_reward_rate - user_reward_rate) * user_working;
+    if !pending.is_zero() {
+      rewards.add(&reward_info.with_balance(pending));
+    }
+  }
+
+  Ok(rewards.0)
+}
+
+fn get_pending_rewards(deps: Deps, env: Env, asset_query: AssetQuery) -> StdResult<Binary> {
   let config = CONFIG.load(deps.storage)?;
   let asset_info = asset_query.asset;
   let addr = deps.api.addr_validate(&asset_query.address)?;
-  let key = (addr, &asset_info);
+  let key = (addr.clone(), &asset_info);
   let user_reward_rate = USER_ASSET_REWARD_RATE.load(deps.storage, key.clone())?;
-  let asset_reward_rate = ASSET_REWARD_RATE.load(deps.storage, &asset_info)?;
+  let asset_reward_rate = get_asset_reward_rate(deps, &env, &asset_info)?;
   let user_share = SHARES.load(deps.storage, key.clone())?;
   let unclaimed_rewards = UNCLAIMED_REWARDS.load(deps.storage, key).unwrap_or_default();
-  let pending_rewards = (asset_reward_rate - user_reward_rate) * user_share;
+  let user_working = get_working_balance(deps.storage, &addr, &asset_info)?;
+  let pending_rewards = (asset_reward_rate - user_reward_rate) * user_working;
+  let extra_rewards = get_pending_extra_rewards(deps, &env, &addr, &asset_info)?;
 
   to_json_binary(&PendingRewardsRes {
     staked_asset_share: asset_info.with_balance(user_share),
     reward_asset: config.reward_info.with_balance(unclaimed_rewards + pending_rewards),
+    extra_rewards,
   })
 }
 
-fn get_all_pending_rewards(deps: Deps, query: AllPendingRewardsQuery) -> StdResult<Binary> {
+fn get_all_pending_rewards(
+  deps: Deps,
+  env: Env,
+  query: AllPendingRewardsQuery,
+) -> StdResult<Binary> {
   let config = CONFIG.load(deps.storage)?;
   let addr = deps.api.addr_validate(&query.address)?;
   let all_pending_rewards: StdResult<Vec<PendingRewardsRes>> = USER_ASSET_REWARD_RATE
@@ -242,18 +338,21 @@ fn get_all_pending_rewards(deps: Deps, query: AllPendingRewardsQuery) -> StdResu
     .range(deps.storage, None, None, Order::Ascending)
     .map(|item| {
       let (asset, user_reward_rate) = item?;
-      let asset_reward_rate = ASSET_REWARD_RATE.load(deps.storage, &asset)?;
+      let asset_reward_rate = get_asset_reward_rate(deps, &env, &asset)?;
       let user_share = SHARES.load(deps.storage, (addr.clone(), &asset)).unwrap_or_default();
       let unclaimed_rewards =
         UNCLAIMED_REWARDS.load(deps.storage, (addr.clone(), &asset)).unwrap_or_default();
-      let pending_rewards = (asset_reward_rate - user_reward_rate) * user_share;
+      let user_working = get_working_balance(deps.storage, &addr, &asset)?;
+      let pending_rewards = (asset_reward_rate - user_reward_rate) * user_working;
+      let extra_rewards = get_pending_extra_rewards(deps, &env, &addr, &asset)?;
       Ok(PendingRewardsRes {
         staked_asset_share: asset.with_balance(user_share),
         reward_asset: config.reward_info.with_balance(pending_rewards + unclaimed_rewards),
+        extra_rewards,
       })
     })
     .filter(|a| match a {
-      Ok(o) => !o.reward_asset.amount.is_zero(),
+      Ok(o) => !o.reward_asset.amount.is_zero() || !o.extra_rewards.is_empty(),
       Err(_) => true,
     })
     .collect::<StdResult<Vec<PendingRewardsRes>>>();
@@ -273,24 +372,20 @@ fn get_all_pending_rewards_detail(
     .range(deps.storage, None, None, Order::Ascending)
     .map(|item| {
       let (asset_info, user_reward_rate) = item?;
-      let asset_reward_rate = ASSET_REWARD_RATE.load(deps.storage, &asset_info)?;
+      let asset_reward_rate = get_asset_reward_rate(deps, &env, &asset_info)?;
       let user_shares = SHARES.load(deps.storage, (addr.clone(), &asset_info)).unwrap_or_default();
       let unclaimed_rewards =
         UNCLAIMED_REWARDS.load(deps.storage, (addr.clone(), &asset_info)).unwrap_or_default();
-      let pending_rewards = (asset_reward_rate - user_reward_rate) * user_shares;
+      let user_working = get_working_balance(deps.storage, &addr, &asset_info)?;
+      let pending_rewards = (asset_reward_rate - user_reward_rate) * user_working;
+      let extra_rewards = get_pending_extra_rewards(deps, &env, &addr, &asset_info)?;
 
       let (balance, shares) = TOTAL.load(deps.storage, &asset_info).unwrap_or_default();
       let mut asset_config = ASSET_CONFIG.load(deps.storage, &asset_info)?;
 
       if asset_config.last_taken_s != 0 {
-        let take_diff_s =
-          Uint128::new((env.block.time.seconds() - asset_config.last_taken_s).into());
-        let relevant_balance = balance.saturating_sub(asset_config.taken);
-        let take_amount = asset_config.yearly_take_rate
-          * relevant_balance.multiply_ratio(
-            min(take_diff_s, Uint128::new(SECONDS_PER_YEAR.into())),
-            SECONDS_PER_YEAR,
-          );
+        let take_amount =
+          asset_config.take_amount(balance, env.block.time.seconds() - asset_config.last_taken_s);
         asset_config.last_taken_s = env.block.time.seconds();
         asset_config.taken = asset_config.taken.checked_add(take_amount)?
       };
@@ -302,6 +397,7 @@ fn get_all_pending_rewards_detail(
         share: user_shares,
         staked_asset: asset_info.with_balance(real_balance),
         reward_asset: config.reward_info.with_balance(pending_rewards + unclaimed_rewards),
+        extra_rewards,
       })
     })
     .filter(|a| match a {
@@ -322,13 +418,8 @@ fn get_total_staked_balances(deps: Deps, env: Env) -> StdResult<Binary> {
       let mut config = ASSET_CONFIG.load(deps.storage, &asset)?;
 
       if config.last_taken_s != 0 {
-        let take_diff_s = Uint128::new((env.block.time.seconds() - config.last_taken_s).into());
-        let relevant_balance = balance.saturating_sub(config.taken);
-        let take_amount = config.yearly_take_rate
-          * relevant_balance.multiply_ratio(
-            min(take_diff_s, Uint128::new(SECONDS_PER_YEAR.into())),
-            SECONDS_PER_YEAR,
-          );
+        let take_amount =
+          config.take_amount(balance, env.block.time.seconds() - config.last_taken_s);
         config.last_taken_s = env.block.time.seconds();
         config.taken = config.taken.checked_add(take_amount)?
       };
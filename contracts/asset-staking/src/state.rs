@@ -1,13 +1,17 @@
-use cosmwasm_std::{Addr, Decimal, Uint128};
+use cosmwasm_std::{Addr, Decimal, StdResult, Storage, Uint128};
 use cw_asset::AssetInfo;
 use cw_storage_plus::{Item, Map};
 use ve3_shared::{
   helpers::assets::Assets,
-  msgs_asset_staking::{AssetConfigRuntime, AssetDistribution, Config},
+  msgs_asset_staking::{
+    AssetConfigRuntime, AssetDistribution, Config, PendingUnbond, RewardStream,
+  },
 };
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const WHITELIST: Map<&AssetInfo, bool> = Map::new("whitelist");
+// assets paused by the guardian, only emergency unstaking is possible
+pub const PAUSED: Map<&AssetInfo, bool> = Map::new("paused");
 
 pub const SHARES: Map<(Addr, &AssetInfo), Uint128> = Map::new("shares");
 // balance -> share
@@ -16,10 +20,59 @@ pub const TOTAL: Map<&AssetInfo, (Uint128, Uint128)> = Map::new("total");
 pub const ASSET_REWARD_DISTRIBUTION: Item<Vec<AssetDistribution>> =
   Item::new("asset_reward_distribution");
 pub const ASSET_REWARD_RATE: Map<&AssetInfo, Decimal> = Map::new("asset_reward_rate");
+// harvested rewards not yet released into ASSET_REWARD_RATE
+pub const ASSET_REWARD_STREAM: Map<&AssetInfo, RewardStream> = Map::new("asset_reward_stream");
+// time of the last reward update, fallback for assets without an ASSET_LAST_REWARD_UPDATE_S
+pub const LAST_REWARD_UPDATE_S: Item<u64> = Item::new("last_reward_update_s");
+// time of the last reward update by asset, used to compute the emissions allowed by the apr cap
+pub const ASSET_LAST_REWARD_UPDATE_S: Map<&AssetInfo, u64> = Map::new("asset_last_reward_update_s");
 
 pub const ASSET_CONFIG: Map<&AssetInfo, AssetConfigRuntime> = Map::new("asset_config");
 pub const ASSET_BRIBES: Map<&AssetInfo, Assets> = Map::new("asset_bribes");
+// harvested rewards taken through take_on_rewards, sent out with the next take distribution
+pub const ASSET_REWARD_TAKE: Map<&AssetInfo, Assets> = Map::new("asset_reward_take");
 
 pub const USER_ASSET_REWARD_RATE: Map<(Addr, &AssetInfo), Decimal> =
   Map::new("user_asset_reward_rate");
 pub const UNCLAIMED_REWARDS: Map<(Addr, &AssetInfo), Uint128> = Map::new("unclaimed_rewards");
+
+// receipt denom -> asset, stake wrapped into the denom is held by the contract itself
+pub const RECEIPT_DENOMS: Map<&str, AssetInfo> = Map::new("receipt_denoms");
+pub const RECEIPT_DENOM_INDEX: Item<u64> = Item::new("receipt_denom_index");
+
+// boosted share used for the reward distribution, falls back to SHARES / TOTAL until first updated
+pub const WORKING_BALANCE: Map<(Addr, &AssetInfo), Uint128> = Map::new("working_balance");
+pub const TOTAL_WORKING_BALANCE: Map<&AssetInfo, Uint128> = Map::new("total_working_balance");
+
+// unstaked assets waiting for the unbonding period of the asset, by the user that unstaked
+pub const PENDING_UNBONDS: Map<&Addr, Vec<PendingUnbond>> = Map::new("pending_unbonds");
+// total of the PENDING_UNBONDS by asset, held by the contract until withdrawn
+pub const ASSET_UNBONDING: Map<&AssetInfo, Uint128> = Map::new("asset_unbonding");
+
+// additional rewards added through AddRewards, keyed by (staked asset, reward asset)
+pub const ASSET_EXTRA_REWARD_RATE: Map<(&AssetInfo, &AssetInfo), Decimal> =
+  Map::new("asset_extra_reward_rate");
+pub const ASSET_EXTRA_REWARD_STREAM: Map<(&AssetInfo, &AssetInfo), RewardStream> =
+  Map::new("asset_extra_reward_stream");
+pub const USER_ASSET_EXTRA_REWARD_RATE: Map<(Addr, &AssetInfo, &AssetInfo), Decimal> =
+  Map::new("user_asset_extra_reward_rate");
+pub const UNCLAIMED_EXTRA_REWARDS: Map<(Addr, &AssetInfo), Assets> =
+  Map::new("unclaimed_extra_rewards");
+
+pub fn get_working_balance(
+  storage: &dyn Storage,
+  user: &Addr,
+  asset: &AssetInfo,
+) -> StdResult<Uint128> {
+  match WORKING_BALANCE.may_load(storage, (user.clone(), asset))? {
+    Some(working) => Ok(working),
+    None => Ok(SHARES.may_load(storage, (user.clone(), asset))?.unwrap_or_default()),
+  }
+}
+
+pub fn get_total_working_balance(storage: &dyn Storage, asset: &AssetInfo) -> StdResult<Uint128> {
+  match TOTAL_WORKING_BALANCE.may_load(storage, asset)? {
+    Some(working) => Ok(working),
+    None => Ok(TOTAL.may_load(storage, asset)?.unwrap_or_default().1),
+  }
+}
@@ -1,6 +1,9 @@
 use crate::contract::execute;
 use crate::error::ContractError;
-use crate::state::{ASSET_REWARD_DISTRIBUTION, ASSET_REWARD_RATE, TOTAL, USER_ASSET_REWARD_RATE};
+use crate::query::query;
+use crate::state::{
+  ASSET_REWARD_DISTRIBUTION, ASSET_REWARD_RATE, ASSET_REWARD_STREAM, TOTAL, USER_ASSET_REWARD_RATE,
+};
 use crate::tests::helpers::{
   asset_distribution_1, asset_distribution_2, asset_distribution_broken_1,
   asset_distribution_broken_2, claim_rewards, mock_dependencies, query_all_rewards,
@@ -8,11 +11,13 @@ use crate::tests::helpers::{
 };
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-  coin, coins, to_json_binary, Addr, BankMsg, CosmosMsg, Decimal, Response, Uint128, WasmMsg,
+  coin, coins, from_json, to_json_binary, Addr, BankMsg, CosmosMsg, Decimal, Deps, Response,
+  Uint128, WasmMsg,
 };
-use cw_asset::{Asset, AssetInfo};
+use cw_asset::{Asset, AssetInfo, AssetInfoUnchecked};
 use ve3_shared::error::SharedError;
 use ve3_shared::extensions::asset_info_ext::AssetInfoExt;
+use ve3_shared::stake_config::StakeConfig;
 use ve3_shared::{msgs_asset_staking::*, msgs_connector_alliance};
 
 #[test]
@@ -196,6 +201,7 @@ fn claim_user_rewards() {
     rewards,
     PendingRewardsRes {
       reward_asset: Asset::native("uluna".to_string(), 100000u128),
+      extra_rewards: vec![],
       staked_asset_share: Asset::native("aWHALE".to_string(), 1000000u128),
     }
   );
@@ -205,6 +211,7 @@ fn claim_user_rewards() {
     all_rewards,
     vec![PendingRewardsRes {
       reward_asset: Asset::native("uluna".to_string(), 100000u128),
+      extra_rewards: vec![],
       staked_asset_share: Asset::native("aWHALE".to_string(), 1000000u128),
     }]
   );
@@ -241,6 +248,7 @@ fn claim_user_rewards() {
     rewards,
     PendingRewardsRes {
       reward_asset: Asset::native("uluna".to_string(), 0u128),
+      extra_rewards: vec![],
       staked_asset_share: Asset::native("aWHALE".to_string(), 1000000u128),
     }
   );
@@ -595,3 +603,137 @@ fn test_set_asset_reward_distribution() {
 
   assert_eq!(err, ContractError::InvalidDistribution {});
 }
+
+#[test]
+fn stream_user_rewards() {
+  let mut deps = mock_dependencies();
+  deps.querier.bank_querier.update_balance(MOCK_CONTRACT_ADDR, vec![coin(2000000, "uluna")]);
+  setup_contract(deps.as_mut());
+  whitelist_assets(
+    deps.as_mut(),
+    vec![AssetInfoWithConfig::new(
+      AssetInfoUnchecked::native("aWHALE"),
+      Some(AssetConfig {
+        yearly_take_rate: Some(Decimal::zero()),
+        stake_config: StakeConfig::Default,
+        reward_stream_seconds: Some(100),
+        unbonding_seconds: None,
+        max_boost: None,
+        tokenized: None,
+        take_tiers: None,
+        take_on_rewards: None,
+        max_total_stake: None,
+        max_stake_per_user: None,
+        apr_cap: None,
+        clear: None,
+      }),
+    )],
+  );
+  stake(deps.as_mut(), "user1", 1000000, "aWHALE");
+
+  ASSET_REWARD_DISTRIBUTION
+    .save(
+      deps.as_mut().storage,
+      &vec![AssetDistribution {
+        asset: AssetInfo::Native("aWHALE".to_string()),
+        distribution: Decimal::percent(100),
+        total_vp: Uint128::zero(),
+      }],
+    )
+    .unwrap();
+
+  execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(MOCK_CONTRACT_ADDR, &[]),
+    ExecuteMsg::Callback(CallbackMsg::UpdateRewards {
+      initial_balance: AssetInfo::native("uluna").with_balance(Uint128::new(1000000u128)),
+    }),
+  )
+  .unwrap();
+
+  // nothing released yet
+  let rewards = query_rewards(deps.as_ref(), "user1", "aWHALE");
+  assert_eq!(rewards.reward_asset, Asset::native("uluna".to_string(), 0u128));
+
+  let stream = ASSET_REWARD_STREAM
+    .load(deps.as_ref().storage, &AssetInfo::Native("aWHALE".to_string()))
+    .unwrap();
+  assert_eq!(
+    stream,
+    RewardStream {
+      reward_rate: Decimal::from_atomics(Uint128::new(10000), 0).unwrap(),
+      period_finish: mock_env().block.time.seconds() + 100,
+      last_update_s: mock_env().block.time.seconds(),
+    }
+  );
+
+  let query_rewards_at = |deps: Deps, seconds: u64, user: &str| -> PendingRewardsRes {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    from_json(
+      query(
+        deps,
+        env,
+        QueryMsg::PendingRewards(AssetQuery {
+          address: user.to_string(),
+          asset: AssetInfo::Native("aWHALE".to_string()),
+        }),
+      )
+      .unwrap(),
+    )
+    .unwrap()
+  };
+
+  let rewards = query_rewards_at(deps.as_ref(), 50, "user1");
+  assert_eq!(rewards.reward_asset, Asset::native("uluna".to_string(), 500000u128));
+
+  // user2 joins in the middle of the stream and only receives a share of the second half
+  let mut env = mock_env();
+  env.block.time = env.block.time.plus_seconds(50);
+  execute(
+    deps.as_mut(),
+    env,
+    mock_info("user2", &coins(1000000, "aWHALE")),
+    ExecuteMsg::Stake {
+      recipient: None,
+    },
+  )
+  .unwrap();
+
+  let rewards = query_rewards_at(deps.as_ref(), 100, "user1");
+  assert_eq!(rewards.reward_asset, Asset::native("uluna".to_string(), 750000u128));
+  let rewards = query_rewards_at(deps.as_ref(), 100, "user2");
+  assert_eq!(rewards.reward_asset, Asset::native("uluna".to_string(), 250000u128));
+
+  // stream finished, nothing more released
+  let rewards = query_rewards_at(deps.as_ref(), 200, "user1");
+  assert_eq!(rewards.reward_asset, Asset::native("uluna".to_string(), 750000u128));
+
+  let mut env = mock_env();
+  env.block.time = env.block.time.plus_seconds(200);
+  let res = execute(
+    deps.as_mut(),
+    env,
+    mock_info("user1", &[]),
+    ExecuteMsg::ClaimReward {
+      asset: AssetInfo::Native("aWHALE".to_string()),
+      recipient: None,
+    },
+  )
+  .unwrap();
+  assert_eq!(
+    res,
+    Response::new()
+      .add_attributes(vec![
+        ("action", "asset/claim_rewards"),
+        ("user", "user1"),
+        ("assets", "native:aWHALE"),
+        ("reward_amount", "750000"),
+      ])
+      .add_message(CosmosMsg::Bank(BankMsg::Send {
+        to_address: "user1".to_string(),
+        amount: coins(750000, "uluna"),
+      }))
+  );
+}
@@ -73,6 +73,15 @@ fn test_stake_cw20() {
         last_taken_s: 1571797419,
         taken: Uint128::zero(),
         harvested: Uint128::zero(),
+        reward_stream_seconds: 0,
+        unbonding_seconds: 0,
+        max_boost: None,
+        receipt_denom: None,
+        take_tiers: vec![],
+        take_on_rewards: None,
+        max_total_stake: None,
+        max_stake_per_user: None,
+        apr_cap: None,
         yearly_take_rate: Decimal::percent(10),
         stake_config: ve3_shared::stake_config::StakeConfig::Default
       }
@@ -228,6 +237,15 @@ fn test_stake() {
         last_taken_s: 1571797419,
         taken: Uint128::zero(),
         harvested: Uint128::zero(),
+        reward_stream_seconds: 0,
+        unbonding_seconds: 0,
+        max_boost: None,
+        receipt_denom: None,
+        take_tiers: vec![],
+        take_on_rewards: None,
+        max_total_stake: None,
+        max_stake_per_user: None,
+        apr_cap: None,
         yearly_take_rate: Decimal::percent(10),
         stake_config: ve3_shared::stake_config::StakeConfig::Default
       }
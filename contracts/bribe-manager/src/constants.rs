@@ -1,2 +1,5 @@
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Maximum number of periods a single bribe distribution can be expanded to.
+pub const MAX_DISTRIBUTION_PERIODS: u64 = 520;
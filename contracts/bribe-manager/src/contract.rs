@@ -7,20 +7,30 @@ use crate::{
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, Uint128};
+use cosmwasm_std::{
+  attr, from_json, Addr, Api, Attribute, Decimal, DepsMut, Env, MessageInfo, Response, Uint128,
+};
 use cw2::set_contract_version;
-use cw_asset::{Asset, AssetInfo};
+use cw20::Cw20ReceiveMsg;
+use cw_asset::{Asset, AssetInfo, AssetUnchecked};
 use ve3_shared::{
-  adapters::global_config_adapter::ConfigExt,
+  adapters::{asset_gauge::AssetGauge, global_config_adapter::ConfigExt},
   constants::{AT_BRIBE_WHITELIST_CONTROLLER, AT_FEE_COLLECTOR, AT_FREE_BRIBES},
   error::SharedError,
   extensions::{
-    asset_ext::{AssetExt, AssetsExt, AssetsUncheckedExt},
+    asset_ext::{AssetsExt, AssetsUncheckedExt},
     asset_info_ext::AssetInfoExt,
   },
-  helpers::{assets::Assets, general::addr_opt_fallback, governance::get_period, time::Times},
+  helpers::{
+    assets::Assets,
+    general::addr_opt_fallback,
+    governance::get_period,
+    time::{Time, Times},
+  },
   msgs_asset_gauge::UserShare,
-  msgs_bribe_manager::{BribeBuckets, BribeDistribution, Config, ExecuteMsg, InstantiateMsg},
+  msgs_bribe_manager::{
+    BribeBuckets, BribeDistribution, Config, ExecuteMsg, InstantiateMsg, ReceiveMsg,
+  },
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -33,7 +43,8 @@ pub fn instantiate(
   set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
   let fee = msg.fee.check(deps.api, None)?;
-  fee.info.assert_native()?;
+  let fee_options = check_fees(deps.api, msg.fee_options.unwrap_or_default())?;
+  let bribe_fee = assert_bribe_fee(msg.bribe_fee.unwrap_or_default())?;
 
   let whitelist = msg.whitelist.check(deps.api)?;
   let allow_any = whitelist.is_empty();
@@ -44,6 +55,8 @@ pub fn instantiate(
       global_config_addr: deps.api.addr_validate(&msg.global_config_addr)?,
       whitelist,
       fee,
+      fee_options,
+      bribe_fee,
       allow_any,
     },
   )?;
@@ -59,15 +72,28 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
       distribution,
       gauge,
       for_info,
+      fee_info,
     } => {
       let bribe = bribe.check(deps.api, None)?;
-      let for_info = for_info.check(deps.api, None)?;
+      let for_info = for_info.map(|a| a.check(deps.api, None)).transpose()?;
+      let fee_info = fee_info.map(|a| a.check(deps.api, None)).transpose()?;
 
-      add_bribe(deps, info, env, bribe, gauge, for_info, distribution)
+      add_bribe(deps, info, env, bribe, false, gauge, for_info, distribution, fee_info)
     },
+    ExecuteMsg::Receive(cw20_msg) => receive(deps, env, info, cw20_msg),
     ExecuteMsg::WithdrawBribes {
       period,
     } => withdraw_bribes(deps, info, env, period),
+    ExecuteMsg::WithdrawBribe {
+      period,
+      gauge,
+      for_info,
+      asset,
+    } => {
+      let for_info = for_info.map(|a| a.check(deps.api, None)).transpose()?;
+      let asset = asset.check(deps.api, None)?;
+      withdraw_bribe(deps, info, env, period, gauge, for_info, asset)
+    },
     ExecuteMsg::ClaimBribes {
       periods,
       recipient,
@@ -84,15 +110,23 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
     },
     ExecuteMsg::UpdateConfig {
       fee,
+      fee_options,
+      bribe_fee,
       allow_any,
     } => {
       let mut config = CONFIG.load(deps.storage)?;
       config.global_config().assert_owner(&deps.querier, &info.sender)?;
 
       if let Some(fee) = fee {
-        let fee = fee.check(deps.api, None)?;
-        fee.info.assert_native()?;
-        config.fee = fee;
+        config.fee = fee.check(deps.api, None)?;
+      }
+
+      if let Some(fee_options) = fee_options {
+        config.fee_options = check_fees(deps.api, fee_options)?;
+      }
+
+      if let Some(bribe_fee) = bribe_fee {
+        config.bribe_fee = assert_bribe_fee(bribe_fee)?;
       }
 
       if let Some(allow_any) = allow_any {
@@ -105,14 +139,18 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
   }
 }
 
+/// Adds a bribe for the gauge. If received is set, the cw20 bribe was already sent through the receive hook.
+#[allow(clippy::too_many_arguments)]
 fn add_bribe(
   deps: DepsMut,
   info: MessageInfo,
   env: Env,
   bribe: Asset,
+  received: bool,
   gauge: String,
-  for_info: AssetInfo,
+  for_info: Option<AssetInfo>,
   distribution: BribeDistribution,
+  fee_info: Option<AssetInfo>,
 ) -> Result<Response, ContractError> {
   let config = CONFIG.load(deps.storage)?;
 
@@ -120,49 +158,70 @@ fn add_bribe(
   let user = &info.sender;
   let mut msgs = vec![];
 
+  let free_bribes = config.global_config().is_in_list(&deps.querier, AT_FREE_BRIBES, user)?;
+  let fee = if free_bribes {
+    None
+  } else {
+    config.select_fee(fee_info)?
+  };
+
+  // a fee in the same cw20 as the received bribe is taken from the received amount
+  let fee_from_received = received && fee.as_ref().is_some_and(|fee| fee.info == bribe.info);
+  let mut bribe = bribe;
+  if fee_from_received {
+    if let Some(fee) = &fee {
+      bribe.amount = bribe.amount.checked_sub(fee.amount).map_err(|_| {
+        SharedError::WrongDeposit(format!("expected more than {0} for the fee", fee))
+      })?;
+    }
+  }
+
   if bribe.amount.is_zero() {
     Err(SharedError::NotSupported("bribes required".to_string()))?;
   }
 
-  let free_bribes = config.global_config().is_in_list(&deps.querier, AT_FREE_BRIBES, user)?;
-  let has_fee = !config.fee.amount.is_zero() && !free_bribes;
+  let bribe_fee = if free_bribes {
+    Uint128::zero()
+  } else {
+    bribe.amount * config.bribe_fee
+  };
 
   let contract = env.contract.address;
+  // all native assets are expected to be sent together with the message
+  let mut expected_funds = Assets::default();
 
-  // println!("{has_fee} {bribe:?}");
-  match (has_fee, &bribe.info) {
-    (false, AssetInfo::Native(_)) => {
-      // no fee and native bribe
-      bribe.assert_sent(&info)?
-    },
-    (false, AssetInfo::Cw20(_)) => {
-      // no fee and cw20 bribe
-      msgs.push(bribe.transfer_from_msg(user, contract)?)
-    },
-    (true, AssetInfo::Native(_)) if bribe.info == config.fee.info => {
-      // fee and native bribe same asset
-      let expected_amount = bribe.amount.checked_add(config.fee.amount)?;
-      let expected_deposit = bribe.info.with_balance(expected_amount);
-      expected_deposit.assert_sent(&info)?
-    },
-    (true, AssetInfo::Native(_)) => {
-      // fee and native bribe different asset
-      vec![&bribe, &config.fee].assert_sent(&info)?
-    },
-    (true, AssetInfo::Cw20(_)) => {
-      // fee and cw20 bribe
-      config.fee.assert_sent(&info)?;
-      msgs.push(bribe.transfer_from_msg(user, contract)?)
-    },
-
+  match &bribe.info {
+    AssetInfo::Native(_) => expected_funds.add(&bribe),
+    AssetInfo::Cw20(_) if received => (),
+    AssetInfo::Cw20(_) => msgs.push(bribe.transfer_from_msg(user, &contract)?),
     _ => Err(SharedError::WrongDeposit("combination not supported".to_string()))?,
   }
 
-  if has_fee {
+  if fee.is_some() || !bribe_fee.is_zero() {
     let fee_collector = config.global_config().get_address(&deps.querier, AT_FEE_COLLECTOR)?;
-    msgs.push(config.fee.transfer_msg(fee_collector)?)
+
+    if let Some(fee) = fee {
+      match &fee.info {
+        AssetInfo::Native(_) => {
+          expected_funds.add(&fee);
+          msgs.push(fee.transfer_msg(&fee_collector)?)
+        },
+        AssetInfo::Cw20(_) if fee_from_received => msgs.push(fee.transfer_msg(&fee_collector)?),
+        // cw20 fees are directly sent from the user to the fee collector
+        AssetInfo::Cw20(_) => msgs.push(fee.transfer_from_msg(user, &fee_collector)?),
+        _ => Err(SharedError::WrongDeposit("combination not supported".to_string()))?,
+      }
+    }
+
+    if !bribe_fee.is_zero() {
+      msgs.push(bribe.info.with_balance(bribe_fee).transfer_msg(&fee_collector)?)
+    }
   }
 
+  expected_funds.0.iter().collect::<Vec<_>>().assert_sent(&info)?;
+
+  // the percentage fee is taken from the bribe itself
+  let bribe = bribe.info.with_balance(bribe.amount.checked_sub(bribe_fee)?);
   let bribes: Vec<(u64, Uint128)> = distribution.create_distribution(block_period, bribe.amount)?;
 
   let start = bribes.first().map(|a| a.0).unwrap_or_default();
@@ -180,8 +239,8 @@ fn add_bribe(
     let mut global_bucket = BRIBE_AVAILABLE.load(deps.storage, period).unwrap_or_default();
     let mut user_bucket = BRIBE_CREATOR.load(deps.storage, user_key).unwrap_or_default();
 
-    global_bucket.add(&gauge, &for_info, &bribe_split);
-    user_bucket.add(&gauge, &for_info, &bribe_split);
+    global_bucket.add(&gauge, for_info.as_ref(), &bribe_split);
+    user_bucket.add(&gauge, for_info.as_ref(), &bribe_split);
 
     // unwrap is used here to get a correct coverage report
     BRIBE_AVAILABLE.save(deps.storage, period, &global_bucket).unwrap();
@@ -198,6 +257,34 @@ fn add_bribe(
   )
 }
 
+fn receive(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+  let received = Asset::cw20(info.sender, cw20_msg.amount);
+  // the bribe is added on behalf of the cw20 sender
+  let info = MessageInfo {
+    sender: deps.api.addr_validate(&cw20_msg.sender)?,
+    funds: info.funds,
+  };
+
+  match from_json(&cw20_msg.msg)? {
+    ReceiveMsg::AddBribe {
+      gauge,
+      for_info,
+      distribution,
+      fee_info,
+    } => {
+      let for_info = for_info.map(|a| a.check(deps.api, None)).transpose()?;
+      let fee_info = fee_info.map(|a| a.check(deps.api, None)).transpose()?;
+
+      add_bribe(deps, info, env, received, true, gauge, for_info, distribution, fee_info)
+    },
+  }
+}
+
 fn withdraw_bribes(
   deps: DepsMut,
   info: MessageInfo,
@@ -222,12 +309,7 @@ fn withdraw_bribes(
   let mut together = Assets::default();
   for bucket in user_bucket.buckets {
     for bribe in bucket.assets {
-      if let Some(asset) = &bucket.asset {
-        available.remove(&bucket.gauge, asset, &bribe)?
-      } else {
-        // buckets always have some asset, except for the group result
-      }
-
+      available.remove(&bucket.gauge, bucket.asset.as_ref(), &bribe)?;
       together.add(&bribe);
     }
   }
@@ -243,6 +325,56 @@ fn withdraw_bribes(
   Ok(Response::new().add_attribute("action", "bribe/withdraw_bribes").add_messages(transfer_msgs))
 }
 
+fn withdraw_bribe(
+  deps: DepsMut,
+  info: MessageInfo,
+  env: Env,
+  period: u64,
+  gauge: String,
+  for_info: Option<AssetInfo>,
+  asset: AssetInfo,
+) -> Result<Response, ContractError> {
+  let block_period = get_period(env.block.time.seconds())?;
+
+  if period <= block_period {
+    return Err(ContractError::BribesAlreadyDistributing);
+  }
+
+  let user = &info.sender;
+  let user_key = (user.as_str(), period);
+  let mut user_bucket = BRIBE_CREATOR.may_load(deps.storage, user_key)?.unwrap_or_default();
+
+  let bribe = user_bucket
+    .find(&gauge, for_info.as_ref())
+    .and_then(|b| b.assets.0.iter().find(|a| a.info == asset))
+    .cloned()
+    .ok_or(ContractError::NoBribes)?;
+
+  let mut available = BRIBE_AVAILABLE.load(deps.storage, period)?;
+  available.remove(&gauge, for_info.as_ref(), &bribe)?;
+  user_bucket.remove(&gauge, for_info.as_ref(), &bribe)?;
+
+  if available.is_empty() {
+    BRIBE_AVAILABLE.remove(deps.storage, period);
+  } else {
+    BRIBE_AVAILABLE.save(deps.storage, period, &available)?;
+  }
+
+  if user_bucket.is_empty() {
+    BRIBE_CREATOR.remove(deps.storage, user_key);
+  } else {
+    BRIBE_CREATOR.save(deps.storage, user_key, &user_bucket)?;
+  }
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "bribe/withdraw_bribe")
+      .add_attribute("period", period.to_string())
+      .add_attribute("withdrawn", bribe.to_string())
+      .add_message(bribe.transfer_msg(user)?),
+  )
+}
+
 fn claim_bribes(
   deps: DepsMut,
   _env: Env,
@@ -278,6 +410,7 @@ fn claim_bribes(
     // shares list sorted by period, each time we find a new one, context is updated.
     // starts with 0
     if share.period != context.period {
+      claim_gauge_bribes(&deps, &asset_gauge, &mut context, &mut bribe_total, &mut attrs, user)?;
       context.maybe_save(deps.storage, user)?;
 
       periods.push(share.period);
@@ -315,6 +448,7 @@ fn claim_bribes(
         bribe_available,
         bribe_totals,
         bribe_claimed: BribeBuckets::default(),
+        gauge_vps: vec![],
       };
     }
 
@@ -331,41 +465,16 @@ fn claim_bribes(
       ..
     } = share;
 
-    // see how much total bribe rewards for the asset in the gauge
-    let total_bribe_bucket = context.bribe_totals.get(&gauge, &asset);
-    // calculate the reward share based on the user vp compared to total vp
-    let mut rewards = total_bribe_bucket.assets.calc_share_amounts(vp, total_vp)?;
-    // add these rewards to the claimed bucket by the user
-    context.bribe_claimed.get(&gauge, &asset).assets.add_multi(&rewards);
-
-    // remove the rewards from the available bribe bucket
-    // in case it wants to take more than available, rewards are being reduced by the overtaken amount, and overtaken amount returned.
-    let overtakens = context
-      .bribe_available
-      .get(&gauge, &asset)
-      .assets
-      .remove_multi_overtaken(&mut rewards)
-      .map_err(|s| {
-        // safety if we try to take more than what is available in the bucket for the asset, then it fails for the user
-        ContractError::SharedErrorExtended(
-          s,
-          format!("gauge {gauge} asset {asset} vp {vp} total {total_vp} user {user}"),
-        )
-      })?;
+    // the vp in the gauge is used for bribes targeting the whole gauge
+    context.add_gauge_vp(&gauge, vp);
 
-    for overtaken in overtakens {
-      attrs.push(attr(
-        "overtaken",
-        format!(
-          "gauge {gauge} asset {asset} vp {vp} total {total_vp} user {user} overtaken {overtaken}"
-        ),
-      ))
-    }
+    let rewards = claim_bucket(&mut context, &gauge, Some(&asset), vp, total_vp, &mut attrs, user)?;
 
     // add the rewards also to the flattened
     bribe_total.add_multi(&rewards);
   }
 
+  claim_gauge_bribes(&deps, &asset_gauge, &mut context, &mut bribe_total, &mut attrs, user)?;
   context.maybe_save(deps.storage, user)?;
 
   let recipient = addr_opt_fallback(deps.api, &recipient, user.clone())?;
@@ -381,6 +490,79 @@ fn claim_bribes(
   )
 }
 
+/// Moves the user share of a bribe bucket from the available bribes to the claimed bribes.
+fn claim_bucket(
+  context: &mut ClaimContext,
+  gauge: &str,
+  asset: Option<&AssetInfo>,
+  vp: Uint128,
+  total_vp: Uint128,
+  attrs: &mut Vec<Attribute>,
+  user: &Addr,
+) -> Result<Vec<Asset>, ContractError> {
+  let asset_str = asset.map(|a| a.to_string()).unwrap_or_else(|| "gauge".to_string());
+
+  // see how much total bribe rewards for the asset in the gauge
+  let total_bribe_bucket = context.bribe_totals.get(gauge, asset);
+  // calculate the reward share based on the user vp compared to total vp
+  let mut rewards = total_bribe_bucket.assets.calc_share_amounts(vp, total_vp)?;
+  // add these rewards to the claimed bucket by the user
+  context.bribe_claimed.get(gauge, asset).assets.add_multi(&rewards);
+
+  // remove the rewards from the available bribe bucket
+  // in case it wants to take more than available, rewards are being reduced by the overtaken amount, and overtaken amount returned.
+  let overtakens =
+    context.bribe_available.get(gauge, asset).assets.remove_multi_overtaken(&mut rewards).map_err(
+      |s| {
+        // safety if we try to take more than what is available in the bucket for the asset, then it fails for the user
+        ContractError::SharedErrorExtended(
+          s,
+          format!("gauge {gauge} asset {asset_str} vp {vp} total {total_vp} user {user}"),
+        )
+      },
+    )?;
+
+  for overtaken in overtakens {
+    attrs.push(attr(
+      "overtaken",
+      format!(
+        "gauge {gauge} asset {asset_str} vp {vp} total {total_vp} user {user} overtaken {overtaken}"
+      ),
+    ))
+  }
+
+  Ok(rewards)
+}
+
+/// Claims the bribes targeting a whole gauge, based on the user vp in the gauge compared to the total gauge vp.
+fn claim_gauge_bribes(
+  deps: &DepsMut,
+  asset_gauge: &AssetGauge,
+  context: &mut ClaimContext,
+  bribe_total: &mut Assets,
+  attrs: &mut Vec<Attribute>,
+  user: &Addr,
+) -> Result<(), ContractError> {
+  if context.skip {
+    return Ok(());
+  }
+
+  for (gauge, vp) in context.gauge_vps.clone() {
+    if context.bribe_totals.find(&gauge, None).is_none() {
+      continue;
+    }
+
+    let total_vp = asset_gauge
+      .query_distribution(&deps.querier, gauge.clone(), Some(Time::Period(context.period)))?
+      .total_gauge_vp;
+
+    let rewards = claim_bucket(context, &gauge, None, vp, total_vp, attrs, user)?;
+    bribe_total.add_multi(&rewards);
+  }
+
+  Ok(())
+}
+
 fn whitelist_assets(
   deps: DepsMut,
   info: MessageInfo,
@@ -431,6 +613,18 @@ fn remove_assets(
   )
 }
 
+fn check_fees(api: &dyn Api, fees: Vec<AssetUnchecked>) -> Result<Vec<Asset>, ContractError> {
+  fees.into_iter().map(|fee| Ok(fee.check(api, None)?)).collect()
+}
+
+fn assert_bribe_fee(bribe_fee: Decimal) -> Result<Decimal, ContractError> {
+  if bribe_fee > Decimal::percent(50) {
+    Err(ContractError::BribeFeeTooHigh)
+  } else {
+    Ok(bribe_fee)
+  }
+}
+
 fn asset_sum_equal(asset: &Asset, bribes: &[(u64, Uint128)]) -> Result<(), ContractError> {
   let sum: Uint128 = bribes.iter().map(|(_, b)| b).sum();
   if sum == asset.amount {
@@ -1,7 +1,7 @@
 use cosmwasm_std::{Decimal, Uint128};
 use ve3_shared::msgs_bribe_manager::{BribeDistribution, FuncType};
 
-use crate::error::ContractError;
+use crate::{constants::MAX_DISTRIBUTION_PERIODS, error::ContractError};
 
 pub trait BribeDistributionExt {
   fn create_distribution(
@@ -61,8 +61,174 @@ impl BribeDistributionExt for BribeDistribution {
       },
       BribeDistribution::Next => Ok(vec![(block_period + 1, amount)]),
       BribeDistribution::Specific(specific) => Ok(specific),
+      BribeDistribution::Piecewise {
+        points,
+      } => {
+        let (first, last) = match (points.first(), points.last()) {
+          (Some(first), Some(last)) => (first.0, last.0),
+          _ => Err(ContractError::BribeDistribution("points required".to_string()))?,
+        };
+
+        if points.windows(2).any(|w| w[0].0 >= w[1].0) {
+          Err(ContractError::BribeDistribution(
+            "points must be sorted by period without duplicates.".to_string(),
+          ))?;
+        }
+        assert_max_periods(last - first)?;
+
+        let mut weights = vec![];
+        let mut knot = 0;
+        for n in first..=last {
+          while points[knot].0 < n {
+            knot += 1;
+          }
+
+          let (to_period, to_weight) = points[knot];
+          if to_period == n {
+            weights.push(to_weight);
+          } else {
+            // interpolate between the previous and the next knot
+            let (from_period, from_weight) = points[knot - 1];
+            let progress = Decimal::from_ratio(n - from_period, to_period - from_period);
+            let weight = if to_weight >= from_weight {
+              from_weight + (to_weight - from_weight) * progress
+            } else {
+              from_weight - (from_weight - to_weight) * progress
+            };
+            weights.push(weight);
+          }
+        }
+
+        distribute_weights(first, weights, amount)
+      },
+      BribeDistribution::ExponentialDecay {
+        start,
+        end,
+        half_life_periods,
+      } => {
+        let start = start.unwrap_or(block_period + 1);
+        if end < start {
+          Err(ContractError::BribeDistribution(format!(
+            "from ({0}) must be <= to ({1}).",
+            start, end
+          )))?;
+        }
+        if half_life_periods == 0 {
+          Err(ContractError::BribeDistribution("half life must be > 0".to_string()))?;
+        }
+        assert_max_periods(end - start)?;
+
+        let decay = decay_per_period(half_life_periods)?;
+        let mut weights = vec![];
+        let mut weight = Decimal::one();
+        for _ in start..=end {
+          weights.push(weight);
+          weight *= decay;
+        }
+
+        distribute_weights(start, weights, amount)
+      },
+      BribeDistribution::Recurring {
+        start,
+        every_n_periods,
+        count,
+      } => {
+        let start = start.unwrap_or(block_period + 1);
+        if every_n_periods == 0 || count == 0 {
+          Err(ContractError::BribeDistribution(
+            "every_n_periods and count must be > 0".to_string(),
+          ))?;
+        }
+        assert_max_periods(count - 1)?;
+
+        let mut results = vec![];
+        let mut last = Uint128::zero();
+        for i in 0..count {
+          let total = amount.multiply_ratio(i + 1, count);
+          let delta = total - last;
+          last = total;
+
+          // periods without any bribes are skipped
+          if !delta.is_zero() {
+            let period = i.checked_mul(every_n_periods)?.checked_add(start)?;
+            results.push((period, delta));
+          }
+        }
+
+        Ok(results)
+      },
+    }
+  }
+}
+
+/// Splits the amount proportionally to the weights, starting at the start period.
+/// The last period receives the rounding difference, so that the sum is always equal to the amount.
+fn distribute_weights(
+  start: u64,
+  weights: Vec<Decimal>,
+  amount: Uint128,
+) -> Result<Vec<(u64, Uint128)>, ContractError> {
+  let total_weight =
+    weights.iter().try_fold(Decimal::zero(), |acc, weight| acc.checked_add(*weight))?;
+  if total_weight.is_zero() {
+    Err(ContractError::BribeDistribution("total weight must be > 0".to_string()))?;
+  }
+
+  let mut results = vec![];
+  let mut last = Uint128::zero();
+  let mut cumulative = Decimal::zero();
+  let periods = weights.len();
+  for (i, weight) in weights.into_iter().enumerate() {
+    cumulative += weight;
+    let delta = if i == periods - 1 {
+      amount - last
+    } else {
+      let total = amount.multiply_ratio(cumulative.atomics(), total_weight.atomics());
+      let delta = total - last;
+      last = total;
+      delta
+    };
+
+    // periods without any bribes are skipped
+    if !delta.is_zero() {
+      results.push((start + i as u64, delta));
     }
   }
+
+  Ok(results)
+}
+
+/// Rejects distributions spanning more than MAX_DISTRIBUTION_PERIODS periods.
+/// Expects the difference between the last and the first period.
+fn assert_max_periods(period_span: u64) -> Result<(), ContractError> {
+  if period_span >= MAX_DISTRIBUTION_PERIODS {
+    Err(ContractError::BribeDistribution(format!(
+      "distribution must not exceed {0} periods.",
+      MAX_DISTRIBUTION_PERIODS
+    )))?;
+  }
+  Ok(())
+}
+
+/// Calculates the factor x with x^half_life = 0.5 through bisection.
+fn decay_per_period(half_life_periods: u64) -> Result<Decimal, ContractError> {
+  let half_life: u32 = half_life_periods
+    .try_into()
+    .map_err(|_| ContractError::BribeDistribution("half life too long".to_string()))?;
+  let half = Decimal::percent(50);
+
+  let mut low = half;
+  let mut high = Decimal::one();
+  for _ in 0..64 {
+    let mid = (low + high) / dec(2);
+    if mid.pow(half_life) > half {
+      high = mid;
+    } else {
+      low = mid;
+    }
+  }
+
+  Ok(low)
 }
 
 pub fn linear(t: Decimal) -> Decimal {
@@ -101,10 +267,11 @@ fn dec(numb: u128) -> Decimal {
 
 #[cfg(test)]
 mod test {
-  use cosmwasm_std::Uint128;
+  use cosmwasm_std::{Decimal, Uint128};
+  use proptest::prelude::*;
   use ve3_shared::msgs_bribe_manager::{BribeDistribution, FuncType};
 
-  use crate::error::ContractError;
+  use crate::{constants::MAX_DISTRIBUTION_PERIODS, error::ContractError};
 
   use super::BribeDistributionExt;
 
@@ -177,4 +344,210 @@ mod test {
 
     Ok(())
   }
+
+  #[test]
+  fn test_piecewise() -> Result<(), ContractError> {
+    let distribution = BribeDistribution::Piecewise {
+      points: vec![(10, Decimal::one()), (12, Decimal::percent(300)), (14, Decimal::zero())],
+    }
+    .create_distribution(0, Uint128::new(900))?;
+
+    // weights 1, 2, 3, 1.5, 0
+    assert_eq!(
+      distribution,
+      vec![
+        (10, Uint128::new(120)),
+        (11, Uint128::new(240)),
+        (12, Uint128::new(360)),
+        (13, Uint128::new(180))
+      ]
+    );
+
+    let err = BribeDistribution::Piecewise {
+      points: vec![(12, Decimal::one()), (10, Decimal::one())],
+    }
+    .create_distribution(0, Uint128::new(900))
+    .unwrap_err();
+
+    assert_eq!(
+      err,
+      ContractError::BribeDistribution(
+        "points must be sorted by period without duplicates.".to_string()
+      )
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_exponential_decay() -> Result<(), ContractError> {
+    let distribution = BribeDistribution::ExponentialDecay {
+      start: Some(1),
+      end: 3,
+      half_life_periods: 1,
+    }
+    .create_distribution(0, Uint128::new(700))?;
+
+    assert_eq!(
+      distribution,
+      vec![(1, Uint128::new(400)), (2, Uint128::new(200)), (3, Uint128::new(100))]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_recurring() -> Result<(), ContractError> {
+    let distribution = BribeDistribution::Recurring {
+      start: None,
+      every_n_periods: 4,
+      count: 3,
+    }
+    .create_distribution(10, Uint128::new(1000))?;
+
+    assert_eq!(
+      distribution,
+      vec![(11, Uint128::new(333)), (15, Uint128::new(333)), (19, Uint128::new(334))]
+    );
+
+    // periods that would receive nothing are skipped
+    let distribution = BribeDistribution::Recurring {
+      start: Some(1),
+      every_n_periods: 1,
+      count: 4,
+    }
+    .create_distribution(0, Uint128::new(2))?;
+
+    assert_eq!(distribution, vec![(2, Uint128::new(1)), (4, Uint128::new(1))]);
+
+    let err = BribeDistribution::Recurring {
+      start: Some(u64::MAX - 10),
+      every_n_periods: 10,
+      count: 3,
+    }
+    .create_distribution(0, Uint128::new(1000))
+    .unwrap_err();
+
+    assert!(matches!(err, ContractError::OverflowError(_)));
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_max_periods() -> Result<(), ContractError> {
+    let expected = ContractError::BribeDistribution(format!(
+      "distribution must not exceed {0} periods.",
+      MAX_DISTRIBUTION_PERIODS
+    ));
+
+    let err = BribeDistribution::Recurring {
+      start: None,
+      every_n_periods: 1,
+      count: MAX_DISTRIBUTION_PERIODS + 1,
+    }
+    .create_distribution(0, Uint128::new(1000))
+    .unwrap_err();
+    assert_eq!(err, expected);
+
+    let err = BribeDistribution::ExponentialDecay {
+      start: Some(1),
+      end: u64::MAX,
+      half_life_periods: 1,
+    }
+    .create_distribution(0, Uint128::new(1000))
+    .unwrap_err();
+    assert_eq!(err, expected);
+
+    let err = BribeDistribution::Piecewise {
+      points: vec![(1, Decimal::one()), (1 + MAX_DISTRIBUTION_PERIODS, Decimal::one())],
+    }
+    .create_distribution(0, Uint128::new(1000))
+    .unwrap_err();
+    assert_eq!(err, expected);
+
+    // the maximum itself is allowed
+    BribeDistribution::Recurring {
+      start: None,
+      every_n_periods: 1,
+      count: MAX_DISTRIBUTION_PERIODS,
+    }
+    .create_distribution(0, Uint128::new(1000))?;
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_weight_overflow() {
+    let err = BribeDistribution::Piecewise {
+      points: vec![(1, Decimal::MAX), (2, Decimal::MAX)],
+    }
+    .create_distribution(0, Uint128::new(1000))
+    .unwrap_err();
+
+    assert!(matches!(err, ContractError::OverflowError(_)));
+  }
+
+  fn assert_sum(distribution: Vec<(u64, Uint128)>, amount: u128) {
+    let sum: Uint128 = distribution.iter().map(|(_, a)| a).sum();
+    assert_eq!(sum, Uint128::new(amount));
+    assert!(distribution.windows(2).all(|w| w[0].0 < w[1].0));
+  }
+
+  proptest! {
+    #[test]
+    fn prop_piecewise_sum(
+      amount in 1u128..1_000_000_000_000u128,
+      knots in prop::collection::vec((1u64..10u64, 0u64..1000u64), 1..10),
+    ) {
+      let mut period = 0;
+      let mut points = vec![];
+      for (gap, weight) in knots {
+        period += gap;
+        points.push((period, Decimal::permille(weight)));
+      }
+      prop_assume!(points.iter().any(|p| !p.1.is_zero()));
+
+      let distribution = BribeDistribution::Piecewise {
+        points,
+      }
+      .create_distribution(0, Uint128::new(amount))
+      .unwrap();
+
+      assert_sum(distribution, amount);
+    }
+
+    #[test]
+    fn prop_exponential_decay_sum(
+      amount in 1u128..1_000_000_000_000u128,
+      periods in 0u64..200u64,
+      half_life_periods in 1u64..100u64,
+    ) {
+      let distribution = BribeDistribution::ExponentialDecay {
+        start: Some(1),
+        end: 1 + periods,
+        half_life_periods,
+      }
+      .create_distribution(0, Uint128::new(amount))
+      .unwrap();
+
+      assert_sum(distribution, amount);
+    }
+
+    #[test]
+    fn prop_recurring_sum(
+      amount in 1u128..1_000_000_000_000u128,
+      every_n_periods in 1u64..10u64,
+      count in 1u64..100u64,
+    ) {
+      let distribution = BribeDistribution::Recurring {
+        start: None,
+        every_n_periods,
+        count,
+      }
+      .create_distribution(0, Uint128::new(amount))
+      .unwrap();
+
+      assert_sum(distribution, amount);
+    }
+  }
 }
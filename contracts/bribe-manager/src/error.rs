@@ -53,4 +53,7 @@ pub enum ContractError {
 
   #[error("Sent asset infos must contain at least one.")]
   RequiresAssetInfos,
+
+  #[error("Bribe fee needs to be less or equal 50%")]
+  BribeFeeTooHigh,
 }
@@ -2,19 +2,25 @@ use std::cmp::min;
 
 use crate::{
   error::ContractError,
-  state::{fetch_last_claimed, ClaimContext, BRIBE_AVAILABLE, BRIBE_CLAIMED, BRIBE_TOTAL, CONFIG},
+  state::{
+    fetch_last_claimed, ClaimContext, BRIBE_AVAILABLE, BRIBE_CLAIMED, BRIBE_CREATOR, BRIBE_TOTAL,
+    CONFIG,
+  },
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_json_binary, Addr, Binary, Deps, Env, StdResult};
+use cosmwasm_std::{to_json_binary, Addr, Binary, Deps, Env, Order, StdResult};
+use cw_storage_plus::Bound;
 use itertools::Itertools;
 use ve3_shared::{
   adapters::asset_gauge::AssetGauge,
+  constants::{DEFAULT_LIMIT, MAX_LIMIT},
   error::SharedError,
   helpers::time::{GetPeriod, Time, Times},
   msgs_asset_gauge::UserShare,
   msgs_bribe_manager::{
-    BribeBuckets, BribesResponse, NextClaimPeriodResponse, QueryMsg, UserClaimableResponse,
+    BribeBuckets, BribesResponse, CreatorBribesResponse, NextClaimPeriodResponse, QueryMsg,
+    UserClaimableResponse,
   },
 };
 
@@ -32,6 +38,11 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
       periods,
       user,
     } => Ok(to_json_binary(&user_claimable(deps, env, user, periods)?)?),
+    QueryMsg::CreatorBribes {
+      creator,
+      start_after,
+      limit,
+    } => Ok(to_json_binary(&creator_bribes(deps, creator, start_after, limit)?)?),
   }
 }
 
@@ -110,6 +121,23 @@ fn bribes(deps: Deps, env: Env, time: Option<Time>) -> StdResult<BribesResponse>
   Ok(bribes)
 }
 
+fn creator_bribes(
+  deps: Deps,
+  creator: String,
+  start_after: Option<u64>,
+  limit: Option<u32>,
+) -> StdResult<CreatorBribesResponse> {
+  let creator = deps.api.addr_validate(&creator)?;
+  let start = start_after.map(Bound::exclusive);
+  let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+  BRIBE_CREATOR
+    .prefix(creator.as_str())
+    .range(deps.storage, start, None, Order::Ascending)
+    .take(limit)
+    .collect()
+}
+
 fn user_claimable(
   deps: Deps,
   _env: Env,
@@ -142,6 +170,8 @@ fn user_claimable(
     // shares list sorted by period, each time we find a new one, context is updated.
     // starts with 0
     if share.period != context.period {
+      add_gauge_claimable(&deps, &asset_gauge, &context, &mut claimed)?;
+
       let bribe_available = match BRIBE_AVAILABLE.may_load(deps.storage, share.period)? {
         Some(buckets) => buckets,
         None => {
@@ -190,19 +220,53 @@ fn user_claimable(
       ..
     } = share;
 
+    context.add_gauge_vp(&gauge, vp);
+
     // see how much total bribe rewards for the asset in the gauge
-    let total_bribe_bucket = context.bribe_totals.get(&gauge, &asset);
+    let total_bribe_bucket = context.bribe_totals.get(&gauge, Some(&asset));
     // calculate the reward share based on the user vp compared to total vp
     let rewards = total_bribe_bucket.assets.calc_share_amounts(vp, total_vp)?;
     // add these rewards to the claimed bucket by the user
     if !rewards.is_empty() {
-      claimed.get(&gauge, &asset).assets.add_multi(&rewards);
+      claimed.get(&gauge, Some(&asset)).assets.add_multi(&rewards);
     }
   }
 
+  add_gauge_claimable(&deps, &asset_gauge, &context, &mut claimed)?;
+
   Ok(UserClaimableResponse {
     start,
     end,
     buckets: claimed.buckets,
   })
 }
+
+/// Adds the share of the bribes targeting a whole gauge, based on the user vp in the gauge compared to the total gauge vp.
+fn add_gauge_claimable(
+  deps: &Deps,
+  asset_gauge: &AssetGauge,
+  context: &ClaimContext,
+  claimed: &mut BribeBuckets,
+) -> Result<(), ContractError> {
+  if context.skip {
+    return Ok(());
+  }
+
+  for (gauge, vp) in context.gauge_vps.iter() {
+    let total_bribe_bucket = match context.bribe_totals.find(gauge, None) {
+      Some(bucket) => bucket,
+      None => continue,
+    };
+
+    let total_vp = asset_gauge
+      .query_distribution(&deps.querier, gauge.clone(), Some(Time::Period(context.period)))?
+      .total_gauge_vp;
+
+    let rewards = total_bribe_bucket.assets.calc_share_amounts(*vp, total_vp)?;
+    if !rewards.is_empty() {
+      claimed.get(gauge, None).assets.add_multi(&rewards);
+    }
+  }
+
+  Ok(())
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, Order, StdResult, Storage};
+use cosmwasm_std::{Addr, Order, StdResult, Storage, Uint128};
 use cw_storage_plus::{Bound, Item, Map};
 use ve3_shared::msgs_bribe_manager::{BribeBuckets, Config};
 
@@ -32,9 +32,19 @@ pub struct ClaimContext {
   pub bribe_totals: BribeBuckets,
 
   pub bribe_claimed: BribeBuckets,
+
+  /// user vp per gauge in the period
+  pub gauge_vps: Vec<(String, Uint128)>,
 }
 
 impl ClaimContext {
+  pub fn add_gauge_vp(&mut self, gauge: &str, vp: Uint128) {
+    match self.gauge_vps.iter_mut().find(|(g, _)| g == gauge) {
+      Some((_, existing)) => *existing += vp,
+      None => self.gauge_vps.push((gauge.to_string(), vp)),
+    }
+  }
+
   pub fn maybe_save(&self, store: &mut dyn Storage, user: &Addr) -> StdResult<()> {
     if self.period > 0 && self.should_save {
       BRIBE_CLAIMED.save(store, (user.as_str(), self.period), &self.bribe_claimed)?;
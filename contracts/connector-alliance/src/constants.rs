@@ -3,3 +3,7 @@ pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 // pub const CREATE_REPLY_ID: u64 = 1;
 pub const CLAIM_REWARD_ERROR_REPLY_ID: u64 = 2;
+
+pub const DEFAULT_MAX_MOVES: u32 = 10;
+// a validator that received a redelegation can't redelegate it further until it matured
+pub const REDELEGATION_COOLDOWN_S: u64 = 21 * 24 * 60 * 60;
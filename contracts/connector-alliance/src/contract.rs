@@ -1,14 +1,19 @@
-use crate::constants::{CLAIM_REWARD_ERROR_REPLY_ID, CONTRACT_NAME, CONTRACT_VERSION};
+use crate::constants::{
+  CLAIM_REWARD_ERROR_REPLY_ID, CONTRACT_NAME, CONTRACT_VERSION, DEFAULT_MAX_MOVES,
+  REDELEGATION_COOLDOWN_S,
+};
 use crate::error::ContractError;
-use crate::state::{CONFIG, STATE, VALIDATORS};
+use crate::state::{CONFIG, REDELEGATION_COOLDOWNS, STATE, TARGET_DELEGATIONS, VALIDATORS};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-  Addr, Binary, CosmosMsg, DepsMut, Env, MessageInfo, Reply, Response, SubMsg, Uint128,
+  Addr, Binary, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Reply, Response, Storage, SubMsg,
+  Uint128,
 };
 use cw2::set_contract_version;
 use cw_asset::AssetInfo;
-use std::collections::HashSet;
+use std::cmp::min;
+use std::collections::{HashMap, HashSet};
 use terra_proto_rs::alliance::alliance::{
   MsgClaimDelegationRewards, MsgDelegate, MsgRedelegate, MsgUndelegate,
 };
@@ -20,6 +25,7 @@ use ve3_shared::constants::{at_asset_staking, AT_DELEGATION_CONTROLLER};
 use ve3_shared::error::SharedError;
 use ve3_shared::extensions::asset_info_ext::AssetInfoExt;
 use ve3_shared::extensions::env_ext::EnvExt;
+use ve3_shared::helpers::alliance::query_alliance_delegations;
 use ve3_shared::helpers::denom::MsgCreateDenom;
 use ve3_shared::helpers::general::addr_opt_fallback;
 use ve3_shared::helpers::take::{compute_balance_amount, compute_share_amount};
@@ -120,6 +126,12 @@ pub fn execute(
     ExecuteMsg::AllianceDelegate(msg) => alliance_delegate(deps, env, info, msg),
     ExecuteMsg::AllianceUndelegate(msg) => alliance_undelegate(deps, env, info, msg),
     ExecuteMsg::AllianceRedelegate(msg) => alliance_redelegate(deps, env, info, msg),
+    ExecuteMsg::SetTargetDelegations {
+      weights,
+    } => set_target_delegations(deps, info, weights),
+    ExecuteMsg::Rebalance {
+      max_moves,
+    } => rebalance(deps, env, max_moves),
     ExecuteMsg::RemoveValidator {
       validator,
     } => remove_validator(deps, env, info, validator),
@@ -408,28 +420,149 @@ fn alliance_redelegate(
   let mut msgs = vec![];
   let mut validators = VALIDATORS.load(deps.storage)?;
   for redelegation in msg.redelegations {
-    let src_validator = redelegation.src_validator;
-    let dst_validator = redelegation.dst_validator;
-    let redelegate_msg = MsgRedelegate {
-      amount: Some(Coin {
-        denom: config.alliance_token_denom.clone(),
-        amount: redelegation.amount.to_string(),
-      }),
-      delegator_address: env.contract.address.to_string(),
-      validator_src_address: src_validator.to_string(),
-      validator_dst_address: dst_validator.to_string(),
-    };
-    let msg = CosmosMsg::Stargate {
-      type_url: "/alliance.alliance.MsgRedelegate".to_string(),
-      value: Binary::from(redelegate_msg.encode_to_vec()),
-    };
-    msgs.push(msg);
-    validators.insert(dst_validator);
+    msgs.push(_redelegate(
+      deps.storage,
+      &env,
+      &config,
+      &redelegation.src_validator,
+      &redelegation.dst_validator,
+      redelegation.amount,
+    )?);
+    validators.insert(redelegation.dst_validator);
   }
   VALIDATORS.save(deps.storage, &validators)?;
   Ok(Response::new().add_attributes(vec![("action", "ca/alliance_redelegate")]).add_messages(msgs))
 }
 
+fn set_target_delegations(
+  deps: DepsMut,
+  info: MessageInfo,
+  weights: Vec<(String, Decimal)>,
+) -> Result<Response, ContractError> {
+  let config = CONFIG.load(deps.storage)?;
+  assert_controller(&deps, &info, &config)?;
+
+  let mut unique = HashSet::new();
+  let mut total = Decimal::zero();
+  for (validator, weight) in weights.iter() {
+    if !unique.insert(validator) {
+      return Err(ContractError::DuplicateValidator(validator.to_string()));
+    }
+    // catches typos before the rebalancing redelegates to the validator
+    if deps.querier.query_validator(validator)?.is_none() {
+      return Err(ContractError::InvalidValidator(validator.to_string()));
+    }
+    total = total.checked_add(*weight)?;
+  }
+  if total != Decimal::one() {
+    return Err(ContractError::InvalidTargetWeights);
+  }
+
+  TARGET_DELEGATIONS.save(deps.storage, &weights)?;
+  Ok(Response::new().add_attributes(vec![("action", "ca/set_target_delegations")]))
+}
+
+fn rebalance(deps: DepsMut, env: Env, max_moves: Option<u32>) -> Result<Response, ContractError> {
+  let config = CONFIG.load(deps.storage)?;
+  let targets =
+    TARGET_DELEGATIONS.may_load(deps.storage)?.ok_or(ContractError::NoTargetDelegations)?;
+  let max_moves = max_moves.unwrap_or(DEFAULT_MAX_MOVES) as usize;
+  let now = env.block.time.seconds();
+
+  let current: HashMap<String, Uint128> = query_alliance_delegations(
+    &deps.querier,
+    env.contract.address.to_string(),
+    &config.alliance_token_denom,
+  )?
+  .into_iter()
+  .collect();
+  let total: Uint128 = current.values().sum();
+
+  let mut target_amounts: HashMap<String, Uint128> = HashMap::new();
+  for (validator, weight) in targets {
+    target_amounts.insert(validator, weight * total);
+  }
+
+  let mut sources = vec![];
+  for (validator, amount) in current.iter() {
+    let target = target_amounts.get(validator).copied().unwrap_or_default();
+    let cooldown = REDELEGATION_COOLDOWNS.may_load(deps.storage, validator)?.unwrap_or_default();
+    if *amount > target && cooldown <= now {
+      sources.push((validator.clone(), *amount - target));
+    }
+  }
+  let mut deficits = vec![];
+  for (validator, target) in target_amounts.into_iter() {
+    let amount = current.get(&validator).copied().unwrap_or_default();
+    if target > amount {
+      deficits.push((validator, target - amount));
+    }
+  }
+  // largest differences first, names to keep the order deterministic
+  sources.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+  deficits.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+  let mut msgs = vec![];
+  let mut validators = VALIDATORS.load(deps.storage)?;
+  for (src_validator, mut surplus) in sources {
+    for (dst_validator, deficit) in deficits.iter_mut() {
+      if msgs.len() >= max_moves || surplus.is_zero() {
+        break;
+      }
+      if deficit.is_zero() {
+        continue;
+      }
+
+      let amount = min(surplus, *deficit);
+      msgs.push(_redelegate(deps.storage, &env, &config, &src_validator, dst_validator, amount)?);
+      validators.insert(dst_validator.clone());
+      surplus -= amount;
+      *deficit -= amount;
+    }
+  }
+
+  if msgs.is_empty() {
+    return Err(ContractError::NothingToRebalance);
+  }
+  VALIDATORS.save(deps.storage, &validators)?;
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![("action", "ca/rebalance"), ("moves", &msgs.len().to_string())])
+      .add_messages(msgs),
+  )
+}
+
+fn _redelegate(
+  storage: &mut dyn Storage,
+  env: &Env,
+  config: &Config,
+  src_validator: &str,
+  dst_validator: &str,
+  amount: Uint128,
+) -> Result<CosmosMsg, ContractError> {
+  // the redelegated amount can't be moved again from the destination until it matured
+  REDELEGATION_COOLDOWNS.save(
+    storage,
+    dst_validator,
+    &(env.block.time.seconds() + REDELEGATION_COOLDOWN_S),
+  )?;
+
+  let redelegate_msg = MsgRedelegate {
+    amount: Some(Coin {
+      denom: config.alliance_token_denom.clone(),
+      amount: amount.to_string(),
+    }),
+    delegator_address: env.contract.address.to_string(),
+    validator_src_address: src_validator.to_string(),
+    validator_dst_address: dst_validator.to_string(),
+  };
+  Ok(CosmosMsg::Stargate {
+    type_url: "/alliance.alliance.MsgRedelegate".to_string(),
+    value: Binary::from(redelegate_msg.encode_to_vec()),
+  })
+}
+
 fn claim_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
   let config = CONFIG.load(deps.storage)?;
 
@@ -25,4 +25,19 @@ pub enum ContractError {
 
   #[error("Nothing to take")]
   NothingToTake,
+
+  #[error("Target delegation weights need to sum up to 1")]
+  InvalidTargetWeights,
+
+  #[error("Duplicate validator {0}")]
+  DuplicateValidator(String),
+
+  #[error("Invalid validator {0}")]
+  InvalidValidator(String),
+
+  #[error("No target delegations set")]
+  NoTargetDelegations,
+
+  #[error("Nothing to rebalance")]
+  NothingToRebalance,
 }
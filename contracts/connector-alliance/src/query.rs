@@ -4,12 +4,13 @@ use cosmwasm_std::{to_json_binary, Binary, Decimal, Deps, Env};
 use cw_asset::AssetInfo;
 use ve3_shared::{
   extensions::asset_info_ext::AssetInfoExt,
-  msgs_connector_alliance::{QueryMsg, StateResponse},
+  helpers::alliance::{query_validators_delegations, query_validators_rewards},
+  msgs_connector_alliance::{AllianceDelegation, QueryMsg, StateResponse},
 };
 
 use crate::{
   error::ContractError,
-  state::{CONFIG, STATE, VALIDATORS},
+  state::{CONFIG, STATE, TARGET_DELEGATIONS, VALIDATORS},
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -18,6 +19,9 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
     QueryMsg::Config {} => get_config(deps),
     QueryMsg::State {} => get_state(deps, env),
     QueryMsg::Validators {} => get_validators(deps),
+    QueryMsg::Delegations {} => get_delegations(deps, env),
+    QueryMsg::PendingAllianceRewards {} => get_pending_alliance_rewards(deps, env),
+    QueryMsg::TargetDelegations {} => get_target_delegations(deps),
   }
 }
 
@@ -57,3 +61,40 @@ fn get_validators(deps: Deps) -> Result<Binary, ContractError> {
   let res = VALIDATORS.load(deps.storage)?;
   Ok(to_json_binary(&res)?)
 }
+
+fn get_delegations(deps: Deps, env: Env) -> Result<Binary, ContractError> {
+  let config = CONFIG.load(deps.storage)?;
+  let validators = VALIDATORS.load(deps.storage)?;
+
+  let res = query_validators_delegations(
+    &deps.querier,
+    &env.contract.address,
+    validators,
+    &config.alliance_token_denom,
+  )?
+  .into_iter()
+  .map(|(validator, amount)| AllianceDelegation {
+    validator,
+    amount,
+  })
+  .collect::<Vec<_>>();
+  Ok(to_json_binary(&res)?)
+}
+
+fn get_pending_alliance_rewards(deps: Deps, env: Env) -> Result<Binary, ContractError> {
+  let config = CONFIG.load(deps.storage)?;
+  let validators = VALIDATORS.load(deps.storage)?;
+
+  let res = query_validators_rewards(
+    &deps.querier,
+    &env.contract.address,
+    validators,
+    &config.alliance_token_denom,
+  )?;
+  Ok(to_json_binary(&res)?)
+}
+
+fn get_target_delegations(deps: Deps) -> Result<Binary, ContractError> {
+  let res = TARGET_DELEGATIONS.may_load(deps.storage)?.unwrap_or_default();
+  Ok(to_json_binary(&res)?)
+}
@@ -1,7 +1,11 @@
-use cw_storage_plus::Item;
+use cosmwasm_std::Decimal;
+use cw_storage_plus::{Item, Map};
 use std::collections::HashSet;
 use ve3_shared::msgs_connector_alliance::{Config, State};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const VALIDATORS: Item<HashSet<String>> = Item::new("validators");
 pub const STATE: Item<State> = Item::new("state");
+pub const TARGET_DELEGATIONS: Item<Vec<(String, Decimal)>> = Item::new("target_delegations");
+/// validator -> time until incoming redelegations matured
+pub const REDELEGATION_COOLDOWNS: Map<&str, u64> = Map::new("redelegation_cooldowns");
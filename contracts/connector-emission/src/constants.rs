@@ -1,2 +1,5 @@
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// maximum number of periods returned by the emission schedule query
+pub const MAX_SCHEDULE_PERIODS: u64 = 520;
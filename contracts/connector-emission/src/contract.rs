@@ -1,19 +1,26 @@
 use crate::{
   constants::{CONTRACT_NAME, CONTRACT_VERSION},
   error::{ContractError, ContractResult},
-  state::CONFIG,
+  state::{CONFIG, GAUGE_ACCRUED, GAUGE_LAST_CLAIM_S, STATE, TEAM_VESTINGS},
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response, Uint128};
+use cosmwasm_std::{
+  Api, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
+  Storage, Uint128,
+};
 use cw2::set_contract_version;
+use std::collections::HashSet;
 use ve3_shared::{
   adapters::{global_config_adapter::ConfigExt, mint_proxy::MintProxy},
-  constants::{
-    at_asset_staking, AT_MINT_PROXY, AT_TEAM_WALLET, SECONDS_PER_WEEK, SECONDS_PER_YEAR,
-  },
+  constants::{at_asset_staking, AT_MINT_PROXY, AT_TEAM_WALLET, SECONDS_PER_YEAR},
+  error::SharedError,
   extensions::asset_info_ext::AssetInfoExt,
-  msgs_connector_emission::{Config, ExecuteMsg, InstantiateMsg, RebaseConfg},
+  helpers::governance::{get_period, get_s_from_period},
+  msgs_connector_emission::{
+    AccruedEmission, Config, EmissionSchedule, ExecuteMsg, InstantiateMsg, RebaseConfg,
+    SimulateClaimResponse, State, TeamPayout, TeamVesting,
+  },
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -25,6 +32,12 @@ pub fn instantiate(
 ) -> ContractResult {
   set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+  let emission_schedule = msg.emission_schedule.unwrap_or_default();
+  assert_emission_schedule(&emission_schedule)?;
+  assert_rebase_config(deps.api, &msg.rebase_config)?;
+  let gauges = msg.gauges.unwrap_or_default();
+  assert_gauges(&gauges)?;
+
   CONFIG.save(
     deps.storage,
     &Config {
@@ -37,6 +50,11 @@ pub fn instantiate(
       mint_config: msg.mint_config,
       emission_token: msg.emission_token.check(deps.api, None)?,
       gauge: msg.gauge,
+      emission_schedule,
+      max_total_emission: msg.max_total_emission,
+      cap_reached: false,
+      team_payout: msg.team_payout.unwrap_or_default(),
+      gauges,
     },
   )?;
 
@@ -44,9 +62,10 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> ContractResult {
+pub fn execute(mut deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> ContractResult {
   match msg {
     ExecuteMsg::ClaimRewards {} => claim_rewards(deps, env, info),
+    ExecuteMsg::ClaimTeamVested {} => claim_team_vested(deps, env),
     ExecuteMsg::UpdateConfig {
       emissions_per_s: emissions_per_week,
       team_share,
@@ -54,10 +73,21 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
       mint_config,
       enabled,
       gauge,
+      emission_schedule,
+      max_total_emission,
+      clear_max_total_emission,
+      team_payout,
+      gauges,
     } => {
       let mut config = CONFIG.load(deps.storage)?;
       config.global_config().assert_owner(&deps.querier, &info.sender)?;
 
+      // checkpoints the gauges with the current config, so it runs before any other change
+      if let Some(gauges) = gauges {
+        assert_gauges(&gauges)?;
+        update_gauges(deps.branch(), &mut config, gauges, env.block.time.seconds())?;
+      }
+
       if let Some(emissions_per_week) = emissions_per_week {
         config.emissions_per_week = emissions_per_week;
       }
@@ -67,6 +97,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
       }
 
       if let Some(rebase_config) = rebase_config {
+        assert_rebase_config(deps.api, &rebase_config)?;
         config.rebase_config = rebase_config;
       }
 
@@ -78,6 +109,30 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
         config.mint_config = mint_config;
       }
 
+      if let Some(emission_schedule) = emission_schedule {
+        assert_emission_schedule(&emission_schedule)?;
+        config.emission_schedule = emission_schedule;
+      }
+
+      let previous_cap = config.max_total_emission;
+      if clear_max_total_emission == Some(true) {
+        config.max_total_emission = None;
+      } else if let Some(max_total_emission) = max_total_emission {
+        config.max_total_emission = Some(max_total_emission);
+      }
+      // a connector stopped by the cap resumes once the cap is raised or removed
+      if config.cap_reached
+        && config.max_total_emission.map_or(true, |max| previous_cap.is_some_and(|prev| max > prev))
+      {
+        config.cap_reached = false;
+        config.enabled = true;
+        config.last_claim_s = env.block.time.seconds();
+      }
+
+      if let Some(team_payout) = team_payout {
+        config.team_payout = team_payout;
+      }
+
       if let Some(enabled) = enabled {
         config.enabled = enabled;
         if enabled {
@@ -94,60 +149,57 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
 fn claim_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> ContractResult {
   let mut config = CONFIG.load(deps.storage)?;
 
-  assert_asset_staking_right_gauge(&deps, &info, &config)?;
+  let (gauge, weight) = if config.gauges.is_empty() {
+    match assert_asset_staking_right_gauge(&deps, &info, &config) {
+      Ok(()) => (config.gauge.clone(), Decimal::one()),
+      Err(err) => get_removed_gauge(&deps, &info, &config)?.ok_or(err)?,
+    }
+  } else {
+    get_sender_gauge(&deps, &info, &config)?
+  };
+  let last_claim_s = get_last_claim_s(deps.storage, &config, &gauge)?;
   let asset_staking_addr = info.sender;
 
   if config.enabled {
-    let diff_claim_time_seconds =
-      env.block.time.seconds().checked_sub(config.last_claim_s).unwrap_or_default();
-
-    let emission_amount = Uint128::new(diff_claim_time_seconds.into())
-      .multiply_ratio(config.emissions_per_week, SECONDS_PER_WEEK);
-
-    if !emission_amount.is_zero() {
-      let team_amount = emission_amount * config.team_share;
-      let rebase_amount = match config.rebase_config {
-        RebaseConfg::Fixed(percent) => emission_amount * percent,
-        RebaseConfg::TargetYearlyApy(apy) => {
-          let voting_escrow = config.voting_escrow(&deps.querier)?;
-          let total_fixed = voting_escrow.query_total_fixed(&deps.querier, None)?.fixed;
-
-          // println!("percent: {apy}");
-          // println!("total_fixed: {total_fixed}");
-          apy
-            * Uint128::new(diff_claim_time_seconds.into())
-              .multiply_ratio(total_fixed, SECONDS_PER_YEAR)
-        },
-        RebaseConfg::Dynamic {} => {
-          // weeklyEmissions × (1 - (VP.totalSupply / 10) / TOKEN.totalSupply)ˆ2 × 0.5
+    let now_s = env.block.time.seconds();
+    let mut state = STATE.may_load(deps.storage)?.unwrap_or_default();
+    let accrued = GAUGE_ACCRUED.may_load(deps.storage, &gauge)?.unwrap_or_default();
+    let split = calc_claim(deps.as_ref(), &config, &state, weight, &accrued, last_claim_s, now_s)?;
+    let SimulateClaimResponse {
+      emission_amount,
+      team_amount,
+      rebase_amount,
+      cap_reached,
+    } = split;
+    let total = split.total();
 
-          let voting_escrow = config.voting_escrow(&deps.querier)?;
-          let total_vp = voting_escrow.query_total_vp(&deps.querier, None)?.vp;
-          let token_supply = config.emission_token.total_supply(&deps.querier)?;
+    if cap_reached {
+      // stop emitting once the cap is reached
+      config.enabled = false;
+      config.cap_reached = true;
+      set_last_claim_s(deps.storage, &mut config, &gauge, now_s)?;
+      CONFIG.save(deps.storage, &config)?;
+    }
 
-          let quotient = Decimal::from_ratio(total_vp, token_supply) * Decimal::percent(10);
-          let reverse_quotient = Decimal::one() - quotient;
-          let factor = reverse_quotient * reverse_quotient * Decimal::percent(50);
+    if !total.is_zero() {
+      set_last_claim_s(deps.storage, &mut config, &gauge, now_s)?;
 
-          // println!("total_vp: {total_vp}");
-          // println!("token_supply: {token_supply}");
-          // println!("quotient: {quotient}");
-          // println!("reverse_quotient: {reverse_quotient}");
-          // println!("factor: {factor}");
-
-          emission_amount * factor
-        },
-      };
-      // println!("rebase_amount: {rebase_amount}");
-
-      config.last_claim_s = env.block.time.seconds();
+      state.total_emitted += emission_amount;
+      state.total_team += team_amount;
+      state.total_rebase += rebase_amount;
+      STATE.save(deps.storage, &state)?;
 
       let mut msgs = vec![];
 
-      let total = emission_amount + team_amount + rebase_amount;
       match config.mint_config {
         ve3_shared::msgs_connector_emission::MintConfig::UseBalance => {
-          // nothing to do, as we expect the balance already to be there in the contract
+          // the balance is expected to be there already, but must not eat into unvested team tokens
+          let balance =
+            config.emission_token.query_balance(&deps.querier, env.contract.address.clone())?;
+          let reserved = team_vesting_unclaimed(deps.storage)?;
+          if balance < reserved + total {
+            Err(SharedError::InsufficientBalance("contract balance".to_string()))?;
+          }
         },
         ve3_shared::msgs_connector_emission::MintConfig::MintDirect => {
           match &config.emission_token {
@@ -175,13 +227,37 @@ fn claim_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> ContractResult {
         },
       };
 
-      msgs.push(
-        config.emission_token.with_balance(emission_amount).transfer_msg(asset_staking_addr)?,
-      );
+      if !emission_amount.is_zero() {
+        msgs.push(
+          config.emission_token.with_balance(emission_amount).transfer_msg(asset_staking_addr)?,
+        );
+      }
 
       if !team_amount.is_zero() {
-        let team_wallet = config.global_config().get_address(&deps.querier, AT_TEAM_WALLET)?;
-        msgs.push(config.emission_token.with_balance(team_amount).transfer_msg(team_wallet)?)
+        match config.team_payout {
+          TeamPayout::Direct => {
+            let team_wallet = config.global_config().get_address(&deps.querier, AT_TEAM_WALLET)?;
+            msgs.push(config.emission_token.with_balance(team_amount).transfer_msg(team_wallet)?)
+          },
+          TeamPayout::Vesting {
+            duration_s,
+          } => {
+            // everything emitted in a period starts vesting at the beginning of the next period.
+            // an existing tranche keeps its end, so a changed duration applies from the next period.
+            let period = get_period(env.block.time.seconds())?;
+            let start_s = get_s_from_period(period + 1);
+            TEAM_VESTINGS.update(deps.storage, period, |vesting| -> StdResult<_> {
+              let mut vesting = vesting.unwrap_or(TeamVesting {
+                start_s,
+                end_s: start_s + duration_s,
+                amount: Uint128::zero(),
+                claimed: Uint128::zero(),
+              });
+              vesting.amount += team_amount;
+              Ok(vesting)
+            })?;
+          },
+        }
       }
 
       if !rebase_amount.is_zero() {
@@ -193,19 +269,373 @@ fn claim_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> ContractResult {
 
       CONFIG.save(deps.storage, &config)?;
 
+      let mut response = Response::default()
+        .add_attribute("action", "ce/claim_rewards")
+        .add_attribute("gauge", gauge)
+        .add_attribute("emission_amount", emission_amount)
+        .add_attribute("rebase_amount", rebase_amount)
+        .add_attribute("team_amount", team_amount);
+
+      if cap_reached {
+        response = response.add_attribute("cap_reached", "true");
+      }
+
+      return Ok(response.add_messages(msgs));
+    }
+
+    if cap_reached {
       return Ok(
         Response::default()
-          .add_attribute("action", "ce/claim_rewards")
-          .add_attribute("emission_amount", emission_amount)
-          .add_attribute("rebase_amount", rebase_amount)
-          .add_attribute("team_amount", team_amount)
-          .add_messages(msgs),
+          .add_attribute("action", "ce/claim_rewards_noop")
+          .add_attribute("cap_reached", "true"),
       );
     }
   }
   Ok(Response::default().add_attribute("action", "ce/claim_rewards_noop"))
 }
 
+/// Calculates the emission, team and rebase amounts for the time between from_s and to_s
+/// including the accrued amounts of the gauge, reduced to the remaining max_total_emission.
+/// The weight is the share of the gauge in multi gauge mode.
+pub(crate) fn calc_claim(
+  deps: Deps,
+  config: &Config,
+  state: &State,
+  weight: Decimal,
+  accrued: &AccruedEmission,
+  from_s: u64,
+  to_s: u64,
+) -> Result<SimulateClaimResponse, ContractError> {
+  let mut split = calc_accrued(deps, config, weight, from_s, to_s)?;
+  split.add(accrued);
+
+  let AccruedEmission {
+    mut emission_amount,
+    mut team_amount,
+    mut rebase_amount,
+  } = split;
+
+  if emission_amount.is_zero() {
+    return Ok(SimulateClaimResponse::default());
+  }
+
+  let mut cap_reached = false;
+  if let Some(max_total_emission) = config.max_total_emission {
+    let remaining = max_total_emission.saturating_sub(state.total());
+    let total = emission_amount + team_amount + rebase_amount;
+    if total >= remaining {
+      // scale down all parts to the remaining amount
+      emission_amount = emission_amount.multiply_ratio(remaining, total);
+      team_amount = team_amount.multiply_ratio(remaining, total);
+      rebase_amount = rebase_amount.multiply_ratio(remaining, total);
+      cap_reached = true;
+    }
+  }
+
+  Ok(SimulateClaimResponse {
+    emission_amount,
+    team_amount,
+    rebase_amount,
+    cap_reached,
+  })
+}
+
+/// Calculates the emission, team and rebase amounts a gauge accrues between from_s and to_s.
+fn calc_accrued(
+  deps: Deps,
+  config: &Config,
+  weight: Decimal,
+  from_s: u64,
+  to_s: u64,
+) -> Result<AccruedEmission, ContractError> {
+  let diff_claim_time_seconds = to_s.checked_sub(from_s).unwrap_or_default();
+
+  let emission_amount =
+    config.emission_schedule.emission_between(config.emissions_per_week, from_s, to_s)? * weight;
+
+  if emission_amount.is_zero() {
+    return Ok(AccruedEmission::default());
+  }
+
+  let team_amount = emission_amount * config.team_share;
+  let rebase_amount = match &config.rebase_config {
+    RebaseConfg::Fixed(percent) => emission_amount * *percent,
+    RebaseConfg::TargetYearlyApy(apy) => {
+      let voting_escrow = config.voting_escrow(&deps.querier)?;
+      let total_fixed = voting_escrow.query_total_fixed(&deps.querier, None)?.fixed;
+
+      // println!("percent: {apy}");
+      // println!("total_fixed: {total_fixed}");
+      *apy
+        * Uint128::new(diff_claim_time_seconds.into()).multiply_ratio(total_fixed, SECONDS_PER_YEAR)
+        * weight
+    },
+    RebaseConfg::Dynamic {
+      divisor,
+      exponent,
+      multiplier,
+      max,
+    } => {
+      // weeklyEmissions × min((1 - (VP.totalSupply / divisor) / TOKEN.totalSupply)ˆexponent × multiplier, max)
+
+      let token_supply = config.emission_token.total_supply(&deps.querier)?;
+
+      if token_supply.is_zero() {
+        Uint128::zero()
+      } else {
+        let voting_escrow = config.voting_escrow(&deps.querier)?;
+        let total_vp = voting_escrow.query_total_vp(&deps.querier, None)?.vp;
+
+        let quotient = Decimal::from_ratio(total_vp, token_supply)
+          .checked_div(*divisor)
+          .map_err(|e| StdError::generic_err(e.to_string()))?;
+        let reverse_quotient = Decimal::one().checked_sub(quotient).unwrap_or_default();
+        let mut factor =
+          reverse_quotient.checked_pow(*exponent).map_err(StdError::from)? * *multiplier;
+        if let Some(max) = max {
+          factor = factor.min(*max);
+        }
+
+        // println!("total_vp: {total_vp}");
+        // println!("token_supply: {token_supply}");
+        // println!("quotient: {quotient}");
+        // println!("reverse_quotient: {reverse_quotient}");
+        // println!("factor: {factor}");
+
+        emission_amount * factor
+      }
+    },
+    RebaseConfg::LockedRatio {
+      target_ratio,
+      max_rebase,
+      excluded_addrs,
+    } => {
+      // weeklyEmissions × max_rebase × (1 - lockRatio / target_ratio)
+
+      let voting_escrow = config.voting_escrow(&deps.querier)?;
+      let total_fixed = voting_escrow.query_total_fixed(&deps.querier, None)?.fixed;
+
+      let mut circulating_supply = config.emission_token.total_supply(&deps.querier)?;
+      for addr in excluded_addrs {
+        let balance = config.emission_token.query_balance(&deps.querier, addr)?;
+        circulating_supply = circulating_supply.saturating_sub(balance);
+      }
+
+      if circulating_supply.is_zero() {
+        Uint128::zero()
+      } else {
+        let lock_ratio = Decimal::from_ratio(total_fixed, circulating_supply);
+        let missing_ratio = target_ratio.checked_sub(lock_ratio).unwrap_or_default();
+        emission_amount * (*max_rebase * (missing_ratio / *target_ratio))
+      }
+    },
+  };
+  // println!("rebase_amount: {rebase_amount}");
+
+  Ok(AccruedEmission {
+    emission_amount,
+    team_amount,
+    rebase_amount,
+  })
+}
+
+fn get_sender_gauge(
+  deps: &DepsMut,
+  info: &MessageInfo,
+  config: &Config,
+) -> Result<(String, Decimal), ContractError> {
+  for (gauge, weight) in config.gauges.iter() {
+    let asset_staking = config.global_config().get_address(&deps.querier, &at_asset_staking(gauge));
+    if asset_staking.is_ok_and(|asset_staking| asset_staking == info.sender) {
+      return Ok((gauge.clone(), *weight));
+    }
+  }
+  get_removed_gauge(deps, info, config)?.ok_or_else(|| SharedError::Unauthorized {}.into())
+}
+
+/// Removed gauges can still claim what they accrued before, with a weight of zero.
+fn get_removed_gauge(
+  deps: &DepsMut,
+  info: &MessageInfo,
+  config: &Config,
+) -> Result<Option<(String, Decimal)>, ContractError> {
+  for gauge in GAUGE_ACCRUED.keys(deps.storage, None, None, Order::Ascending) {
+    let gauge = gauge?;
+    let asset_staking =
+      config.global_config().get_address(&deps.querier, &at_asset_staking(&gauge));
+    if asset_staking.is_ok_and(|asset_staking| asset_staking == info.sender) {
+      return Ok(Some((gauge, Decimal::zero())));
+    }
+  }
+  Ok(None)
+}
+
+pub(crate) fn get_last_claim_s(
+  storage: &dyn Storage,
+  config: &Config,
+  gauge: &str,
+) -> StdResult<u64> {
+  if config.gauges.is_empty() {
+    Ok(config.last_claim_s)
+  } else {
+    // config.last_claim_s is the earliest claim time for all gauges (set when enabling)
+    Ok(GAUGE_LAST_CLAIM_S.may_load(storage, gauge)?.unwrap_or_default().max(config.last_claim_s))
+  }
+}
+
+fn set_last_claim_s(
+  storage: &mut dyn Storage,
+  config: &mut Config,
+  gauge: &str,
+  now_s: u64,
+) -> Result<(), ContractError> {
+  if config.gauges.is_empty() {
+    if gauge == config.gauge {
+      config.last_claim_s = now_s;
+    }
+  } else if config.gauges.iter().any(|(active, _)| active == gauge) {
+    GAUGE_LAST_CLAIM_S.save(storage, gauge, &now_s)?;
+  }
+  // the accrued amount is part of every claim
+  GAUGE_ACCRUED.remove(storage, gauge);
+  Ok(())
+}
+
+fn update_gauges(
+  deps: DepsMut,
+  config: &mut Config,
+  gauges: Vec<(String, Decimal)>,
+  now_s: u64,
+) -> Result<(), ContractError> {
+  let previous = if config.gauges.is_empty() {
+    vec![(config.gauge.clone(), Decimal::one())]
+  } else {
+    config.gauges.clone()
+  };
+
+  if config.enabled {
+    // checkpoint the emissions at the previous weights, the new weights only apply from now on
+    for (gauge, weight) in previous.iter() {
+      let last_claim_s = get_last_claim_s(deps.storage, config, gauge)?;
+      let accrued = calc_accrued(deps.as_ref(), config, *weight, last_claim_s, now_s)?;
+      if !accrued.is_zero() {
+        GAUGE_ACCRUED.update(deps.storage, gauge, |existing| -> StdResult<_> {
+          let mut existing = existing.unwrap_or_default();
+          existing.add(&accrued);
+          Ok(existing)
+        })?;
+      }
+    }
+  }
+
+  GAUGE_LAST_CLAIM_S.clear(deps.storage);
+  if gauges.is_empty() {
+    config.last_claim_s = now_s;
+  } else {
+    for (gauge, _) in gauges.iter() {
+      GAUGE_LAST_CLAIM_S.save(deps.storage, gauge, &now_s)?;
+    }
+  }
+
+  config.gauges = gauges;
+  Ok(())
+}
+
+fn assert_gauges(gauges: &[(String, Decimal)]) -> Result<(), ContractError> {
+  if gauges.is_empty() {
+    return Ok(());
+  }
+
+  let mut unique = HashSet::new();
+  let mut total = Decimal::zero();
+  for (gauge, weight) in gauges.iter() {
+    if !unique.insert(gauge) {
+      return Err(ContractError::DuplicateGauge(gauge.to_string()));
+    }
+    total = total.checked_add(*weight).map_err(StdError::from)?;
+  }
+  if total != Decimal::one() {
+    return Err(ContractError::InvalidGaugeWeights);
+  }
+  Ok(())
+}
+
+fn assert_rebase_config(api: &dyn Api, rebase_config: &RebaseConfg) -> Result<(), ContractError> {
+  match rebase_config {
+    RebaseConfg::Fixed(_) | RebaseConfg::TargetYearlyApy(_) => (),
+    RebaseConfg::Dynamic {
+      divisor,
+      ..
+    } => {
+      if divisor.is_zero() {
+        return Err(ContractError::InvalidRebaseConfig("divisor must not be zero".to_string()));
+      }
+    },
+    RebaseConfg::LockedRatio {
+      target_ratio,
+      excluded_addrs,
+      ..
+    } => {
+      if target_ratio.is_zero() {
+        return Err(ContractError::InvalidRebaseConfig(
+          "target_ratio must not be zero".to_string(),
+        ));
+      }
+      for addr in excluded_addrs {
+        api.addr_validate(addr)?;
+      }
+    },
+  }
+  Ok(())
+}
+
+fn team_vesting_unclaimed(storage: &dyn Storage) -> StdResult<Uint128> {
+  TEAM_VESTINGS.range(storage, None, None, Order::Ascending).try_fold(
+    Uint128::zero(),
+    |acc, item| -> StdResult<_> {
+      let (_, vesting) = item?;
+      Ok(acc + vesting.amount.saturating_sub(vesting.claimed))
+    },
+  )
+}
+
+fn claim_team_vested(deps: DepsMut, env: Env) -> ContractResult {
+  let config = CONFIG.load(deps.storage)?;
+  let now_s = env.block.time.seconds();
+
+  let vestings = TEAM_VESTINGS
+    .range(deps.storage, None, None, Order::Ascending)
+    .collect::<StdResult<Vec<_>>>()?;
+
+  let mut amount = Uint128::zero();
+  for (period, mut vesting) in vestings {
+    let claimable = vesting.claimable(now_s);
+    if claimable.is_zero() {
+      continue;
+    }
+
+    amount += claimable;
+    vesting.claimed += claimable;
+    if vesting.claimed >= vesting.amount {
+      TEAM_VESTINGS.remove(deps.storage, period);
+    } else {
+      TEAM_VESTINGS.save(deps.storage, period, &vesting)?;
+    }
+  }
+
+  if amount.is_zero() {
+    return Err(ContractError::NothingToClaim {});
+  }
+
+  let team_wallet = config.global_config().get_address(&deps.querier, AT_TEAM_WALLET)?;
+  Ok(
+    Response::default()
+      .add_attribute("action", "ce/claim_team_vested")
+      .add_attribute("amount", amount)
+      .add_message(config.emission_token.with_balance(amount).transfer_msg(team_wallet)?),
+  )
+}
+
 fn assert_asset_staking_right_gauge(
   deps: &DepsMut,
   info: &MessageInfo,
@@ -218,3 +648,32 @@ fn assert_asset_staking_right_gauge(
   )?;
   Ok(())
 }
+
+fn assert_emission_schedule(emission_schedule: &EmissionSchedule) -> Result<(), ContractError> {
+  match emission_schedule {
+    EmissionSchedule::Constant => (),
+    EmissionSchedule::Steps(steps) => {
+      if steps.is_empty() {
+        return Err(ContractError::InvalidEmissionSchedule(
+          "steps must not be empty, use Constant instead".to_string(),
+        ));
+      }
+      if steps.windows(2).any(|w| w[0].0 >= w[1].0) {
+        return Err(ContractError::InvalidEmissionSchedule(
+          "steps must be sorted by start_period".to_string(),
+        ));
+      }
+    },
+    EmissionSchedule::Decay {
+      weekly_decay,
+      ..
+    } => {
+      if *weekly_decay > Decimal::one() {
+        return Err(ContractError::InvalidEmissionSchedule(
+          "weekly_decay must not be greater than 1".to_string(),
+        ));
+      }
+    },
+  }
+  Ok(())
+}
@@ -19,4 +19,22 @@ pub enum ContractError {
 
   #[error("{0}")]
   SharedError(#[from] SharedError),
+
+  #[error("Invalid emission schedule: {0}")]
+  InvalidEmissionSchedule(String),
+
+  #[error("Invalid rebase config: {0}")]
+  InvalidRebaseConfig(String),
+
+  #[error("Gauge weights need to sum up to 1")]
+  InvalidGaugeWeights,
+
+  #[error("Duplicate gauge {0}")]
+  DuplicateGauge(String),
+
+  #[error("Invalid period range: {0}")]
+  InvalidPeriodRange(String),
+
+  #[error("Nothing to claim")]
+  NothingToClaim {},
 }
@@ -1,13 +1,104 @@
+use crate::constants::MAX_SCHEDULE_PERIODS;
+use crate::contract::{calc_claim, get_last_claim_s};
 use crate::error::ContractError;
-use crate::state::CONFIG;
+use crate::state::{CONFIG, GAUGE_ACCRUED, STATE, TEAM_VESTINGS};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_json_binary, Binary, Deps, Env};
-use ve3_shared::msgs_connector_emission::QueryMsg;
+use cosmwasm_std::{
+  to_json_binary, Binary, Decimal, Deps, Env, Order, StdError, StdResult, Uint128, Uint64,
+};
+use ve3_shared::error::SharedError;
+use ve3_shared::msgs_connector_emission::{
+  AccruedEmission, QueryMsg, SimulateClaimResponse, TeamVestingResponse,
+};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
   match msg {
     QueryMsg::Config {} => Ok(to_json_binary(&CONFIG.load(deps.storage)?)?),
+    QueryMsg::State {} => Ok(to_json_binary(&STATE.may_load(deps.storage)?.unwrap_or_default())?),
+    QueryMsg::SimulateClaim {
+      seconds,
+      gauge,
+    } => Ok(to_json_binary(&simulate_claim(deps, env, seconds, gauge)?)?),
+    QueryMsg::TeamVesting {} => Ok(to_json_binary(&get_team_vesting(deps, env)?)?),
+    QueryMsg::EmissionSchedule {
+      from_period,
+      to_period,
+    } => Ok(to_json_binary(&get_emission_schedule(deps, from_period, to_period)?)?),
   }
 }
+
+fn get_emission_schedule(
+  deps: Deps,
+  from_period: u64,
+  to_period: u64,
+) -> Result<Vec<(u64, Uint128)>, ContractError> {
+  if to_period < from_period {
+    return Err(ContractError::InvalidPeriodRange(
+      "to_period must not be before from_period".to_string(),
+    ));
+  }
+  if to_period - from_period >= MAX_SCHEDULE_PERIODS {
+    return Err(ContractError::InvalidPeriodRange(format!(
+      "at most {MAX_SCHEDULE_PERIODS} periods"
+    )));
+  }
+
+  let config = CONFIG.load(deps.storage)?;
+  Ok(
+    (from_period..=to_period)
+      .map(|period| {
+        (period, config.emission_schedule.emissions_per_week(config.emissions_per_week, period))
+      })
+      .collect(),
+  )
+}
+
+fn get_team_vesting(deps: Deps, env: Env) -> Result<TeamVestingResponse, ContractError> {
+  let vestings = TEAM_VESTINGS
+    .range(deps.storage, None, None, Order::Ascending)
+    .collect::<StdResult<Vec<_>>>()?;
+
+  let claimable =
+    vestings.iter().map(|(_, vesting)| vesting.claimable(env.block.time.seconds())).sum();
+
+  Ok(TeamVestingResponse {
+    vestings,
+    claimable,
+  })
+}
+
+fn simulate_claim(
+  deps: Deps,
+  env: Env,
+  seconds: u64,
+  gauge: Option<String>,
+) -> Result<SimulateClaimResponse, ContractError> {
+  let config = CONFIG.load(deps.storage)?;
+  let state = STATE.may_load(deps.storage)?.unwrap_or_default();
+  let now_s = env.block.time.seconds();
+  let to_s = Uint64::new(now_s).checked_add(Uint64::new(seconds)).map_err(StdError::from)?.u64();
+
+  let (weight, accrued, from_s) = match gauge {
+    None => (Decimal::one(), AccruedEmission::default(), now_s),
+    Some(gauge) => {
+      let weight = if config.gauges.is_empty() {
+        (gauge == config.gauge).then(Decimal::one)
+      } else {
+        config.gauges.iter().find(|(active, _)| *active == gauge).map(|(_, weight)| *weight)
+      };
+      let accrued = GAUGE_ACCRUED.may_load(deps.storage, &gauge)?;
+      let weight = match (weight, &accrued) {
+        (Some(weight), _) => weight,
+        // a removed gauge only claims what it accrued before
+        (None, Some(_)) => Decimal::zero(),
+        (None, None) => return Err(SharedError::NotFound(format!("gauge {gauge}")).into()),
+      };
+      let from_s = get_last_claim_s(deps.storage, &config, &gauge)?;
+      (weight, accrued.unwrap_or_default(), from_s)
+    },
+  };
+
+  calc_claim(deps, &config, &state, weight, &accrued, from_s, to_s)
+}
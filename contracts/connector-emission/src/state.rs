@@ -1,4 +1,14 @@
-use cw_storage_plus::Item;
-use ve3_shared::msgs_connector_emission::Config;
+use cw_storage_plus::{Item, Map};
+use ve3_shared::msgs_connector_emission::{AccruedEmission, Config, State, TeamVesting};
 
 pub const CONFIG: Item<Config> = Item::new("config");
+pub const STATE: Item<State> = Item::new("state");
+
+/// team share vestings by the period in which it was emitted
+pub const TEAM_VESTINGS: Map<u64, TeamVesting> = Map::new("team_vestings");
+
+/// last claim time by gauge in multi gauge mode
+pub const GAUGE_LAST_CLAIM_S: Map<&str, u64> = Map::new("gauge_last_claim_s");
+
+/// emissions accrued by a gauge before its weight was changed
+pub const GAUGE_ACCRUED: Map<&str, AccruedEmission> = Map::new("gauge_accrued");
@@ -1,2 +1,7 @@
+use ve3_shared::constants::SECONDS_PER_DAY;
+
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// upper bound for the delay of an address type, a typo must not lock it forever
+pub const MAX_DELAY_S: u64 = 30 * SECONDS_PER_DAY;
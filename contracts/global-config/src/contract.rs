@@ -1,14 +1,16 @@
 use crate::{
-  constants::{CONTRACT_NAME, CONTRACT_VERSION},
-  error::ContractResult,
-  state::{ADDRESSES, ADDRESS_LIST},
+  constants::{CONTRACT_NAME, CONTRACT_VERSION, MAX_DELAY_S},
+  error::{ContractError, ContractResult},
+  state::{ADDRESSES, ADDRESS_LIST, DELAYS, PENDING_CHANGES},
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_json_string, Addr, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+  to_json_string, Addr, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Storage, Uint64,
+};
 use cw2::set_contract_version;
 use cw_ownable::update_ownership;
-use ve3_shared::msgs_global_config::{ExecuteMsg, InstantiateMsg};
+use ve3_shared::msgs_global_config::{ExecuteMsg, InstantiateMsg, PendingChange};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -43,6 +45,18 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
     ExecuteMsg::ClearLists {
       lists,
     } => clear_lists(deps, info.sender, lists),
+    ExecuteMsg::SetDelays {
+      delays,
+    } => set_delays(deps, info.sender, delays),
+    ExecuteMsg::ProposeAddresses {
+      addresses,
+    } => propose_addresses(deps, env, info.sender, addresses),
+    ExecuteMsg::ApplyAddresses {
+      address_types,
+    } => apply_addresses(deps, env, address_types),
+    ExecuteMsg::CancelProposal {
+      address_types,
+    } => cancel_proposal(deps, info.sender, address_types),
   }
 }
 
@@ -55,6 +69,7 @@ fn set_addresses(
   cw_ownable::assert_owner(deps.storage, &sender)?;
 
   for (address_type, address) in addresses {
+    assert_not_timelocked(deps.storage, &address_type)?;
     if address.is_empty() {
       ADDRESSES.remove(deps.storage, address_type);
     } else {
@@ -73,6 +88,7 @@ fn clear_addresses(deps: DepsMut, sender: Addr, addresses: Vec<String>) -> Contr
   cw_ownable::assert_owner(deps.storage, &sender)?;
 
   for address_type in addresses {
+    assert_not_timelocked(deps.storage, &address_type)?;
     ADDRESSES.remove(deps.storage, address_type);
   }
 
@@ -89,6 +105,107 @@ fn clear_lists(deps: DepsMut, sender: Addr, addresses: Vec<String>) -> ContractR
   Ok(Response::new().add_attribute("action", "clear_lists"))
 }
 
+fn set_delays(deps: DepsMut, sender: Addr, delays: Vec<(String, u64)>) -> ContractResult {
+  cw_ownable::assert_owner(deps.storage, &sender)?;
+
+  for (address_type, delay) in delays {
+    if delay > MAX_DELAY_S {
+      return Err(ContractError::DelayTooLong(address_type, MAX_DELAY_S));
+    }
+    let current = DELAYS.may_load(deps.storage, address_type.clone())?.unwrap_or_default();
+    if delay < current {
+      return Err(ContractError::DelayDecreaseNotAllowed(address_type));
+    }
+    DELAYS.save(deps.storage, address_type.clone(), &delay)?;
+
+    // a pending change must wait for the raised delay as well
+    if let Some(mut change) = PENDING_CHANGES.may_load(deps.storage, address_type.clone())? {
+      change.apply_after_s = change.apply_after_s.max(add_delay(change.proposed_s, delay)?);
+      PENDING_CHANGES.save(deps.storage, address_type, &change)?;
+    }
+  }
+
+  Ok(Response::new().add_attribute("action", "set_delays"))
+}
+
+fn propose_addresses(
+  deps: DepsMut,
+  env: Env,
+  sender: Addr,
+  addresses: Vec<(String, String)>,
+) -> ContractResult {
+  cw_ownable::assert_owner(deps.storage, &sender)?;
+
+  let now_s = env.block.time.seconds();
+  for (address_type, address) in addresses {
+    let address = if address.is_empty() {
+      None
+    } else {
+      Some(deps.api.addr_validate(&address)?)
+    };
+    let delay = DELAYS.may_load(deps.storage, address_type.clone())?.unwrap_or_default();
+
+    PENDING_CHANGES.save(
+      deps.storage,
+      address_type.clone(),
+      &PendingChange {
+        address_type,
+        address,
+        proposed_s: now_s,
+        apply_after_s: add_delay(now_s, delay)?,
+      },
+    )?;
+  }
+
+  Ok(Response::new().add_attribute("action", "propose_addresses"))
+}
+
+fn apply_addresses(deps: DepsMut, env: Env, address_types: Vec<String>) -> ContractResult {
+  let now_s = env.block.time.seconds();
+  for address_type in address_types {
+    let change = PENDING_CHANGES
+      .may_load(deps.storage, address_type.clone())?
+      .ok_or_else(|| ContractError::NoPendingChange(address_type.clone()))?;
+
+    if now_s < change.apply_after_s {
+      return Err(ContractError::TimelockNotExpired(address_type, change.apply_after_s));
+    }
+
+    match change.address {
+      Some(address) => ADDRESSES.save(deps.storage, address_type.clone(), &address)?,
+      None => ADDRESSES.remove(deps.storage, address_type.clone()),
+    }
+    PENDING_CHANGES.remove(deps.storage, address_type);
+  }
+
+  Ok(Response::new().add_attribute("action", "apply_addresses"))
+}
+
+fn cancel_proposal(deps: DepsMut, sender: Addr, address_types: Vec<String>) -> ContractResult {
+  cw_ownable::assert_owner(deps.storage, &sender)?;
+
+  for address_type in address_types {
+    if !PENDING_CHANGES.has(deps.storage, address_type.clone()) {
+      return Err(ContractError::NoPendingChange(address_type));
+    }
+    PENDING_CHANGES.remove(deps.storage, address_type);
+  }
+
+  Ok(Response::new().add_attribute("action", "cancel_proposal"))
+}
+
+fn add_delay(time_s: u64, delay: u64) -> StdResult<u64> {
+  Ok(Uint64::new(time_s).checked_add(Uint64::new(delay)).map_err(StdError::from)?.u64())
+}
+
+fn assert_not_timelocked(storage: &dyn Storage, address_type: &str) -> Result<(), ContractError> {
+  let delay = DELAYS.may_load(storage, address_type.to_string())?.unwrap_or_default();
+  if delay > 0 {
+    return Err(ContractError::AddressTypeTimelocked(address_type.to_string()));
+  }
+  Ok(())
+}
+
 fn _set_address_list(deps: &mut DepsMut, address_type: String, list: Vec<String>) -> StdResult<()> {
   let mut addresses_addr = vec![];
   for address in list {
@@ -15,4 +15,19 @@ pub enum ContractError {
 
   #[error("{0}")]
   SharedError(#[from] SharedError),
+
+  #[error("Address type {0} is timelocked, use ProposeAddresses")]
+  AddressTypeTimelocked(String),
+
+  #[error("Delay of address type {0} can not be decreased")]
+  DelayDecreaseNotAllowed(String),
+
+  #[error("Delay of address type {0} can be at most {1} seconds")]
+  DelayTooLong(String, u64),
+
+  #[error("No pending change for address type {0}")]
+  NoPendingChange(String),
+
+  #[error("Pending change for address type {0} can be applied after {1}")]
+  TimelockNotExpired(String, u64),
 }
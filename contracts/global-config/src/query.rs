@@ -1,5 +1,5 @@
 use crate::error::ContractError;
-use crate::state::{ADDRESSES, ADDRESS_LIST};
+use crate::state::{ADDRESSES, ADDRESS_LIST, DELAYS, PENDING_CHANGES};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdResult};
@@ -7,7 +7,9 @@ use cw_ownable::get_ownership;
 use cw_storage_plus::Bound;
 use ve3_shared::constants::{DEFAULT_LIMIT, MAX_LIMIT};
 use ve3_shared::error::SharedError;
-use ve3_shared::msgs_global_config::{AddressListResponse, AddressResponse, QueryMsg};
+use ve3_shared::msgs_global_config::{
+  AddressListResponse, AddressResponse, PendingChange, QueryMsg,
+};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
@@ -25,6 +27,11 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
     QueryMsg::AddressList(address_type) => {
       Ok(to_json_binary(&query_address_list(deps, address_type)?)?)
     },
+    QueryMsg::PendingChanges {
+      start_after,
+      limit,
+    } => Ok(to_json_binary(&query_pending_changes(deps, start_after, limit)?)?),
+    QueryMsg::Delays {} => Ok(to_json_binary(&query_delays(deps)?)?),
   }
 }
 
@@ -67,3 +74,22 @@ fn query_all_addresses(
     })
     .collect()
 }
+
+fn query_pending_changes(
+  deps: Deps,
+  start_after: Option<String>,
+  limit: Option<u32>,
+) -> StdResult<Vec<PendingChange>> {
+  let start = start_after.map(Bound::exclusive);
+  let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+  PENDING_CHANGES
+    .range(deps.storage, start, None, Order::Ascending)
+    .take(limit)
+    .map(|item| Ok(item?.1))
+    .collect()
+}
+
+fn query_delays(deps: Deps) -> StdResult<Vec<(String, u64)>> {
+  DELAYS.range(deps.storage, None, None, Order::Ascending).collect()
+}
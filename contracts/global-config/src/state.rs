@@ -1,7 +1,10 @@
 use cosmwasm_std::Addr;
 use cw_ownable::Ownership;
 use cw_storage_plus::{Item, Map};
+use ve3_shared::msgs_global_config::PendingChange;
 
 pub const OWNERSHIP: Item<Ownership<Addr>> = Item::new("ownership");
 pub const ADDRESSES: Map<String, Addr> = Map::new("addresses");
 pub const ADDRESS_LIST: Map<String, Vec<Addr>> = Map::new("address_list");
+pub const DELAYS: Map<String, u64> = Map::new("delays");
+pub const PENDING_CHANGES: Map<String, PendingChange> = Map::new("pending_changes");
@@ -1,15 +1,17 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_json_binary, Addr, Binary, Decimal, Deps, Env, Order, StdError};
+use cosmwasm_std::{to_json_binary, Addr, Binary, Deps, Env, Order, StdError};
 use cw_asset::{Asset, AssetInfo, AssetInfoUnchecked};
 use cw_storage_plus::Bound;
 use ve3_shared::{
-  adapters::{pair::Pair, router::Router},
   constants::{DEFAULT_LIMIT, MAX_LIMIT},
-  extensions::asset_info_ext::AssetInfoExt,
-  helpers::assets::Assets,
+  helpers::{
+    alliance::{query_validators_delegations, query_validators_rewards},
+    assets::Assets,
+  },
   msgs_phoenix_treasury::{
-    BalancesResponse, Direction, Oracle, OraclesResponse, QueryMsg, TreasuryAction,
+    AllianceDelegation, BalancesResponse, Direction, Oracle, OraclesResponse, QueryMsg,
+    TreasuryAction,
   },
 };
 
@@ -24,6 +26,8 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
     QueryMsg::Config {} => get_config(deps),
     QueryMsg::State {} => get_state(deps, env),
     QueryMsg::Validators {} => get_validators(deps),
+    QueryMsg::Delegations {} => get_delegations(deps, env),
+    QueryMsg::PendingAllianceRewards {} => get_pending_alliance_rewards(deps, env),
     QueryMsg::Actions {
       limit,
       start_after,
@@ -61,6 +65,38 @@ fn get_validators(deps: Deps) -> Result<Binary, ContractError> {
   Ok(to_json_binary(&res)?)
 }
 
+fn get_delegations(deps: Deps, env: Env) -> Result<Binary, ContractError> {
+  let config = CONFIG.load(deps.storage)?;
+  let validators = VALIDATORS.load(deps.storage)?;
+
+  let res = query_validators_delegations(
+    &deps.querier,
+    &env.contract.address,
+    validators,
+    &config.alliance_token_denom,
+  )?
+  .into_iter()
+  .map(|(validator, amount)| AllianceDelegation {
+    validator,
+    amount,
+  })
+  .collect::<Vec<_>>();
+  Ok(to_json_binary(&res)?)
+}
+
+fn get_pending_alliance_rewards(deps: Deps, env: Env) -> Result<Binary, ContractError> {
+  let config = CONFIG.load(deps.storage)?;
+  let validators = VALIDATORS.load(deps.storage)?;
+
+  let res = query_validators_rewards(
+    &deps.querier,
+    &env.contract.address,
+    validators,
+    &config.alliance_token_denom,
+  )?;
+  Ok(to_json_binary(&res)?)
+}
+
 fn get_actions(
   deps: Deps,
   start_after: Option<u64>,
@@ -182,52 +218,7 @@ fn get_oracle_prices(
   let mut prices: OraclesResponse = vec![];
 
   for (info, oracle) in oracles {
-    let price = match oracle {
-      Oracle::Usdc => Decimal::one(),
-      Oracle::Pair {
-        contract,
-        simulation_amount,
-        from_decimals,
-      } => {
-        let result = Pair(contract).query_simulate(
-          &deps.querier,
-          false,
-          info.with_balance(simulation_amount),
-          None,
-        )?;
-
-        Decimal::from_ratio(result.return_amount, simulation_amount)
-          * Decimal::from_ratio(u32::pow(10, from_decimals.unwrap_or(6)), u32::pow(10, 6))
-      },
-
-      Oracle::Route {
-        contract,
-        path,
-        simulation_amount,
-        from_decimals,
-      } => {
-        let result = Router(contract).query_simulate(
-          &deps.querier,
-          info.with_balance(simulation_amount),
-          path,
-        )?;
-
-        Decimal::from_ratio(result.amount, simulation_amount)
-          * Decimal::from_ratio(u32::pow(10, from_decimals.unwrap_or(6)), u32::pow(10, 6))
-      },
-      Oracle::RouteAsset {
-        contract,
-        path,
-        simulation_amount,
-        from_decimals,
-      } => {
-        let result =
-          Router(contract).query_simulate(&deps.querier, simulation_amount.clone(), path)?;
-
-        Decimal::from_ratio(result.amount, simulation_amount.amount)
-          * Decimal::from_ratio(u32::pow(10, from_decimals.unwrap_or(6)), u32::pow(10, 6))
-      },
-    };
+    let price = oracle.query_price(&deps.querier, &info)?;
 
     prices.push((info, price));
   }
@@ -1,13 +1,13 @@
 use crate::{
   error::SharedError,
   extensions::asset_ext::AssetExt,
-  helpers::time::Times,
+  helpers::time::{Time, Times},
   msgs_asset_gauge::{
-    ExecuteMsg, LastDistributionPeriodResponse, QueryMsg, UserFirstParticipationResponse,
-    UserSharesResponse,
+    ExecuteMsg, GaugeDistributionResponse, LastDistributionPeriodResponse, QueryMsg,
+    UserFirstParticipationResponse, UserInfoExtendedResponse, UserSharesResponse,
   },
 };
-use cosmwasm_std::{ Addr, CosmosMsg, QuerierWrapper, StdResult};
+use cosmwasm_std::{Addr, CosmosMsg, QuerierWrapper, StdResult};
 use cw_asset::Asset;
 
 pub struct AssetGauge(pub Addr);
@@ -28,6 +28,21 @@ impl AssetGauge {
     )
   }
 
+  pub fn query_user_info(
+    &self,
+    querier: &QuerierWrapper,
+    user: String,
+    time: Option<Time>,
+  ) -> StdResult<UserInfoExtendedResponse> {
+    querier.query_wasm_smart(
+      self.0.clone(),
+      &QueryMsg::UserInfo {
+        user,
+        time,
+      },
+    )
+  }
+
   pub fn query_first_participation(
     &self,
     querier: &QuerierWrapper,
@@ -48,6 +63,21 @@ impl AssetGauge {
     querier.query_wasm_smart(self.0.clone(), &QueryMsg::LastDistributionPeriod {})
   }
 
+  pub fn query_distribution(
+    &self,
+    querier: &QuerierWrapper,
+    gauge: String,
+    time: Option<Time>,
+  ) -> StdResult<GaugeDistributionResponse> {
+    querier.query_wasm_smart(
+      self.0.clone(),
+      &QueryMsg::Distribution {
+        gauge,
+        time,
+      },
+    )
+  }
+
   pub fn add_rebase_msg(&self, asset: Asset) -> Result<CosmosMsg, SharedError> {
     asset.send_or_execute_msg(self.0.to_string(), &ExecuteMsg::AddRebase {})
   }
@@ -24,8 +24,9 @@ impl BribeManager {
           msg: to_json_binary(&ExecuteMsg::AddBribe {
             bribe: bribe.clone().into(),
             gauge,
-            for_info: for_info.into(),
+            for_info: Some(for_info.into()),
             distribution: BribeDistribution::Next,
+            fee_info: None,
           })?,
           funds: vec![coin(bribe.amount.u128(), denom)],
         })]
@@ -42,8 +43,9 @@ impl BribeManager {
           msg: to_json_binary(&ExecuteMsg::AddBribe {
             bribe: bribe.into(),
             gauge,
-            for_info: for_info.into(),
+            for_info: Some(for_info.into()),
             distribution: BribeDistribution::Next,
+            fee_info: None,
           })?,
           funds: vec![],
         }),
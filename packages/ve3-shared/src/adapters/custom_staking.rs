@@ -0,0 +1,53 @@
+use cosmwasm_std::{from_json, Addr, Binary, CosmosMsg, Uint128, WasmMsg};
+use cw_asset::{Asset, AssetInfo};
+use serde::de::IgnoredAny;
+
+use crate::{error::SharedError, extensions::asset_ext::AssetExt};
+
+/// External staker addressed through json message templates.
+/// "{amount}" and "{lp_token}" (denom or cw20 address) are replaced in the templates.
+pub struct CustomStaking(pub Addr);
+
+impl CustomStaking {
+  /// sends the asset with the deposit message (cw20 through a send hook)
+  pub fn deposit_msg(&self, template: &str, asset: Asset) -> Result<CosmosMsg, SharedError> {
+    let msg = render(template, asset.amount, &asset.info)?;
+    asset.send_or_execute_msg_raw(self.0.to_string(), msg)
+  }
+
+  pub fn withdraw_msg(&self, template: &str, asset: Asset) -> Result<CosmosMsg, SharedError> {
+    self.execute_msg(render(template, asset.amount, &asset.info)?)
+  }
+
+  pub fn claim_msg(&self, template: &str, asset: AssetInfo) -> Result<CosmosMsg, SharedError> {
+    self.execute_msg(render(template, Uint128::zero(), &asset)?)
+  }
+
+  fn execute_msg(&self, msg: Binary) -> Result<CosmosMsg, SharedError> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+      contract_addr: self.0.to_string(),
+      msg,
+      funds: vec![],
+    }))
+  }
+}
+
+/// verifies that the template is valid json after replacing the placeholders
+pub fn check_template(template: &str) -> Result<(), SharedError> {
+  let msg = render(template, Uint128::zero(), &AssetInfo::native("lp"))?;
+  from_json::<IgnoredAny>(&msg).map_err(|_| {
+    SharedError::InvalidStakeConfig(format!("template is not valid json: {template}"))
+  })?;
+  Ok(())
+}
+
+fn render(template: &str, amount: Uint128, asset: &AssetInfo) -> Result<Binary, SharedError> {
+  let lp_token = match asset {
+    cw_asset::AssetInfoBase::Native(denom) => denom.to_string(),
+    cw_asset::AssetInfoBase::Cw20(contract) => contract.to_string(),
+    _ => Err(SharedError::NotSupported("asset".to_string()))?,
+  };
+
+  let msg = template.replace("{amount}", &amount.to_string()).replace("{lp_token}", &lp_token);
+  Ok(Binary::from(msg.into_bytes()))
+}
@@ -4,10 +4,12 @@ pub mod astroport;
 pub mod bribe_manager;
 pub mod compounder;
 pub mod connector;
+pub mod custom_staking;
 pub mod eris;
 pub mod global_config_adapter;
 pub mod mint_proxy;
 pub mod pair;
 pub mod router;
 pub mod voting_escrow;
+pub mod white_whale;
 pub mod zapper;
@@ -0,0 +1,97 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{coin, to_json_binary, Addr, Coin, CosmosMsg, WasmMsg};
+use cw_asset::Asset;
+
+use crate::error::SharedError;
+
+#[cw_serde]
+pub enum WhiteWhaleExecuteMsg {
+  /// Claims the rewards of all positions of the sender
+  Claim {},
+  /// Manages a position of the sender
+  ManagePosition {
+    action: PositionAction,
+  },
+}
+
+#[cw_serde]
+pub enum PositionAction {
+  /// Creates or expands a position with the sent lp tokens
+  Fill {
+    identifier: Option<String>,
+    unlocking_duration: u64,
+    receiver: Option<String>,
+  },
+  /// Starts unlocking the lp tokens of the position, the full position if no lp_asset is set
+  Close {
+    identifier: String,
+    lp_asset: Option<Coin>,
+  },
+  /// Withdraws the unlocked lp tokens of the position
+  Withdraw {
+    identifier: String,
+    emergency_unlock: Option<bool>,
+  },
+}
+
+pub struct WhiteWhaleIncentives(pub Addr);
+
+impl WhiteWhaleIncentives {
+  pub fn claim_msg(&self) -> Result<CosmosMsg, SharedError> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+      contract_addr: self.0.to_string(),
+      msg: to_json_binary(&WhiteWhaleExecuteMsg::Claim {})?,
+      funds: vec![],
+    }))
+  }
+
+  pub fn fill_msg(&self, asset: Asset, unlocking_duration: u64) -> Result<CosmosMsg, SharedError> {
+    let denom = native_denom(&asset)?;
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+      contract_addr: self.0.to_string(),
+      msg: to_json_binary(&WhiteWhaleExecuteMsg::ManagePosition {
+        action: PositionAction::Fill {
+          identifier: Some(denom.clone()),
+          unlocking_duration,
+          receiver: None,
+        },
+      })?,
+      funds: vec![coin(asset.amount.u128(), denom)],
+    }))
+  }
+
+  /// closes the amount of the position and withdraws it, only possible without unlocking duration
+  pub fn close_and_withdraw_msgs(&self, asset: Asset) -> Result<Vec<CosmosMsg>, SharedError> {
+    let denom = native_denom(&asset)?;
+    Ok(vec![
+      CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: self.0.to_string(),
+        msg: to_json_binary(&WhiteWhaleExecuteMsg::ManagePosition {
+          action: PositionAction::Close {
+            identifier: denom.clone(),
+            lp_asset: Some(coin(asset.amount.u128(), denom.clone())),
+          },
+        })?,
+        funds: vec![],
+      }),
+      CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: self.0.to_string(),
+        msg: to_json_binary(&WhiteWhaleExecuteMsg::ManagePosition {
+          action: PositionAction::Withdraw {
+            identifier: denom,
+            emergency_unlock: None,
+          },
+        })?,
+        funds: vec![],
+      }),
+    ])
+  }
+}
+
+// positions are identified by the lp denom, the incentive manager only supports native lp tokens
+fn native_denom(asset: &Asset) -> Result<String, SharedError> {
+  match &asset.info {
+    cw_asset::AssetInfoBase::Native(denom) => Ok(denom.clone()),
+    _ => Err(SharedError::NotSupported("only native lp tokens".to_string())),
+  }
+}
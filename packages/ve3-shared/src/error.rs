@@ -48,4 +48,7 @@ pub enum SharedError {
 
   #[error("{0}, containing: {1}")]
   StdExtended(String, String),
+
+  #[error("Invalid stake config: {0}")]
+  InvalidStakeConfig(String),
 }
@@ -0,0 +1,339 @@
+use super::denom::Coin;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{QuerierWrapper, StdError, StdResult, Uint128};
+use osmosis_std_derive::CosmwasmExt;
+use std::str::FromStr;
+
+// see https://github.com/terra-money/alliance/blob/v0.3.6/proto/alliance/alliance/query.proto
+
+/// Delegations requested per page by query_alliance_delegations.
+const DELEGATIONS_PAGE_LIMIT: u64 = 100;
+
+/// PageRequest is to be embedded in gRPC request messages for efficient pagination.
+/// Only the offset is used, as the next_key of the response is base64 encoded in json.
+#[derive(
+  Clone,
+  PartialEq,
+  Eq,
+  ::prost::Message,
+  serde::Serialize,
+  serde::Deserialize,
+  schemars::JsonSchema,
+  CosmwasmExt,
+)]
+#[proto_message(type_url = "/cosmos.base.query.v1beta1.PageRequest")]
+pub struct PageRequest {
+  #[prost(bytes = "vec", tag = "1")]
+  pub key: ::prost::alloc::vec::Vec<u8>,
+  #[prost(uint64, tag = "2")]
+  pub offset: u64,
+  #[prost(uint64, tag = "3")]
+  pub limit: u64,
+  #[prost(bool, tag = "4")]
+  pub count_total: bool,
+  #[prost(bool, tag = "5")]
+  pub reverse: bool,
+}
+
+/// QueryAlliancesDelegationsRequest queries all alliance delegations of a delegator.
+#[derive(
+  Clone,
+  PartialEq,
+  Eq,
+  ::prost::Message,
+  serde::Serialize,
+  serde::Deserialize,
+  schemars::JsonSchema,
+  CosmwasmExt,
+)]
+#[proto_message(type_url = "/alliance.alliance.QueryAlliancesDelegationsRequest")]
+#[proto_query(
+  path = "/alliance.alliance.Query/AlliancesDelegation",
+  response_type = QueryAlliancesDelegationsResponse
+)]
+pub struct QueryAlliancesDelegationsRequest {
+  #[prost(string, tag = "1")]
+  pub delegator_addr: ::prost::alloc::string::String,
+  #[prost(message, optional, tag = "2")]
+  pub pagination: ::core::option::Option<PageRequest>,
+}
+
+#[derive(
+  Clone,
+  PartialEq,
+  Eq,
+  ::prost::Message,
+  serde::Serialize,
+  serde::Deserialize,
+  schemars::JsonSchema,
+  CosmwasmExt,
+)]
+#[proto_message(type_url = "/alliance.alliance.QueryAlliancesDelegationsResponse")]
+pub struct QueryAlliancesDelegationsResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub delegations: ::prost::alloc::vec::Vec<DelegationResponse>,
+}
+
+/// DelegationResponse is the delegation together with the current balance of it.
+#[derive(
+  Clone,
+  PartialEq,
+  Eq,
+  ::prost::Message,
+  serde::Serialize,
+  serde::Deserialize,
+  schemars::JsonSchema,
+  CosmwasmExt,
+)]
+#[proto_message(type_url = "/alliance.alliance.DelegationResponse")]
+pub struct DelegationResponse {
+  #[prost(message, optional, tag = "1")]
+  pub delegation: ::core::option::Option<Delegation>,
+  #[prost(message, optional, tag = "2")]
+  pub balance: ::core::option::Option<Coin>,
+}
+
+#[derive(
+  Clone,
+  PartialEq,
+  Eq,
+  ::prost::Message,
+  serde::Serialize,
+  serde::Deserialize,
+  schemars::JsonSchema,
+  CosmwasmExt,
+)]
+#[proto_message(type_url = "/alliance.alliance.Delegation")]
+pub struct Delegation {
+  #[prost(string, tag = "1")]
+  pub delegator_address: ::prost::alloc::string::String,
+  #[prost(string, tag = "2")]
+  pub validator_address: ::prost::alloc::string::String,
+  #[prost(string, tag = "3")]
+  pub denom: ::prost::alloc::string::String,
+  #[prost(string, tag = "4")]
+  pub shares: ::prost::alloc::string::String,
+}
+
+#[derive(
+  Clone,
+  PartialEq,
+  Eq,
+  ::prost::Message,
+  serde::Serialize,
+  serde::Deserialize,
+  schemars::JsonSchema,
+  CosmwasmExt,
+)]
+#[proto_message(type_url = "/alliance.alliance.QueryAllianceDelegationRequest")]
+#[proto_query(
+  path = "/alliance.alliance.Query/AllianceDelegation",
+  response_type = QueryAllianceDelegationResponse
+)]
+pub struct QueryAllianceDelegationRequest {
+  #[prost(string, tag = "1")]
+  pub delegator_addr: ::prost::alloc::string::String,
+  #[prost(string, tag = "2")]
+  pub validator_addr: ::prost::alloc::string::String,
+  #[prost(string, tag = "3")]
+  pub denom: ::prost::alloc::string::String,
+}
+
+#[derive(
+  Clone,
+  PartialEq,
+  Eq,
+  ::prost::Message,
+  serde::Serialize,
+  serde::Deserialize,
+  schemars::JsonSchema,
+  CosmwasmExt,
+)]
+#[proto_message(type_url = "/alliance.alliance.QueryAllianceDelegationResponse")]
+pub struct QueryAllianceDelegationResponse {
+  #[prost(message, optional, tag = "1")]
+  pub delegation: ::core::option::Option<DelegationResponse>,
+}
+
+#[derive(
+  Clone,
+  PartialEq,
+  Eq,
+  ::prost::Message,
+  serde::Serialize,
+  serde::Deserialize,
+  schemars::JsonSchema,
+  CosmwasmExt,
+)]
+#[proto_message(type_url = "/alliance.alliance.QueryAllianceDelegationRewardsRequest")]
+#[proto_query(
+  path = "/alliance.alliance.Query/AllianceDelegationRewards",
+  response_type = QueryAllianceDelegationRewardsResponse
+)]
+pub struct QueryAllianceDelegationRewardsRequest {
+  #[prost(string, tag = "1")]
+  pub delegator_addr: ::prost::alloc::string::String,
+  #[prost(string, tag = "2")]
+  pub validator_addr: ::prost::alloc::string::String,
+  #[prost(string, tag = "3")]
+  pub denom: ::prost::alloc::string::String,
+}
+
+#[derive(
+  Clone,
+  PartialEq,
+  Eq,
+  ::prost::Message,
+  serde::Serialize,
+  serde::Deserialize,
+  schemars::JsonSchema,
+  CosmwasmExt,
+)]
+#[proto_message(type_url = "/alliance.alliance.QueryAllianceDelegationRewardsResponse")]
+pub struct QueryAllianceDelegationRewardsResponse {
+  #[prost(message, repeated, tag = "1")]
+  pub rewards: ::prost::alloc::vec::Vec<Coin>,
+}
+
+/// Returns the alliance delegations (validator, amount) of the delegator for the denom.
+/// All pages are queried until an empty page is returned.
+pub fn query_alliance_delegations(
+  querier: &QuerierWrapper,
+  delegator: impl Into<String>,
+  denom: &str,
+) -> StdResult<Vec<(String, Uint128)>> {
+  let delegator: String = delegator.into();
+  let mut delegations = vec![];
+  let mut offset = 0;
+
+  loop {
+    let response = QueryAlliancesDelegationsRequest {
+      delegator_addr: delegator.clone(),
+      pagination: Some(PageRequest {
+        key: vec![],
+        offset,
+        limit: DELEGATIONS_PAGE_LIMIT,
+        count_total: false,
+        reverse: false,
+      }),
+    }
+    .query(querier)?;
+
+    if response.delegations.is_empty() {
+      break;
+    }
+    offset += response.delegations.len() as u64;
+
+    for delegation in response.delegations {
+      if let (Some(delegation), Some(balance)) = (delegation.delegation, delegation.balance) {
+        if balance.denom == denom {
+          delegations.push((delegation.validator_address, Uint128::from_str(&balance.amount)?));
+        }
+      }
+    }
+  }
+  Ok(delegations)
+}
+
+#[cw_serde]
+pub struct AllianceValidatorRewards {
+  pub validator: String,
+  pub rewards: Vec<cosmwasm_std::Coin>,
+}
+
+/// The chain returns an error if there is no delegation, only this error is treated as empty.
+fn is_not_found(err: &StdError) -> bool {
+  err.to_string().to_lowercase().contains("not found")
+}
+
+/// Returns the delegated amount of the delegator to the validator for the denom.
+/// A missing delegation is treated as zero, other errors are returned.
+pub fn query_alliance_delegation(
+  querier: &QuerierWrapper,
+  delegator: impl Into<String>,
+  validator: impl Into<String>,
+  denom: &str,
+) -> StdResult<Uint128> {
+  let response = QueryAllianceDelegationRequest {
+    delegator_addr: delegator.into(),
+    validator_addr: validator.into(),
+    denom: denom.to_string(),
+  }
+  .query(querier);
+
+  match response.map(|response| response.delegation.and_then(|delegation| delegation.balance)) {
+    Ok(Some(balance)) => Ok(Uint128::from_str(&balance.amount)?),
+    Ok(None) => Ok(Uint128::zero()),
+    Err(err) if is_not_found(&err) => Ok(Uint128::zero()),
+    Err(err) => Err(err),
+  }
+}
+
+/// Returns the unclaimed rewards of the delegation of the delegator to the validator.
+/// A missing delegation is treated as no rewards, other errors are returned.
+pub fn query_alliance_rewards(
+  querier: &QuerierWrapper,
+  delegator: impl Into<String>,
+  validator: impl Into<String>,
+  denom: &str,
+) -> StdResult<Vec<cosmwasm_std::Coin>> {
+  let response = QueryAllianceDelegationRewardsRequest {
+    delegator_addr: delegator.into(),
+    validator_addr: validator.into(),
+    denom: denom.to_string(),
+  }
+  .query(querier);
+
+  match response {
+    Ok(response) => response
+      .rewards
+      .into_iter()
+      .map(|coin| Ok(cosmwasm_std::Coin::new(Uint128::from_str(&coin.amount)?.u128(), coin.denom)))
+      .collect(),
+    Err(err) if is_not_found(&err) => Ok(vec![]),
+    Err(err) => Err(err),
+  }
+}
+
+/// Returns the delegations (validator, amount) of the delegator to the validators, sorted by validator.
+pub fn query_validators_delegations(
+  querier: &QuerierWrapper,
+  delegator: impl Into<String>,
+  validators: impl IntoIterator<Item = String>,
+  denom: &str,
+) -> StdResult<Vec<(String, Uint128)>> {
+  let delegator: String = delegator.into();
+  let mut validators = validators.into_iter().collect::<Vec<_>>();
+  validators.sort();
+
+  validators
+    .into_iter()
+    .map(|validator| {
+      let amount = query_alliance_delegation(querier, &delegator, &validator, denom)?;
+      Ok((validator, amount))
+    })
+    .collect()
+}
+
+/// Returns the unclaimed rewards of the delegator per validator, sorted by validator.
+pub fn query_validators_rewards(
+  querier: &QuerierWrapper,
+  delegator: impl Into<String>,
+  validators: impl IntoIterator<Item = String>,
+  denom: &str,
+) -> StdResult<Vec<AllianceValidatorRewards>> {
+  let delegator: String = delegator.into();
+  let mut validators = validators.into_iter().collect::<Vec<_>>();
+  validators.sort();
+
+  validators
+    .into_iter()
+    .map(|validator| {
+      let rewards = query_alliance_rewards(querier, &delegator, &validator, denom)?;
+      Ok(AllianceValidatorRewards {
+        validator,
+        rewards,
+      })
+    })
+    .collect()
+}
@@ -1,8 +1,10 @@
+pub mod alliance;
 pub mod assets;
 pub mod bps;
 pub mod denom;
 pub mod general;
 pub mod governance;
+pub mod oracle;
 pub mod slope;
 pub mod time;
 pub mod take;
@@ -0,0 +1,140 @@
+use crate::{
+  adapters::{pair::Pair, router::Router},
+  error::SharedError,
+  extensions::asset_info_ext::AssetInfoExt,
+};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Api, Decimal, QuerierWrapper, Uint128};
+use cw_address_like::AddressLike;
+use cw_asset::{AssetBase, AssetError, AssetInfo, AssetInfoBase};
+
+#[cw_serde]
+pub enum Oracle<T: AddressLike> {
+  Usdc,
+  Pair {
+    contract: T,
+    simulation_amount: Uint128,
+    #[serde(default)]
+    from_decimals: Option<u32>,
+  },
+  Route {
+    contract: T,
+    path: Vec<AssetInfoBase<T>>,
+    simulation_amount: Uint128,
+    #[serde(default)]
+    from_decimals: Option<u32>,
+  },
+  RouteAsset {
+    contract: T,
+    path: Vec<AssetInfoBase<T>>,
+    simulation_amount: AssetBase<T>,
+    #[serde(default)]
+    from_decimals: Option<u32>,
+  },
+}
+
+impl Oracle<String> {
+  pub fn check(self, api: &dyn Api) -> Result<Oracle<Addr>, SharedError> {
+    Ok(match self {
+      Oracle::Usdc => Oracle::Usdc,
+      Oracle::Pair {
+        contract,
+        simulation_amount,
+        from_decimals,
+      } => Oracle::Pair {
+        contract: api.addr_validate(&contract)?,
+        simulation_amount,
+        from_decimals,
+      },
+      Oracle::Route {
+        contract,
+        path,
+        simulation_amount,
+        from_decimals,
+      } => Oracle::Route {
+        contract: api.addr_validate(&contract)?,
+        simulation_amount,
+        path: path
+          .into_iter()
+          .map(|a| a.check(api, None))
+          .collect::<Result<Vec<AssetInfo>, AssetError>>()?,
+        from_decimals,
+      },
+      Oracle::RouteAsset {
+        contract,
+        path,
+        simulation_amount,
+        from_decimals,
+      } => Oracle::RouteAsset {
+        contract: api.addr_validate(&contract)?,
+        simulation_amount: simulation_amount.check(api, None)?,
+        path: path
+          .into_iter()
+          .map(|a| a.check(api, None))
+          .collect::<Result<Vec<AssetInfo>, AssetError>>()?,
+        from_decimals,
+      },
+    })
+  }
+}
+
+impl Oracle<Addr> {
+  /// price of one unit of the asset in the quote asset of the oracle (Usdc is always 1)
+  pub fn query_price(
+    &self,
+    querier: &QuerierWrapper,
+    info: &AssetInfo,
+  ) -> Result<Decimal, SharedError> {
+    Ok(match self {
+      Oracle::Usdc => Decimal::one(),
+      Oracle::Pair {
+        contract,
+        simulation_amount,
+        from_decimals,
+      } => {
+        let result = Pair(contract.clone()).query_simulate(
+          querier,
+          false,
+          info.with_balance(*simulation_amount),
+          None,
+        )?;
+
+        Decimal::from_ratio(result.return_amount, *simulation_amount)
+          * decimals_factor(*from_decimals)
+      },
+      Oracle::Route {
+        contract,
+        path,
+        simulation_amount,
+        from_decimals,
+      } => {
+        let result = Router(contract.clone()).query_simulate(
+          querier,
+          info.with_balance(*simulation_amount),
+          path.clone(),
+        )?;
+
+        Decimal::from_ratio(result.amount, *simulation_amount) * decimals_factor(*from_decimals)
+      },
+      Oracle::RouteAsset {
+        contract,
+        path,
+        simulation_amount,
+        from_decimals,
+      } => {
+        let result = Router(contract.clone()).query_simulate(
+          querier,
+          simulation_amount.clone(),
+          path.clone(),
+        )?;
+
+        Decimal::from_ratio(result.amount, simulation_amount.amount)
+          * decimals_factor(*from_decimals)
+      },
+    })
+  }
+}
+
+fn decimals_factor(from_decimals: Option<u32>) -> Decimal {
+  Decimal::from_ratio(u32::pow(10, from_decimals.unwrap_or(6)), u32::pow(10, 6))
+}
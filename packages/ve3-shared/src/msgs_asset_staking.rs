@@ -1,14 +1,16 @@
 use crate::{
   adapters::{bribe_manager::BribeManager, connector::Connector, global_config_adapter::ConfigExt},
-  constants::{at_connector, AT_BRIBE_MANAGER},
+  constants::{at_connector, AT_BRIBE_MANAGER, SECONDS_PER_YEAR},
   error::SharedError,
+  helpers::oracle::Oracle,
   stake_config::StakeConfig,
 };
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Api, Decimal, DepsMut, Uint128};
 use cw20::Cw20ReceiveMsg;
 use cw_address_like::AddressLike;
-use cw_asset::{Asset, AssetError, AssetInfo, AssetInfoBase, AssetInfoUnchecked};
+use cw_asset::{Asset, AssetInfo, AssetInfoBase, AssetInfoUnchecked};
+use std::cmp::min;
 
 #[cw_serde]
 pub struct Config {
@@ -43,12 +45,181 @@ pub struct AssetConfigRuntime {
 
   pub yearly_take_rate: Decimal,
   pub stake_config: StakeConfig<Addr>,
+
+  /// duration over which harvested rewards are released to stakers, 0 credits them instantly
+  #[serde(default)]
+  pub reward_stream_seconds: u64,
+  /// cooldown before unstaked assets can be withdrawn, 0 returns them instantly
+  #[serde(default)]
+  pub unbonding_seconds: u64,
+  /// max multiplier of the working balance through the ve voting power, None disables boosting
+  #[serde(default)]
+  pub max_boost: Option<Decimal>,
+  /// tokenfactory denom that stake can be wrapped into, None if the asset is not tokenized
+  #[serde(default)]
+  pub receipt_denom: Option<String>,
+  /// take rates by staked balance, the highest reached tier replaces the yearly_take_rate
+  #[serde(default)]
+  pub take_tiers: Vec<TakeTier>,
+  /// share of the harvested staking rewards that is taken, replaces taking from the principal
+  #[serde(default)]
+  pub take_on_rewards: Option<Decimal>,
+  /// max staked balance of the asset, None is unlimited
+  #[serde(default)]
+  pub max_total_stake: Option<Uint128>,
+  /// max staked balance of the asset per user, None is unlimited
+  #[serde(default)]
+  pub max_stake_per_user: Option<Uint128>,
+  /// max yearly emissions relative to the staked value, None is uncapped
+  #[serde(default)]
+  pub apr_cap: Option<AprCap<Addr>>,
+}
+
+impl AssetConfigRuntime {
+  /// true if the take is extracted from the staked balance
+  pub fn takes_principal(&self) -> bool {
+    self.take_on_rewards.is_none()
+      && (!self.yearly_take_rate.is_zero()
+        || self.take_tiers.iter().any(|tier| !tier.yearly_take_rate.is_zero()))
+  }
+
+  /// yearly take rate applied to the staked balance
+  pub fn take_rate(&self, balance: Uint128) -> Decimal {
+    if self.take_on_rewards.is_some() {
+      return Decimal::zero();
+    }
+
+    self
+      .take_tiers
+      .iter()
+      .filter(|tier| balance >= tier.min_balance)
+      .max_by_key(|tier| tier.min_balance)
+      .map(|tier| tier.yearly_take_rate)
+      .unwrap_or(self.yearly_take_rate)
+  }
+
+  /// amount taken from the balance (including already taken) over the seconds
+  pub fn take_amount(&self, balance: Uint128, seconds: u64) -> Uint128 {
+    let relevant_balance = balance.saturating_sub(self.taken);
+    self.take_rate(relevant_balance)
+      * relevant_balance.multiply_ratio(min(seconds, SECONDS_PER_YEAR), SECONDS_PER_YEAR)
+  }
+}
+
+#[cw_serde]
+pub struct TakeTier {
+  pub min_balance: Uint128,
+  pub yearly_take_rate: Decimal,
+}
+
+/// Caps the emissions of an asset by the value of its staked balance
+#[cw_serde]
+pub struct AprCap<T: AddressLike> {
+  pub max_apr: Decimal,
+  /// prices the staked asset in the reward asset
+  pub oracle: Oracle<T>,
+  /// receives the emissions above the cap, None redistributes them to uncapped assets
+  pub excess_recipient: Option<T>,
+}
+
+impl AprCap<String> {
+  pub fn check(self, api: &dyn Api) -> Result<AprCap<Addr>, SharedError> {
+    Ok(AprCap {
+      max_apr: self.max_apr,
+      oracle: self.oracle.check(api)?,
+      excess_recipient: self.excess_recipient.map(|a| api.addr_validate(&a)).transpose()?,
+    })
+  }
+}
+
+impl AprCap<Addr> {
+  /// max emissions for the staked balance over the seconds
+  pub fn max_rewards(&self, price: Decimal, balance: Uint128, seconds: u64) -> Uint128 {
+    balance.mul_floor(price).mul_floor(self.max_apr).multiply_ratio(seconds, SECONDS_PER_YEAR)
+  }
 }
 
 #[cw_serde]
 pub struct AssetConfig<T: AddressLike> {
   pub yearly_take_rate: Option<Decimal>,
   pub stake_config: StakeConfig<T>,
+  /// None keeps the current value, 0 credits rewards instantly
+  #[serde(default)]
+  pub reward_stream_seconds: Option<u64>,
+  /// None keeps the current value, 0 returns unstaked assets instantly
+  #[serde(default)]
+  pub unbonding_seconds: Option<u64>,
+  /// None keeps the current value, 1 disables boosting, it is removed through clear
+  #[serde(default)]
+  pub max_boost: Option<Decimal>,
+  /// creates a receipt denom for the asset, it can't be removed afterwards
+  #[serde(default)]
+  pub tokenized: Option<bool>,
+  /// None keeps the current tiers, an empty list removes them
+  #[serde(default)]
+  pub take_tiers: Option<Vec<TakeTier>>,
+  /// None keeps the current value, it is removed through clear
+  #[serde(default)]
+  pub take_on_rewards: Option<Decimal>,
+  /// None keeps the current value, it is removed through clear
+  #[serde(default)]
+  pub max_total_stake: Option<Uint128>,
+  /// None keeps the current value, it is removed through clear
+  #[serde(default)]
+  pub max_stake_per_user: Option<Uint128>,
+  /// None keeps the current cap, it is removed through clear
+  #[serde(default)]
+  pub apr_cap: Option<AprCap<T>>,
+  /// removes the listed values from the current config, applied after the provided values
+  #[serde(default)]
+  pub clear: Option<Vec<AssetConfigField>>,
+}
+
+/// Optional values of an asset config that can be removed through AssetConfig::clear
+#[cw_serde]
+pub enum AssetConfigField {
+  TakeOnRewards,
+  MaxTotalStake,
+  MaxStakePerUser,
+  MaxBoost,
+  AprCap,
+}
+
+/// Linear release of harvested rewards for an asset
+#[cw_serde]
+#[derive(Default)]
+pub struct RewardStream {
+  /// rewards released per second
+  pub reward_rate: Decimal,
+  pub period_finish: u64,
+  pub last_update_s: u64,
+}
+
+impl RewardStream {
+  /// rewards released since the last update
+  pub fn released(&self, now: u64) -> Decimal {
+    let until = min(now, self.period_finish);
+    self.reward_rate * Decimal::from_ratio(until.saturating_sub(self.last_update_s), 1u64)
+  }
+
+  /// rewards released since the last update per share, nothing is released without shares
+  pub fn released_per_share(&self, now: u64, total_shares: Uint128) -> Decimal {
+    if total_shares.is_zero() {
+      return Decimal::zero();
+    }
+    self.released(now) / Decimal::from_ratio(total_shares, 1u128)
+  }
+
+  /// adds rewards to the stream, the not yet released rewards are streamed again over the full duration.
+  /// The stream needs to be checkpointed before.
+  pub fn add(&mut self, now: u64, amount: Decimal, duration: u64) {
+    let remaining = self.period_finish.saturating_sub(now);
+    let leftover = self.reward_rate * Decimal::from_ratio(remaining, 1u64);
+
+    self.reward_rate = (leftover + amount) / Decimal::from_ratio(duration, 1u64);
+    self.period_finish = now + duration;
+    self.last_update_s = now;
+  }
 }
 
 #[cw_serde]
@@ -94,15 +265,25 @@ impl AssetInfoWithConfig<String> {
 }
 
 impl AssetInfoWithConfig<String> {
-  pub fn check(self, api: &dyn Api) -> Result<AssetInfoWithConfig<Addr>, AssetError> {
+  pub fn check(self, api: &dyn Api) -> Result<AssetInfoWithConfig<Addr>, SharedError> {
     Ok(AssetInfoWithConfig {
       info: self.info.check(api, None)?,
       config: self
         .config
-        .map(|a| -> Result<AssetConfig<Addr>, AssetError> {
+        .map(|a| -> Result<AssetConfig<Addr>, SharedError> {
           Ok(AssetConfig {
             yearly_take_rate: a.yearly_take_rate,
             stake_config: a.stake_config.check(api)?,
+            reward_stream_seconds: a.reward_stream_seconds,
+            unbonding_seconds: a.unbonding_seconds,
+            max_boost: a.max_boost,
+            tokenized: a.tokenized,
+            take_tiers: a.take_tiers,
+            take_on_rewards: a.take_on_rewards,
+            max_total_stake: a.max_total_stake,
+            max_stake_per_user: a.max_stake_per_user,
+            apr_cap: a.apr_cap.map(|cap| cap.check(api)).transpose()?,
+            clear: a.clear,
           })
         })
         .transpose()?,
@@ -127,6 +308,10 @@ pub enum CallbackMsg {
     take_asset: Asset,
     recipient: Addr,
   },
+  /// Deposits the asset withdrawn from the previous stake config into the current one
+  MigrateStake {
+    asset: Asset,
+  },
 }
 
 impl From<CallbackMsg> for ExecuteMsg {
@@ -162,6 +347,51 @@ pub enum ExecuteMsg {
     assets: Option<Vec<AssetInfo>>,
     recipient: Option<String>,
   },
+  /// Claims the rewards of the assets (all staked assets if none) and zaps them into the
+  /// whitelisted asset `into`, which is staked for the user. Extra rewards are sent to the user.
+  ClaimAndCompound {
+    assets: Option<Vec<AssetInfo>>,
+    into: AssetInfo,
+    min_received: Option<Uint128>,
+  },
+  /// Withdraws the unstaked assets that finished unbonding, at most limit entries (all if none)
+  WithdrawUnbonded {
+    #[serde(default)]
+    limit: Option<u32>,
+  },
+  /// Refreshes the boosted working balances of the user, e.g. after the voting power decayed
+  Kick {
+    user: String,
+  },
+  /// Moves staked shares to another address, the rewards of both are settled first
+  TransferStake {
+    asset: AssetInfo,
+    shares: Uint128,
+    recipient: String,
+  },
+  /// Wraps staked shares into the receipt denom of the asset (1 token per share).
+  /// Wrapped shares do not earn rewards, those go to the remaining stakers.
+  WrapStake {
+    asset: AssetInfo,
+    shares: Uint128,
+    recipient: Option<String>,
+  },
+  /// Burns the sent receipt tokens and credits the shares to the recipient
+  UnwrapStake {
+    recipient: Option<String>,
+  },
+  /// Adds additional rewards for the stakers of an asset, released over the duration in seconds.
+  /// New reward assets need to be added by the whitelist controller first.
+  AddRewards {
+    asset: AssetInfo,
+    rewards: Asset,
+    duration: u64,
+  },
+  /// Unstakes all shares of a paused asset from the balance held by the contract,
+  /// the pending rewards of the asset are forfeited
+  EmergencyUnstake {
+    asset: AssetInfo,
+  },
 
   // controller
   WhitelistAssets(Vec<AssetInfoWithConfig<String>>),
@@ -170,6 +400,18 @@ pub enum ExecuteMsg {
   UpdateAssetConfig(AssetInfoWithConfig<String>),
   SetAssetRewardDistribution(Vec<AssetDistribution>),
 
+  // guardian
+  /// Stops staking and unstaking of the asset, only EmergencyUnstake is possible while paused
+  SetAssetPaused {
+    asset: AssetInfo,
+    paused: bool,
+  },
+  /// Withdraws all funds of a paused asset from the external staker,
+  /// the asset uses the default stake config afterwards
+  EmergencyWithdrawFromExternal {
+    asset: AssetInfo,
+  },
+
   // operator
   UpdateRewards {},
   DistributeTakeRate {
@@ -195,6 +437,9 @@ pub enum QueryMsg {
   #[returns(WhitelistedAssetsDetailsResponse)]
   WhitelistedAssetDetails {},
 
+  #[returns(PausedAssetsResponse)]
+  PausedAssets {},
+
   #[returns(Vec<AssetDistribution>)]
   RewardDistribution {},
 
@@ -221,10 +466,26 @@ pub enum QueryMsg {
 
   #[returns(Vec<UserStakedAssetRes>)]
   AllStakers(AllStakersQuery),
+
+  #[returns(PendingUnbondsResponse)]
+  PendingUnbonds {
+    address: String,
+  },
+
+  #[returns(WorkingBalanceRes)]
+  WorkingBalance(AssetQuery),
+
+  #[returns(SimulateTakeResponse)]
+  SimulateTake {
+    asset: AssetInfo,
+    seconds: u64,
+  },
 }
 
 pub type WhitelistedAssetsResponse = Vec<AssetInfo>;
+pub type PausedAssetsResponse = Vec<AssetInfo>;
 pub type WhitelistedAssetsDetailsResponse = Vec<AssetInfoWithRuntime>;
+pub type PendingUnbondsResponse = Vec<PendingUnbond>;
 
 #[cw_serde]
 pub struct PoolStakersQuery {
@@ -285,6 +546,34 @@ pub struct UserStakedAssetRes {
 pub struct PendingRewardsRes {
   pub staked_asset_share: Asset,
   pub reward_asset: Asset,
+  #[serde(default)]
+  pub extra_rewards: Vec<Asset>,
+}
+
+#[cw_serde]
+pub struct SimulateTakeResponse {
+  /// yearly take rate at the current staked balance
+  pub yearly_take_rate: Decimal,
+  /// amount taken from the staked balance over the next seconds
+  pub take: Asset,
+  /// harvested rewards taken and not yet distributed
+  pub reward_take: Vec<Asset>,
+}
+
+#[cw_serde]
+pub struct WorkingBalanceRes {
+  pub shares: Uint128,
+  pub working_balance: Uint128,
+  pub total_working_balance: Uint128,
+}
+
+#[cw_serde]
+pub struct PendingUnbond {
+  pub asset: Asset,
+  pub release_at_s: u64,
+  /// receiver of the withdrawn asset if it is not the user that unstaked
+  #[serde(default)]
+  pub recipient: Option<Addr>,
 }
 
 #[cw_serde]
@@ -292,4 +581,6 @@ pub struct PendingRewardsDetailRes {
   pub share: Uint128,
   pub staked_asset: Asset,
   pub reward_asset: Asset,
+  #[serde(default)]
+  pub extra_rewards: Vec<Asset>,
 }
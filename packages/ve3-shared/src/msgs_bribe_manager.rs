@@ -5,7 +5,8 @@ use crate::{
   helpers::{assets::Assets, time::Time},
 };
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, QuerierWrapper, Uint128};
+use cosmwasm_std::{Addr, Decimal, QuerierWrapper, Uint128};
+use cw20::Cw20ReceiveMsg;
 use cw_asset::{Asset, AssetInfo, AssetInfoUnchecked, AssetUnchecked};
 
 #[cw_serde]
@@ -13,6 +14,8 @@ pub struct InstantiateMsg {
   pub global_config_addr: String,
   pub whitelist: Vec<AssetInfoUnchecked>,
   pub fee: AssetUnchecked,
+  pub fee_options: Option<Vec<AssetUnchecked>>,
+  pub bribe_fee: Option<Decimal>,
 }
 
 #[cw_serde]
@@ -24,6 +27,22 @@ pub enum BribeDistribution {
   },
   Next,
   Specific(Vec<(u64, Uint128)>),
+  /// Weights at the given periods, linearly interpolated for the periods in between.
+  Piecewise {
+    points: Vec<(u64, Decimal)>,
+  },
+  /// Halves the bribe amount every half_life_periods.
+  ExponentialDecay {
+    start: Option<u64>,
+    end: u64,
+    half_life_periods: u64,
+  },
+  /// Same amount every n periods, for count times.
+  Recurring {
+    start: Option<u64>,
+    every_n_periods: u64,
+    count: u64,
+  },
 }
 
 #[cw_serde]
@@ -40,7 +59,14 @@ pub enum FuncType {
 pub struct Config {
   pub whitelist: Vec<AssetInfo>,
   pub allow_any: bool,
+  /// Fixed fee for adding a bribe.
   pub fee: Asset,
+  /// Alternative fixed fees that can be paid instead of the default fee (native or cw20).
+  #[serde(default)]
+  pub fee_options: Vec<Asset>,
+  /// Percentage of the bribe amount taken as fee.
+  #[serde(default)]
+  pub bribe_fee: Decimal,
   pub global_config_addr: Addr,
 }
 
@@ -48,6 +74,24 @@ impl Config {
   pub fn asset_gauge(&self, querier: &QuerierWrapper) -> Result<AssetGauge, SharedError> {
     self.global_config().get_address(querier, AT_ASSET_GAUGE).map(AssetGauge)
   }
+
+  /// Returns the fixed fee to be paid in the selected asset, or the default fee if nothing selected.
+  pub fn select_fee(&self, fee_info: Option<AssetInfo>) -> Result<Option<Asset>, SharedError> {
+    let fee = match fee_info {
+      None => self.fee.clone(),
+      Some(fee_info) => std::iter::once(&self.fee)
+        .chain(self.fee_options.iter())
+        .find(|a| a.info == fee_info)
+        .cloned()
+        .ok_or_else(|| SharedError::NotSupported(format!("fee asset {0}", fee_info)))?,
+    };
+
+    if fee.amount.is_zero() {
+      Ok(None)
+    } else {
+      Ok(Some(fee))
+    }
+  }
 }
 
 #[cw_serde]
@@ -65,19 +109,19 @@ pub struct BribeBuckets {
 }
 
 impl BribeBuckets {
-  pub fn get(&mut self, gauge: &str, asset: &AssetInfo) -> &mut BribeBucket {
+  /// Returns the bucket for the gauge and asset. A bucket without asset targets the whole gauge.
+  pub fn get(&mut self, gauge: &str, asset: Option<&AssetInfo>) -> &mut BribeBucket {
     self.get_index(gauge, asset).1
   }
 
-  pub fn get_index(&mut self, gauge: &str, asset: &AssetInfo) -> (usize, &mut BribeBucket) {
-    if let Some(i) =
-      self.buckets.iter().position(|a| a.gauge == gauge && a.asset.as_ref() == Some(asset))
+  pub fn get_index(&mut self, gauge: &str, asset: Option<&AssetInfo>) -> (usize, &mut BribeBucket) {
+    if let Some(i) = self.buckets.iter().position(|a| a.gauge == gauge && a.asset.as_ref() == asset)
     {
       (i, &mut self.buckets[i])
     } else {
       self.buckets.push(BribeBucket {
         gauge: gauge.to_string(),
-        asset: Some(asset.clone()),
+        asset: asset.cloned(),
         assets: Assets::default(),
       });
       let i = self.buckets.len() - 1;
@@ -85,14 +129,19 @@ impl BribeBuckets {
     }
   }
 
-  pub fn add(&mut self, gauge: &str, asset: &AssetInfo, bribe: &Asset) {
+  /// Returns the bucket for the gauge and asset without creating it.
+  pub fn find(&self, gauge: &str, asset: Option<&AssetInfo>) -> Option<&BribeBucket> {
+    self.buckets.iter().find(|a| a.gauge == gauge && a.asset.as_ref() == asset)
+  }
+
+  pub fn add(&mut self, gauge: &str, asset: Option<&AssetInfo>, bribe: &Asset) {
     self.get(gauge, asset).assets.add(bribe);
   }
 
   pub fn remove(
     &mut self,
     gauge: &str,
-    asset: &AssetInfo,
+    asset: Option<&AssetInfo>,
     bribe: &Asset,
   ) -> Result<(), SharedError> {
     let (index, bucket) = self.get_index(gauge, asset);
@@ -110,17 +159,29 @@ impl BribeBuckets {
 
 #[cw_serde]
 pub enum ExecuteMsg {
+  Receive(Cw20ReceiveMsg),
+
   AddBribe {
     bribe: AssetUnchecked,
     gauge: String,
-    for_info: AssetInfoUnchecked,
+    /// Asset in the gauge the bribe is for. If not set, the bribe is shared by all voters of the gauge.
+    for_info: Option<AssetInfoUnchecked>,
     distribution: BribeDistribution,
+    /// Asset used to pay the fixed fee. Defaults to the config fee.
+    fee_info: Option<AssetInfoUnchecked>,
   },
 
   WithdrawBribes {
     period: u64,
   },
 
+  WithdrawBribe {
+    period: u64,
+    gauge: String,
+    for_info: Option<AssetInfoUnchecked>,
+    asset: AssetInfoUnchecked,
+  },
+
   ClaimBribes {
     periods: Option<Vec<u64>>,
     recipient: Option<String>,
@@ -132,10 +193,23 @@ pub enum ExecuteMsg {
 
   UpdateConfig {
     fee: Option<AssetUnchecked>,
+    fee_options: Option<Vec<AssetUnchecked>>,
+    bribe_fee: Option<Decimal>,
     allow_any: Option<bool>,
   },
 }
 
+#[cw_serde]
+pub enum ReceiveMsg {
+  /// Adds the received cw20 as bribe. A fee in the same cw20 is taken from the received amount.
+  AddBribe {
+    gauge: String,
+    for_info: Option<AssetInfoUnchecked>,
+    distribution: BribeDistribution,
+    fee_info: Option<AssetInfoUnchecked>,
+  },
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
@@ -157,6 +231,13 @@ pub enum QueryMsg {
     user: String,
     periods: Option<Vec<u64>>,
   },
+
+  #[returns(CreatorBribesResponse)]
+  CreatorBribes {
+    creator: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+  },
 }
 
 #[cw_serde]
@@ -166,6 +247,8 @@ pub struct NextClaimPeriodResponse {
 
 pub type BribesResponse = BribeBuckets;
 
+pub type CreatorBribesResponse = Vec<(u64, BribeBuckets)>;
+
 #[cw_serde]
 pub struct UserClaimableResponse {
   pub start: u64,
@@ -275,27 +358,36 @@ mod test {
     let asset = AssetInfo::cw20(Addr::unchecked("ampLUNA"));
     let gauge = "test".to_string();
 
-    bucket.get(&gauge, &asset).assets.add(&Asset::native("uluna", 1000u128));
-    bucket.get(&gauge, &asset).assets.add(&Asset::native("uluna", 500u128));
-    bucket.get(&gauge, &asset).assets.add(&Asset::cw20(Addr::unchecked("test"), 500u128));
-    bucket.get(&gauge, &asset).assets.remove(&Asset::native("uluna", 1000u128)).unwrap();
+    bucket.get(&gauge, Some(&asset)).assets.add(&Asset::native("uluna", 1000u128));
+    bucket.get(&gauge, Some(&asset)).assets.add(&Asset::native("uluna", 500u128));
+    bucket.get(&gauge, Some(&asset)).assets.add(&Asset::cw20(Addr::unchecked("test"), 500u128));
+    bucket.get(&gauge, Some(&asset)).assets.remove(&Asset::native("uluna", 1000u128)).unwrap();
+    bucket.get(&gauge, None).assets.add(&Asset::native("uluna", 100u128));
 
     assert_eq!(
       bucket,
       BribeBuckets {
-        buckets: vec![BribeBucket {
-          asset: Some(asset.clone()),
-          gauge: gauge.clone(),
-          assets: vec![
-            Asset::native("uluna", 500u128),
-            Asset::cw20(Addr::unchecked("test"), 500u128)
-          ]
-          .into()
-        }]
+        buckets: vec![
+          BribeBucket {
+            asset: Some(asset.clone()),
+            gauge: gauge.clone(),
+            assets: vec![
+              Asset::native("uluna", 500u128),
+              Asset::cw20(Addr::unchecked("test"), 500u128)
+            ]
+            .into()
+          },
+          BribeBucket {
+            asset: None,
+            gauge: gauge.clone(),
+            assets: vec![Asset::native("uluna", 100u128)].into()
+          }
+        ]
       }
     );
 
-    bucket.remove(&gauge, &asset, &Asset::native("uluna", 500u128)).unwrap();
+    bucket.remove(&gauge, Some(&asset), &Asset::native("uluna", 500u128)).unwrap();
+    bucket.remove(&gauge, None, &Asset::native("uluna", 100u128)).unwrap();
 
     assert_eq!(
       bucket,
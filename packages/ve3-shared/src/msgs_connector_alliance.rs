@@ -6,6 +6,7 @@ use cw_asset::{Asset, AssetInfo, AssetInfoUnchecked};
 #[allow(unused_imports)]
 use std::collections::HashSet;
 
+pub use crate::helpers::alliance::AllianceValidatorRewards;
 use crate::{
   adapters::{asset_gauge::AssetGauge, global_config_adapter::ConfigExt},
   constants::AT_ASSET_GAUGE,
@@ -111,6 +112,13 @@ pub enum ExecuteMsg {
     recipient: Option<String>,
   },
 
+  /// Redelegates from validators above their target delegation to validators below it.
+  /// Validators with a maturing incoming redelegation are skipped as source.
+  Rebalance {
+    /// Maximum number of redelegations, defaults to 10
+    max_moves: Option<u32>,
+  },
+
   // Privileged functions
   ClaimRewards {},
 
@@ -118,6 +126,11 @@ pub enum ExecuteMsg {
   AllianceUndelegate(AllianceUndelegateMsg),
   AllianceRedelegate(AllianceRedelegateMsg),
 
+  /// Sets the target weights of the delegations per validator, they need to sum up to 1
+  SetTargetDelegations {
+    weights: Vec<(String, Decimal)>,
+  },
+
   RemoveValidator {
     validator: String,
   },
@@ -136,6 +149,15 @@ pub enum QueryMsg {
 
   #[returns(HashSet<Addr>)]
   Validators {},
+
+  #[returns(Vec<AllianceDelegation>)]
+  Delegations {},
+
+  #[returns(Vec<AllianceValidatorRewards>)]
+  PendingAllianceRewards {},
+
+  #[returns(Vec<(String, Decimal)>)]
+  TargetDelegations {},
 }
 
 #[cw_serde]
@@ -1,5 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Decimal, QuerierWrapper, Uint128};
+use cosmwasm_std::{Addr, Decimal, QuerierWrapper, StdResult, Uint128, Uint256};
 use cw_asset::{AssetInfo, AssetInfoUnchecked};
 #[allow(unused_imports)]
 use std::collections::HashSet;
@@ -8,8 +8,9 @@ use crate::{
   adapters::{
     asset_gauge::AssetGauge, global_config_adapter::ConfigExt, voting_escrow::VotingEscrow,
   },
-  constants::{AT_ASSET_GAUGE, AT_VOTING_ESCROW},
+  constants::{AT_ASSET_GAUGE, AT_VOTING_ESCROW, EPOCH_START, SECONDS_PER_WEEK},
   error::SharedError,
+  helpers::governance::{get_period, get_s_from_period},
 };
 
 #[cw_serde]
@@ -22,16 +23,203 @@ pub struct InstantiateMsg {
   pub gauge: String,
   pub global_config_addr: String,
   pub emission_token: AssetInfoUnchecked,
+
+  #[serde(default)]
+  pub emission_schedule: Option<EmissionSchedule>,
+
+  #[serde(default)]
+  pub max_total_emission: Option<Uint128>,
+
+  #[serde(default)]
+  pub team_payout: Option<TeamPayout>,
+
+  #[serde(default)]
+  pub gauges: Option<Vec<(String, Decimal)>>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum TeamPayout {
+  /// team share is transferred to the team wallet on every claim
+  #[default]
+  Direct,
+
+  /// team share is vested linearly over duration_s starting at the next period,
+  /// claimable by the team wallet through ClaimTeamVested.
+  /// a changed duration_s applies from the tranche of the next period on.
+  Vesting {
+    duration_s: u64,
+  },
+}
+
+#[cw_serde]
+pub struct TeamVesting {
+  pub start_s: u64,
+  pub end_s: u64,
+  pub amount: Uint128,
+  pub claimed: Uint128,
+}
+
+impl TeamVesting {
+  /// Returns the amount that is vested, but not yet claimed.
+  pub fn claimable(&self, now_s: u64) -> Uint128 {
+    let vested = if now_s >= self.end_s {
+      self.amount
+    } else if now_s <= self.start_s {
+      Uint128::zero()
+    } else {
+      self.amount.multiply_ratio(now_s - self.start_s, self.end_s - self.start_s)
+    };
+    vested.saturating_sub(self.claimed)
+  }
+}
+
+/// Emissions a gauge accrued at its previous weight, paid out with its next claim.
+#[cw_serde]
+#[derive(Default)]
+pub struct AccruedEmission {
+  pub emission_amount: Uint128,
+  pub team_amount: Uint128,
+  pub rebase_amount: Uint128,
+}
+
+impl AccruedEmission {
+  pub fn add(&mut self, other: &AccruedEmission) {
+    self.emission_amount += other.emission_amount;
+    self.team_amount += other.team_amount;
+    self.rebase_amount += other.rebase_amount;
+  }
+
+  pub fn is_zero(&self) -> bool {
+    self.emission_amount.is_zero() && self.team_amount.is_zero() && self.rebase_amount.is_zero()
+  }
+}
+
+#[cw_serde]
+pub struct TeamVestingResponse {
+  /// vesting tranches by the period in which the team share was emitted
+  pub vestings: Vec<(u64, TeamVesting)>,
+  pub claimable: Uint128,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum EmissionSchedule {
+  /// emits emissions_per_week in every period
+  #[default]
+  Constant,
+
+  /// list of (start_period, emissions_per_week) sorted by start_period.
+  /// Before the first step, the configured emissions_per_week is used.
+  Steps(Vec<(u64, Uint128)>),
+
+  /// emissions_per_week is reduced by weekly_decay every period after start_period,
+  /// but never below the floor.
+  Decay {
+    start_period: u64,
+    weekly_decay: Decimal,
+    floor: Uint128,
+  },
+}
+
+impl EmissionSchedule {
+  /// Returns the emissions per week for the specified period.
+  pub fn emissions_per_week(&self, emissions_per_week: Uint128, period: u64) -> Uint128 {
+    match self {
+      EmissionSchedule::Constant => emissions_per_week,
+      EmissionSchedule::Steps(steps) => steps
+        .iter()
+        .take_while(|(start_period, _)| *start_period <= period)
+        .last()
+        .map(|(_, amount)| *amount)
+        .unwrap_or(emissions_per_week),
+      EmissionSchedule::Decay {
+        start_period,
+        weekly_decay,
+        floor,
+      } => {
+        let weeks = period.saturating_sub(*start_period);
+        let factor = (Decimal::one() - *weekly_decay)
+          .checked_pow(u32::try_from(weeks).unwrap_or(u32::MAX))
+          .unwrap_or_default();
+        (emissions_per_week * factor).max(*floor)
+      },
+    }
+  }
+
+  /// Returns the emission between from_s and to_s, evaluating the schedule across period boundaries.
+  pub fn emission_between(
+    &self,
+    emissions_per_week: Uint128,
+    from_s: u64,
+    to_s: u64,
+  ) -> StdResult<Uint128> {
+    let mut total = Uint256::zero();
+    let mut start_s = from_s;
+
+    while start_s < to_s {
+      let period = get_period(start_s.max(EPOCH_START))?;
+      let end_s = get_s_from_period(period + 1).min(to_s);
+      let amount = self.emissions_per_week(emissions_per_week, period);
+      total += Uint128::new((end_s - start_s).into()).full_mul(amount);
+      start_s = end_s;
+    }
+
+    Ok((total / Uint256::from(SECONDS_PER_WEEK)).try_into()?)
+  }
 }
 
 #[cw_serde]
 pub enum RebaseConfg {
   Fixed(Decimal),
 
-  // weeklyEmissions × (1 - (VP.totalSupply / 10) ÷ TOKEN.totalsupply)ˆ2 × 0.5
-  Dynamic {},
+  // weeklyEmissions × min((1 - (VP.totalSupply / divisor) ÷ TOKEN.totalsupply)ˆexponent × multiplier, max)
+  // defaults to weeklyEmissions × (1 - (VP.totalSupply / 10) ÷ TOKEN.totalsupply)ˆ2 × 0.5
+  Dynamic {
+    #[serde(default = "default_dynamic_divisor")]
+    divisor: Decimal,
+    #[serde(default = "default_dynamic_exponent")]
+    exponent: u32,
+    #[serde(default = "default_dynamic_multiplier")]
+    multiplier: Decimal,
+    #[serde(default)]
+    max: Option<Decimal>,
+  },
 
   TargetYearlyApy(Decimal),
+
+  // weeklyEmissions × max_rebase × (1 - lockRatio ÷ target_ratio), 0 if lockRatio >= target_ratio
+  // lockRatio = VE.totalFixed ÷ (TOKEN.totalsupply - balances of excluded_addrs)
+  LockedRatio {
+    target_ratio: Decimal,
+    max_rebase: Decimal,
+    #[serde(default)]
+    excluded_addrs: Vec<String>,
+  },
+}
+
+fn default_dynamic_divisor() -> Decimal {
+  Decimal::from_ratio(10u128, 1u128)
+}
+
+fn default_dynamic_exponent() -> u32 {
+  2
+}
+
+fn default_dynamic_multiplier() -> Decimal {
+  Decimal::percent(50)
+}
+
+impl RebaseConfg {
+  /// Dynamic rebase with the default formula parameters.
+  pub fn default_dynamic() -> Self {
+    RebaseConfg::Dynamic {
+      divisor: default_dynamic_divisor(),
+      exponent: default_dynamic_exponent(),
+      multiplier: default_dynamic_multiplier(),
+      max: None,
+    }
+  }
 }
 
 #[cw_serde]
@@ -59,6 +247,58 @@ pub struct Config {
   pub mint_config: MintConfig,
   pub last_claim_s: u64,
   pub emission_token: AssetInfo,
+
+  #[serde(default)]
+  pub emission_schedule: EmissionSchedule,
+
+  /// maximum amount minted over the lifetime of the connector (emission + team + rebase)
+  #[serde(default)]
+  pub max_total_emission: Option<Uint128>,
+
+  /// set when max_total_emission stopped the connector, reset by raising or removing the cap
+  #[serde(default)]
+  pub cap_reached: bool,
+
+  #[serde(default)]
+  pub team_payout: TeamPayout,
+
+  /// multi gauge mode: (gauge, weight) summing up to 1, each receiving its share of the emission.
+  /// If empty, all emissions go to the single gauge.
+  #[serde(default)]
+  pub gauges: Vec<(String, Decimal)>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct SimulateClaimResponse {
+  pub emission_amount: Uint128,
+  pub team_amount: Uint128,
+  pub rebase_amount: Uint128,
+  /// true if the amounts were reduced by max_total_emission
+  pub cap_reached: bool,
+}
+
+impl SimulateClaimResponse {
+  pub fn total(&self) -> Uint128 {
+    self.emission_amount + self.team_amount + self.rebase_amount
+  }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct State {
+  /// emissions sent to the asset staking contract
+  pub total_emitted: Uint128,
+  /// emissions sent to the team
+  pub total_team: Uint128,
+  /// emissions sent to the asset gauge as rebase
+  pub total_rebase: Uint128,
+}
+
+impl State {
+  pub fn total(&self) -> Uint128 {
+    self.total_emitted + self.total_team + self.total_rebase
+  }
 }
 
 impl Config {
@@ -76,6 +316,9 @@ pub enum ExecuteMsg {
   // Privileged functions
   ClaimRewards {},
 
+  /// Transfers the vested team share to the team wallet. Callable by anyone.
+  ClaimTeamVested {},
+
   UpdateConfig {
     emissions_per_s: Option<Uint128>,
     team_share: Option<Decimal>,
@@ -83,6 +326,16 @@ pub enum ExecuteMsg {
     mint_config: Option<MintConfig>,
     enabled: Option<bool>,
     gauge: Option<String>,
+    emission_schedule: Option<EmissionSchedule>,
+    /// None keeps the current cap. Raising the cap resumes a connector that was disabled by reaching it.
+    max_total_emission: Option<Uint128>,
+    /// removes the max total emission, also resumes a connector that was disabled by reaching it
+    #[serde(default)]
+    clear_max_total_emission: Option<bool>,
+    team_payout: Option<TeamPayout>,
+    /// changing the weights only applies from now on, emissions accrued at the previous weights
+    /// stay claimable by their gauges, also by removed ones
+    gauges: Option<Vec<(String, Decimal)>>,
   },
 }
 
@@ -91,4 +344,51 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
   #[returns(Config)]
   Config {},
+
+  #[returns(State)]
+  State {},
+
+  #[returns(TeamVestingResponse)]
+  TeamVesting {},
+
+  /// Simulates a claim of the specified seconds starting at the current block time for all gauges.
+  /// With a gauge, simulates its claim in the specified seconds from its last claim at its weight,
+  /// including what it accrued before. Ignores whether the connector is enabled.
+  #[returns(SimulateClaimResponse)]
+  SimulateClaim {
+    seconds: u64,
+    #[serde(default)]
+    gauge: Option<String>,
+  },
+
+  /// Returns the projected (period, emissions_per_week) for every period in the range (inclusive).
+  #[returns(Vec<(u64, Uint128)>)]
+  EmissionSchedule {
+    from_period: u64,
+    to_period: u64,
+  },
+}
+
+#[cfg(test)]
+mod test {
+  use cosmwasm_std::{from_json, Decimal};
+
+  use super::RebaseConfg;
+
+  #[test]
+  fn test_rebase_config_dynamic_defaults() {
+    let config: RebaseConfg = from_json(r#"{"dynamic":{}}"#).unwrap();
+    assert_eq!(config, RebaseConfg::default_dynamic());
+
+    let config: RebaseConfg = from_json(r#"{"dynamic":{"exponent":3,"max":"0.3"}}"#).unwrap();
+    assert_eq!(
+      config,
+      RebaseConfg::Dynamic {
+        divisor: Decimal::from_ratio(10u128, 1u128),
+        exponent: 3,
+        multiplier: Decimal::percent(50),
+        max: Some(Decimal::percent(30)),
+      }
+    );
+  }
 }
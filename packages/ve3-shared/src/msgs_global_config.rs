@@ -23,6 +23,29 @@ pub enum ExecuteMsg {
   ClearLists {
     lists: Vec<String>,
   },
+
+  /// Sets the delay in seconds between proposing and applying an address type.
+  /// Address types with a delay can only be changed through ProposeAddresses and ApplyAddresses.
+  /// Delays can only be increased up to 30 days, a raised delay also postpones pending changes.
+  /// Only single addresses are timelocked, address lists can always be changed by the owner.
+  SetDelays {
+    delays: Vec<(String, u64)>,
+  },
+
+  /// Proposes new addresses, an empty address removes the address type.
+  /// Proposing again for the same address type replaces the proposal and restarts the delay.
+  ProposeAddresses {
+    addresses: Vec<(String, String)>,
+  },
+
+  /// Applies the proposed addresses once their delay passed. Callable by anyone.
+  ApplyAddresses {
+    address_types: Vec<String>,
+  },
+
+  CancelProposal {
+    address_types: Vec<String>,
+  },
 }
 
 #[cw_serde]
@@ -49,11 +72,31 @@ pub enum QueryMsg {
   /// Get a single address
   #[returns(AddressListResponse)]
   AddressList(String),
+
+  /// Query all proposed address changes with pagination
+  #[returns(Vec<PendingChange>)]
+  PendingChanges {
+    start_after: Option<String>,
+    limit: Option<u32>,
+  },
+
+  /// Get all delays of address types
+  #[returns(Vec<(String, u64)>)]
+  Delays {},
 }
 
 pub type AddressResponse = (String, Addr);
 pub type AddressListResponse = Vec<Addr>;
 
+#[cw_serde]
+pub struct PendingChange {
+  pub address_type: String,
+  /// None removes the address type
+  pub address: Option<Addr>,
+  pub proposed_s: u64,
+  pub apply_after_s: u64,
+}
+
 #[cw_serde]
 pub struct MigrateMsg {
   pub clear: Option<bool>,
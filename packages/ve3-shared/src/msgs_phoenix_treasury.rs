@@ -1,3 +1,4 @@
+pub use crate::helpers::{alliance::AllianceValidatorRewards, oracle::Oracle};
 use crate::{
   adapters::{global_config_adapter::ConfigExt, zapper::Zapper},
   constants::AT_ZAPPER,
@@ -7,7 +8,7 @@ use crate::{
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Api, Decimal, QuerierWrapper, Uint128};
 use cw_address_like::AddressLike;
-use cw_asset::{Asset, AssetBase, AssetError, AssetInfo, AssetInfoBase, AssetInfoUnchecked};
+use cw_asset::{Asset, AssetInfo, AssetInfoBase, AssetInfoUnchecked};
 #[allow(unused_imports)]
 use std::collections::HashSet;
 
@@ -56,76 +57,6 @@ impl Validate<Vec<VetoRight<Addr>>> for Vec<VetoRight<String>> {
   }
 }
 
-#[cw_serde]
-pub enum Oracle<T: AddressLike> {
-  Usdc,
-  Pair {
-    contract: T,
-    simulation_amount: Uint128,
-    #[serde(default)]
-    from_decimals: Option<u32>,
-  },
-  Route {
-    contract: T,
-    path: Vec<AssetInfoBase<T>>,
-    simulation_amount: Uint128,
-    #[serde(default)]
-    from_decimals: Option<u32>,
-  },
-  RouteAsset {
-    contract: T,
-    path: Vec<AssetInfoBase<T>>,
-    simulation_amount: AssetBase<T>,
-    #[serde(default)]
-    from_decimals: Option<u32>,
-  },
-}
-
-impl Oracle<String> {
-  pub fn check(self, api: &dyn Api) -> Result<Oracle<Addr>, SharedError> {
-    Ok(match self {
-      Oracle::Usdc => Oracle::Usdc,
-      Oracle::Pair {
-        contract,
-        simulation_amount,
-        from_decimals,
-      } => Oracle::Pair {
-        contract: api.addr_validate(&contract)?,
-        simulation_amount,
-        from_decimals,
-      },
-      Oracle::Route {
-        contract,
-        path,
-        simulation_amount,
-        from_decimals,
-      } => Oracle::Route {
-        contract: api.addr_validate(&contract)?,
-        simulation_amount,
-        path: path
-          .into_iter()
-          .map(|a| a.check(api, None))
-          .collect::<Result<Vec<AssetInfo>, AssetError>>()?,
-        from_decimals,
-      },
-      Oracle::RouteAsset {
-        contract,
-        path,
-        simulation_amount,
-        from_decimals,
-      } => Oracle::RouteAsset {
-        contract: api.addr_validate(&contract)?,
-        simulation_amount: simulation_amount.check(api, None)?,
-        path: path
-          .into_iter()
-          .map(|a| a.check(api, None))
-          .collect::<Result<Vec<AssetInfo>, AssetError>>()?,
-        from_decimals,
-      },
-    })
-  }
-}
-
 #[cw_serde]
 pub enum TreasuryActionSetup {
   Payment {
@@ -390,6 +321,14 @@ pub enum QueryMsg {
   #[returns(HashSet<Addr>)]
   Validators {},
 
+  /// Returns the delegated amount per validator
+  #[returns(Vec<AllianceDelegation>)]
+  Delegations {},
+
+  /// Returns the unclaimed alliance rewards per validator
+  #[returns(Vec<AllianceValidatorRewards>)]
+  PendingAllianceRewards {},
+
   /// Returns a specific action by id
   #[returns(TreasuryAction)]
   Action {
@@ -1,5 +1,7 @@
 use crate::adapters::asset_staking::AssetStaking;
 use crate::adapters::astroport::AstroportIncentives;
+use crate::adapters::custom_staking::{check_template, CustomStaking};
+use crate::adapters::white_whale::WhiteWhaleIncentives;
 use crate::error::SharedError;
 use crate::extensions::asset_infos_ext::AssetInfosEx;
 use crate::extensions::env_ext::EnvExt;
@@ -30,6 +32,24 @@ pub enum StakeConfig<T: AddressLike> {
     contract: T,
     reward_infos: Vec<AssetInfoBase<T>>,
   },
+  /// White Whale incentive manager with a position per lp denom, only supports native lp tokens.
+  /// Positions are opened without an unlocking duration and unstaking closes and withdraws the position
+  /// in the same transaction. This only targets incentive managers that accept an unlocking duration of 0,
+  /// deployments that enforce a minimum unlocking duration are not supported.
+  /// Claiming claims the rewards of all positions of the contract, so an incentive contract can only be used by one asset.
+  WhiteWhale {
+    contract: T,
+    reward_infos: Vec<AssetInfoBase<T>>,
+  },
+  /// Any other external staker, the messages are json templates where "{amount}" and "{lp_token}" are replaced.
+  /// The deposit_msg is sent together with the asset. The templates need to be valid json after the replacement.
+  Custom {
+    contract: T,
+    deposit_msg: String,
+    withdraw_msg: String,
+    claim_msg: String,
+    reward_infos: Vec<AssetInfoBase<T>>,
+  },
 }
 
 fn track_bribes_callback_msg(
@@ -45,7 +65,7 @@ fn track_bribes_callback_msg(
 }
 
 impl StakeConfig<String> {
-  pub fn check(self, api: &dyn Api) -> Result<StakeConfig<Addr>, AssetError> {
+  pub fn check(self, api: &dyn Api) -> Result<StakeConfig<Addr>, SharedError> {
     Ok(match self {
       StakeConfig::Default => StakeConfig::Default,
       StakeConfig::Astroport {
@@ -68,6 +88,38 @@ impl StakeConfig<String> {
           .map(|a| a.check(api, None))
           .collect::<Result<Vec<_>, AssetError>>()?,
       },
+      StakeConfig::WhiteWhale {
+        contract,
+        reward_infos,
+      } => StakeConfig::WhiteWhale {
+        contract: api.addr_validate(&contract)?,
+        reward_infos: reward_infos
+          .into_iter()
+          .map(|a| a.check(api, None))
+          .collect::<Result<Vec<_>, AssetError>>()?,
+      },
+      StakeConfig::Custom {
+        contract,
+        deposit_msg,
+        withdraw_msg,
+        claim_msg,
+        reward_infos,
+      } => {
+        check_template(&deposit_msg)?;
+        check_template(&withdraw_msg)?;
+        check_template(&claim_msg)?;
+
+        StakeConfig::Custom {
+          contract: api.addr_validate(&contract)?,
+          deposit_msg,
+          withdraw_msg,
+          claim_msg,
+          reward_infos: reward_infos
+            .into_iter()
+            .map(|a| a.check(api, None))
+            .collect::<Result<Vec<_>, AssetError>>()?,
+        }
+      },
     })
   }
 }
@@ -103,6 +155,26 @@ impl StakeConfig<Addr> {
           track_bribes_callback_msg(deps, env, asset.info, reward_infos)?,
         ]
       },
+      StakeConfig::WhiteWhale {
+        contract,
+        reward_infos,
+      } => {
+        vec![
+          WhiteWhaleIncentives(contract.clone()).fill_msg(asset.clone(), 0)?,
+          track_bribes_callback_msg(deps, env, asset.info, reward_infos)?,
+        ]
+      },
+      StakeConfig::Custom {
+        contract,
+        deposit_msg,
+        reward_infos,
+        ..
+      } => {
+        vec![
+          CustomStaking(contract.clone()).deposit_msg(deposit_msg, asset.clone())?,
+          track_bribes_callback_msg(deps, env, asset.info, reward_infos)?,
+        ]
+      },
     })
   }
 
@@ -136,6 +208,27 @@ impl StakeConfig<Addr> {
           track_bribes_callback_msg(deps, env, asset.info, reward_infos)?,
         ]
       },
+      StakeConfig::WhiteWhale {
+        contract,
+        reward_infos,
+        ..
+      } => {
+        let mut msgs =
+          WhiteWhaleIncentives(contract.clone()).close_and_withdraw_msgs(asset.clone())?;
+        msgs.push(track_bribes_callback_msg(deps, env, asset.info, reward_infos)?);
+        msgs
+      },
+      StakeConfig::Custom {
+        contract,
+        withdraw_msg,
+        reward_infos,
+        ..
+      } => {
+        vec![
+          CustomStaking(contract.clone()).withdraw_msg(withdraw_msg, asset.clone())?,
+          track_bribes_callback_msg(deps, env, asset.info, reward_infos)?,
+        ]
+      },
     })
   }
 
@@ -171,6 +264,27 @@ impl StakeConfig<Addr> {
           track_bribes_callback_msg(deps, env, asset, reward_infos)?,
         ]
       },
+      StakeConfig::WhiteWhale {
+        contract,
+        reward_infos,
+        ..
+      } => {
+        vec![
+          WhiteWhaleIncentives(contract.clone()).claim_msg()?,
+          track_bribes_callback_msg(deps, env, asset, reward_infos)?,
+        ]
+      },
+      StakeConfig::Custom {
+        contract,
+        claim_msg,
+        reward_infos,
+        ..
+      } => {
+        vec![
+          CustomStaking(contract.clone()).claim_msg(claim_msg, asset.clone())?,
+          track_bribes_callback_msg(deps, env, asset, reward_infos)?,
+        ]
+      },
     })
   }
 }
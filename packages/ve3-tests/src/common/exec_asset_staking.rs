@@ -1,5 +1,5 @@
 use super::suite::TestingSuite;
-use cosmwasm_std::{to_json_binary, Addr, Coin, StdResult};
+use cosmwasm_std::{to_json_binary, Addr, Coin, StdResult, Uint128};
 use cw20::Cw20ReceiveMsg;
 use cw_asset::{Asset, AssetInfo};
 use cw_multi_test::{AppResponse, Executor};
@@ -133,6 +133,175 @@ impl TestingSuite {
     self
   }
 
+  pub fn e_staking_claim_and_compound(
+    &mut self,
+    asset_infos: Option<Vec<AssetInfo>>,
+    into: AssetInfo,
+    min_received: Option<Uint128>,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::ClaimAndCompound {
+      assets: asset_infos,
+      into,
+      min_received,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_active_staking(), &msg, &[]));
+    self
+  }
+
+  pub fn e_staking_withdraw_unbonded(
+    &mut self,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    self.e_staking_withdraw_unbonded_limit(None, sender, result)
+  }
+
+  pub fn e_staking_withdraw_unbonded_limit(
+    &mut self,
+    limit: Option<u32>,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::WithdrawUnbonded {
+      limit,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_active_staking(), &msg, &[]));
+    self
+  }
+
+  pub fn e_staking_kick(
+    &mut self,
+    user: &str,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::Kick {
+      user: self.address(user).to_string(),
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_active_staking(), &msg, &[]));
+    self
+  }
+
+  pub fn e_staking_transfer_stake(
+    &mut self,
+    asset_info: AssetInfo,
+    shares: u128,
+    recipient: &str,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::TransferStake {
+      asset: asset_info,
+      shares: shares.into(),
+      recipient: self.address(recipient).to_string(),
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_active_staking(), &msg, &[]));
+    self
+  }
+
+  pub fn e_staking_wrap_stake(
+    &mut self,
+    asset_info: AssetInfo,
+    shares: u128,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::WrapStake {
+      asset: asset_info,
+      shares: shares.into(),
+      recipient: None,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_active_staking(), &msg, &[]));
+    self
+  }
+
+  pub fn e_staking_unwrap_stake(
+    &mut self,
+    receipt: Coin,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::UnwrapStake {
+      recipient: None,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_active_staking(), &msg, &[receipt]));
+    self
+  }
+
+  pub fn e_staking_add_rewards(
+    &mut self,
+    asset_info: AssetInfo,
+    rewards: Asset,
+    duration: u64,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let funds = match &rewards.info {
+      AssetInfo::Native(_) => vec![rewards.to_coin().unwrap()],
+      _ => vec![],
+    };
+    let msg = ExecuteMsg::AddRewards {
+      asset: asset_info,
+      rewards,
+      duration,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_active_staking(), &msg, &funds));
+    self
+  }
+
+  pub fn e_staking_emergency_unstake(
+    &mut self,
+    asset_info: AssetInfo,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::EmergencyUnstake {
+      asset: asset_info,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_active_staking(), &msg, &[]));
+    self
+  }
+
+  pub fn e_staking_set_asset_paused(
+    &mut self,
+    asset_info: AssetInfo,
+    paused: bool,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::SetAssetPaused {
+      asset: asset_info,
+      paused,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_active_staking(), &msg, &[]));
+    self
+  }
+
+  pub fn e_staking_emergency_withdraw_from_external(
+    &mut self,
+    asset_info: AssetInfo,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::EmergencyWithdrawFromExternal {
+      asset: asset_info,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_active_staking(), &msg, &[]));
+    self
+  }
+
   pub fn e_staking_whitelist_assets(
     &mut self,
     asset_infos: Vec<AssetInfoWithConfig<String>>,
@@ -255,6 +424,16 @@ impl TestingSuite {
     self
   }
 
+  pub fn q_staking_paused_assets(
+    &mut self,
+    result: impl Fn(StdResult<PausedAssetsResponse>),
+  ) -> &mut Self {
+    let response =
+      self.app.wrap().query_wasm_smart(self.contract_active_staking(), &QueryMsg::PausedAssets {});
+    result(response);
+    self
+  }
+
   pub fn q_staking_reward_distribution(
     &mut self,
     result: impl Fn(StdResult<Vec<AssetDistribution>>),
@@ -368,4 +547,50 @@ impl TestingSuite {
     result(response);
     self
   }
+
+  pub fn q_staking_pending_unbonds(
+    &mut self,
+    address: &str,
+    result: impl Fn(StdResult<PendingUnbondsResponse>),
+  ) -> &mut Self {
+    let address = self.address(address).to_string();
+    let response = self.app.wrap().query_wasm_smart(
+      self.contract_active_staking(),
+      &QueryMsg::PendingUnbonds {
+        address,
+      },
+    );
+    result(response);
+    self
+  }
+
+  pub fn q_staking_working_balance(
+    &mut self,
+    asset_query: AssetQuery,
+    result: impl Fn(StdResult<WorkingBalanceRes>),
+  ) -> &mut Self {
+    let response = self
+      .app
+      .wrap()
+      .query_wasm_smart(self.contract_active_staking(), &QueryMsg::WorkingBalance(asset_query));
+    result(response);
+    self
+  }
+
+  pub fn q_staking_simulate_take(
+    &mut self,
+    asset: AssetInfo,
+    seconds: u64,
+    result: impl Fn(StdResult<SimulateTakeResponse>),
+  ) -> &mut Self {
+    let response = self.app.wrap().query_wasm_smart(
+      self.contract_active_staking(),
+      &QueryMsg::SimulateTake {
+        asset,
+        seconds,
+      },
+    );
+    result(response);
+    self
+  }
 }
@@ -1,5 +1,5 @@
 use super::suite::TestingSuite;
-use cosmwasm_std::{Addr, StdError, StdResult};
+use cosmwasm_std::{to_json_binary, Addr, Decimal, StdError, StdResult};
 use cw20::Cw20ExecuteMsg;
 use cw_asset::{Asset, AssetInfo, AssetInfoUnchecked, AssetUnchecked};
 use cw_multi_test::{AppResponse, Executor};
@@ -39,7 +39,7 @@ impl TestingSuite {
     &mut self,
     funds: Asset,
     gauge: &str,
-    for_info: AssetInfoUnchecked,
+    for_info: Option<AssetInfoUnchecked>,
     distribution: BribeDistribution,
     fees: Option<Asset>,
     sender: &str,
@@ -50,6 +50,7 @@ impl TestingSuite {
       gauge: gauge.to_string(),
       for_info,
       distribution,
+      fee_info: None,
     };
 
     let mut combined = Assets::default();
@@ -80,7 +81,7 @@ impl TestingSuite {
     &mut self,
     funds: Asset,
     gauge: &str,
-    for_info: AssetInfoUnchecked,
+    for_info: Option<AssetInfoUnchecked>,
     distribution: BribeDistribution,
     fees: Option<Asset>,
     sender: &str,
@@ -91,6 +92,7 @@ impl TestingSuite {
       gauge: gauge.to_string(),
       for_info,
       distribution,
+      fee_info: None,
     };
 
     let mut combined = Assets::default();
@@ -130,6 +132,91 @@ impl TestingSuite {
     self
   }
 
+  /// adds a native bribe and pays the fee with a cw20 fee option
+  #[allow(clippy::too_many_arguments)]
+  pub fn e_bribe_add_bribe_cw20_fee(
+    &mut self,
+    funds: Asset,
+    gauge: &str,
+    for_info: Option<AssetInfoUnchecked>,
+    distribution: BribeDistribution,
+    fee: Asset,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::AddBribe {
+      bribe: funds.clone().into(),
+      gauge: gauge.to_string(),
+      for_info,
+      distribution,
+      fee_info: Some(fee.info.clone().into()),
+    };
+
+    let sender = self.address(sender);
+    let fee_token = match &fee.info {
+      cw_asset::AssetInfoBase::Cw20(addr) => addr.clone(),
+      _ => todo!(),
+    };
+
+    self
+      .app
+      .execute_contract(
+        sender.clone(),
+        fee_token,
+        &Cw20ExecuteMsg::IncreaseAllowance {
+          spender: self.contract_bribe().to_string(),
+          amount: fee.amount,
+          expires: Some(cw20::Expiration::AtHeight(self.app.block_info().height + 1)),
+        },
+        &[],
+      )
+      .unwrap();
+
+    result(self.app.execute_contract(
+      sender,
+      self.contract_bribe(),
+      &msg,
+      &[funds.to_coin().unwrap()],
+    ));
+    self
+  }
+
+  /// adds a cw20 bribe through the receive hook, the fee is taken from the sent amount if fee_info is the bribe token
+  #[allow(clippy::too_many_arguments)]
+  pub fn e_bribe_send_bribe_cw20(
+    &mut self,
+    funds: Asset,
+    gauge: &str,
+    for_info: Option<AssetInfoUnchecked>,
+    distribution: BribeDistribution,
+    fee_info: Option<AssetInfoUnchecked>,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ReceiveMsg::AddBribe {
+      gauge: gauge.to_string(),
+      for_info,
+      distribution,
+      fee_info,
+    };
+
+    let token = if let AssetInfo::Cw20(addr) = &funds.info {
+      addr.clone()
+    } else {
+      panic!("{:?}", StdError::generic_err("not supported"))
+    };
+
+    let send_msg = Cw20ExecuteMsg::Send {
+      contract: self.contract_bribe().to_string(),
+      amount: funds.amount,
+      msg: to_json_binary(&msg).unwrap(),
+    };
+
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, token, &send_msg, &[]));
+    self
+  }
+
   pub fn e_bribe_withdraw_bribes(
     &mut self,
     period: u64,
@@ -144,6 +231,26 @@ impl TestingSuite {
     self
   }
 
+  pub fn e_bribe_withdraw_bribe(
+    &mut self,
+    period: u64,
+    gauge: &str,
+    for_info: Option<AssetInfoUnchecked>,
+    asset: AssetInfoUnchecked,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::WithdrawBribe {
+      period,
+      gauge: gauge.to_string(),
+      for_info,
+      asset,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_bribe(), &msg, &[]));
+    self
+  }
+
   pub fn e_bribe_claim_bribes(
     &mut self,
     periods: Option<Vec<u64>>,
@@ -192,6 +299,8 @@ impl TestingSuite {
   ) -> &mut TestingSuite {
     let msg = ExecuteMsg::UpdateConfig {
       fee,
+      fee_options: None,
+      bribe_fee: None,
       allow_any,
     };
     let sender = self.address(sender);
@@ -199,6 +308,24 @@ impl TestingSuite {
     self
   }
 
+  pub fn e_bribe_update_fees(
+    &mut self,
+    fee_options: Option<Vec<AssetUnchecked>>,
+    bribe_fee: Option<Decimal>,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::UpdateConfig {
+      fee: None,
+      fee_options,
+      bribe_fee,
+      allow_any: None,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_bribe(), &msg, &[]));
+    self
+  }
+
   pub fn q_bribe_config(&mut self, result: impl Fn(StdResult<Config>)) -> &mut Self {
     let response = self.app.wrap().query_wasm_smart(self.contract_bribe(), &QueryMsg::Config {});
     result(response);
@@ -251,4 +378,23 @@ impl TestingSuite {
     result(response);
     self
   }
+
+  pub fn q_bribe_creator_bribes(
+    &mut self,
+    creator: &str,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    result: impl Fn(StdResult<CreatorBribesResponse>),
+  ) -> &mut Self {
+    let response = self.app.wrap().query_wasm_smart(
+      self.contract_bribe(),
+      &QueryMsg::CreatorBribes {
+        creator: self.address(creator).to_string(),
+        start_after,
+        limit,
+      },
+    );
+    result(response);
+    self
+  }
 }
@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 
 use super::suite::TestingSuite;
-use cosmwasm_std::{coin, Addr, StdResult};
+use cosmwasm_std::{coin, Addr, Decimal, StdResult};
 use cw_multi_test::{AppResponse, Executor};
 use ve3_shared::msgs_connector_alliance::*;
 
@@ -106,6 +106,37 @@ impl TestingSuite {
     self
   }
 
+  pub fn e_alliance_set_target_delegations(
+    &mut self,
+    weights: Vec<(&str, Decimal)>,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::SetTargetDelegations {
+      weights: weights
+        .into_iter()
+        .map(|(validator, weight)| (validator.to_string(), weight))
+        .collect(),
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_connector_alliance(), &msg, &[]));
+    self
+  }
+
+  pub fn e_alliance_rebalance(
+    &mut self,
+    max_moves: Option<u32>,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::Rebalance {
+      max_moves,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_connector_alliance(), &msg, &[]));
+    self
+  }
+
   pub fn e_alliance_remove_validator(
     &mut self,
     validator: String,
@@ -148,6 +179,42 @@ impl TestingSuite {
     self
   }
 
+  pub fn q_alliance_delegations(
+    &mut self,
+    result: impl Fn(StdResult<Vec<AllianceDelegation>>),
+  ) -> &mut Self {
+    let response = self
+      .app
+      .wrap()
+      .query_wasm_smart(self.contract_connector_alliance(), &QueryMsg::Delegations {});
+    result(response);
+    self
+  }
+
+  pub fn q_alliance_pending_alliance_rewards(
+    &mut self,
+    result: impl Fn(StdResult<Vec<AllianceValidatorRewards>>),
+  ) -> &mut Self {
+    let response = self
+      .app
+      .wrap()
+      .query_wasm_smart(self.contract_connector_alliance(), &QueryMsg::PendingAllianceRewards {});
+    result(response);
+    self
+  }
+
+  pub fn q_alliance_target_delegations(
+    &mut self,
+    result: impl Fn(StdResult<Vec<(String, Decimal)>>),
+  ) -> &mut Self {
+    let response = self
+      .app
+      .wrap()
+      .query_wasm_smart(self.contract_connector_alliance(), &QueryMsg::TargetDelegations {});
+    result(response);
+    self
+  }
+
   pub fn q_alliance_state(&mut self, result: impl Fn(StdResult<StateResponse>)) -> &mut Self {
     let response =
       self.app.wrap().query_wasm_smart(self.contract_connector_alliance(), &QueryMsg::State {});
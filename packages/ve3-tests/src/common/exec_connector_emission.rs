@@ -39,16 +39,217 @@ impl TestingSuite {
       mint_config,
       enabled,
       gauge: gauge.map(|g| g.to_string()),
+      emission_schedule: None,
+      max_total_emission: None,
+      clear_max_total_emission: None,
+      team_payout: None,
+      gauges: None,
     };
     let sender = self.address(sender);
     result(self.app.execute_contract(sender, self.contract_emissions(), &msg, &[]));
     self
   }
 
+  pub fn e_emission_update_schedule(
+    &mut self,
+    emission_schedule: EmissionSchedule,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::UpdateConfig {
+      emissions_per_s: None,
+      team_share: None,
+      rebase_config: None,
+      mint_config: None,
+      enabled: None,
+      gauge: None,
+      emission_schedule: Some(emission_schedule),
+      max_total_emission: None,
+      clear_max_total_emission: None,
+      team_payout: None,
+      gauges: None,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_emissions(), &msg, &[]));
+    self
+  }
+
+  pub fn e_emission_update_max_total_emission(
+    &mut self,
+    max_total_emission: Uint128,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::UpdateConfig {
+      emissions_per_s: None,
+      team_share: None,
+      rebase_config: None,
+      mint_config: None,
+      enabled: None,
+      gauge: None,
+      emission_schedule: None,
+      max_total_emission: Some(max_total_emission),
+      clear_max_total_emission: None,
+      team_payout: None,
+      gauges: None,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_emissions(), &msg, &[]));
+    self
+  }
+
+  pub fn e_emission_clear_max_total_emission(
+    &mut self,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::UpdateConfig {
+      emissions_per_s: None,
+      team_share: None,
+      rebase_config: None,
+      mint_config: None,
+      enabled: None,
+      gauge: None,
+      emission_schedule: None,
+      max_total_emission: None,
+      clear_max_total_emission: Some(true),
+      team_payout: None,
+      gauges: None,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_emissions(), &msg, &[]));
+    self
+  }
+
+  pub fn e_emission_update_team_payout(
+    &mut self,
+    team_payout: TeamPayout,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::UpdateConfig {
+      emissions_per_s: None,
+      team_share: None,
+      rebase_config: None,
+      mint_config: None,
+      enabled: None,
+      gauge: None,
+      emission_schedule: None,
+      max_total_emission: None,
+      clear_max_total_emission: None,
+      team_payout: Some(team_payout),
+      gauges: None,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_emissions(), &msg, &[]));
+    self
+  }
+
+  pub fn e_emission_update_gauges(
+    &mut self,
+    gauges: Vec<(&str, Decimal)>,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::UpdateConfig {
+      emissions_per_s: None,
+      team_share: None,
+      rebase_config: None,
+      mint_config: None,
+      enabled: None,
+      gauge: None,
+      emission_schedule: None,
+      max_total_emission: None,
+      clear_max_total_emission: None,
+      team_payout: None,
+      gauges: Some(gauges.into_iter().map(|(gauge, weight)| (gauge.to_string(), weight)).collect()),
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_emissions(), &msg, &[]));
+    self
+  }
+
+  pub fn e_emission_claim_team_vested(
+    &mut self,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::ClaimTeamVested {};
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_emissions(), &msg, &[]));
+    self
+  }
+
+  pub fn q_emission_team_vesting(
+    &mut self,
+    result: impl Fn(StdResult<TeamVestingResponse>),
+  ) -> &mut Self {
+    let response =
+      self.app.wrap().query_wasm_smart(self.contract_emissions(), &QueryMsg::TeamVesting {});
+    result(response);
+    self
+  }
+
+  pub fn q_emission_simulate_claim(
+    &mut self,
+    seconds: u64,
+    result: impl Fn(StdResult<SimulateClaimResponse>),
+  ) -> &mut Self {
+    let response = self.app.wrap().query_wasm_smart(
+      self.contract_emissions(),
+      &QueryMsg::SimulateClaim {
+        seconds,
+        gauge: None,
+      },
+    );
+    result(response);
+    self
+  }
+
+  pub fn q_emission_simulate_claim_gauge(
+    &mut self,
+    gauge: &str,
+    seconds: u64,
+    result: impl Fn(StdResult<SimulateClaimResponse>),
+  ) -> &mut Self {
+    let response = self.app.wrap().query_wasm_smart(
+      self.contract_emissions(),
+      &QueryMsg::SimulateClaim {
+        seconds,
+        gauge: Some(gauge.to_string()),
+      },
+    );
+    result(response);
+    self
+  }
+
+  pub fn q_emission_state(&mut self, result: impl Fn(StdResult<State>)) -> &mut Self {
+    let response = self.app.wrap().query_wasm_smart(self.contract_emissions(), &QueryMsg::State {});
+    result(response);
+    self
+  }
+
   pub fn q_emission_config(&mut self, result: impl Fn(StdResult<Config>)) -> &mut Self {
     let response =
       self.app.wrap().query_wasm_smart(self.contract_emissions(), &QueryMsg::Config {});
     result(response);
     self
   }
+
+  pub fn q_emission_schedule(
+    &mut self,
+    from_period: u64,
+    to_period: u64,
+    result: impl Fn(StdResult<Vec<(u64, Uint128)>>),
+  ) -> &mut Self {
+    let response = self.app.wrap().query_wasm_smart(
+      self.contract_emissions(),
+      &QueryMsg::EmissionSchedule {
+        from_period,
+        to_period,
+      },
+    );
+    result(response);
+    self
+  }
 }
@@ -24,6 +24,16 @@ impl TestingSuite {
               contract: addr.incentive_mock.to_string(),
               reward_infos: vec![AssetInfoUnchecked::native("astro")],
             },
+            reward_stream_seconds: None,
+            unbonding_seconds: None,
+            max_boost: None,
+            tokenized: None,
+            take_tiers: None,
+            take_on_rewards: None,
+            max_total_stake: None,
+            max_stake_per_user: None,
+            apr_cap: None,
+            clear: None,
           }),
         ),
         AssetInfoUnchecked::cw20(addr.lp_cw20.clone()).into(),
@@ -120,6 +130,16 @@ impl TestingSuite {
             contract: addr.incentive_mock.to_string(),
             reward_infos: vec![AssetInfoUnchecked::native("astro")],
           },
+          reward_stream_seconds: None,
+          unbonding_seconds: None,
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: None,
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: None,
         }),
       )],
       "AT_ASSET_WHITELIST_CONTROLLER",
@@ -137,6 +157,16 @@ impl TestingSuite {
           Some(AssetConfig {
             yearly_take_rate: Some(Decimal::percent(10)),
             stake_config: ve3_shared::stake_config::StakeConfig::Default,
+            reward_stream_seconds: None,
+            unbonding_seconds: None,
+            max_boost: None,
+            tokenized: None,
+            take_tiers: None,
+            take_on_rewards: None,
+            max_total_stake: None,
+            max_stake_per_user: None,
+            apr_cap: None,
+            clear: None,
           }),
         ),
         AssetInfo::cw20(addr.lp_cw20.clone()).into(),
@@ -1,7 +1,9 @@
 use cosmwasm_std::{Addr, StdResult};
 use cw_multi_test::{AppResponse, Executor};
 use cw_ownable::{Action, Ownership};
-use ve3_shared::msgs_global_config::{AddressListResponse, AddressResponse, ExecuteMsg, QueryMsg};
+use ve3_shared::msgs_global_config::{
+  AddressListResponse, AddressResponse, ExecuteMsg, PendingChange, QueryMsg,
+};
 
 use super::suite::TestingSuite;
 
@@ -66,6 +68,62 @@ impl TestingSuite {
     self
   }
 
+  pub fn e_gc_set_delays(
+    &mut self,
+    delays: Vec<(String, u64)>,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::SetDelays {
+      delays,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_2(), &msg, &[]));
+    self
+  }
+
+  pub fn e_gc_propose_addresses(
+    &mut self,
+    addresses: Vec<(String, String)>,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::ProposeAddresses {
+      addresses,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_2(), &msg, &[]));
+    self
+  }
+
+  pub fn e_gc_apply_addresses(
+    &mut self,
+    address_types: Vec<String>,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::ApplyAddresses {
+      address_types,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_2(), &msg, &[]));
+    self
+  }
+
+  pub fn e_gc_cancel_proposal(
+    &mut self,
+    address_types: Vec<String>,
+    sender: &str,
+    result: impl Fn(Result<AppResponse, anyhow::Error>),
+  ) -> &mut TestingSuite {
+    let msg = ExecuteMsg::CancelProposal {
+      address_types,
+    };
+    let sender = self.address(sender);
+    result(self.app.execute_contract(sender, self.contract_2(), &msg, &[]));
+    self
+  }
+
   pub fn q_gc_ownership(&mut self, result: impl Fn(StdResult<Ownership<String>>)) -> &mut Self {
     let response = self.app.wrap().query_wasm_smart(self.contract_2(), &QueryMsg::Ownership {});
     result(response);
@@ -120,4 +178,27 @@ impl TestingSuite {
     result(response);
     self
   }
+
+  pub fn q_gc_pending_changes(
+    &mut self,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    result: impl Fn(StdResult<Vec<PendingChange>>),
+  ) -> &mut Self {
+    let response = self.app.wrap().query_wasm_smart(
+      self.contract_2(),
+      &QueryMsg::PendingChanges {
+        start_after,
+        limit,
+      },
+    );
+    result(response);
+    self
+  }
+
+  pub fn q_gc_delays(&mut self, result: impl Fn(StdResult<Vec<(String, u64)>>)) -> &mut Self {
+    let response = self.app.wrap().query_wasm_smart(self.contract_2(), &QueryMsg::Delays {});
+    result(response);
+    self
+  }
 }
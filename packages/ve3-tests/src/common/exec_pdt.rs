@@ -269,6 +269,25 @@ impl TestingSuite {
     self
   }
 
+  pub fn q_pdt_delegations(
+    &mut self,
+    result: impl Fn(StdResult<Vec<AllianceDelegation>>),
+  ) -> &mut Self {
+    let response = self.app.wrap().query_wasm_smart(self.contract_pdt(), &QueryMsg::Delegations {});
+    result(response);
+    self
+  }
+
+  pub fn q_pdt_pending_alliance_rewards(
+    &mut self,
+    result: impl Fn(StdResult<Vec<AllianceValidatorRewards>>),
+  ) -> &mut Self {
+    let response =
+      self.app.wrap().query_wasm_smart(self.contract_pdt(), &QueryMsg::PendingAllianceRewards {});
+    result(response);
+    self
+  }
+
   pub fn q_pdt_actions(
     &mut self,
     start_after: Option<u64>,
@@ -76,6 +76,7 @@ pub struct Addresses {
   pub fee_recipient: Addr,
 
   pub incentive_mock: Addr,
+  pub white_whale_mock: Addr,
   pub astroport_factory: Addr,
   pub astroport_pair_mock: Addr,
   pub astroport_ampluna_luna_pair: Addr,
@@ -268,6 +269,7 @@ impl TestingSuite {
       coin(1_000_000_000_000_000_000u128, "usdc".to_string()),
       coin(1_000_000_000_000_000_000u128, "lp".to_string()),
       coin(1_000_000_000_000_000_000u128, "astro".to_string()),
+      coin(1_000_000_000_000_000_000u128, "whale".to_string()),
       coin(1_000_000_000_000_000_000u128, "ibc/usdc".to_string()),
     ])
   }
@@ -384,6 +386,7 @@ impl TestingSuite {
         active_connector_alliance: Addr(""),
 
         incentive_mock: Addr(""),
+        white_whale_mock: Addr(""),
 
         astroport_factory: Addr(""),
         astroport_pair_mock: Addr(""),
@@ -490,6 +493,7 @@ impl TestingSuite {
     self.use_staking_1();
 
     self.create_incentive_mock();
+    self.create_white_whale_mock();
     self.create_astroport_pair_mock();
 
     if init.mock_zapper == Some(true) {
@@ -1,5 +1,6 @@
 use crate::mocks::{
-  alliance_rewards_mock, astroport_pair_mock, eris_hub_mock, incentive_mock, zapper_mock,
+  alliance_rewards_mock, astroport_pair_mock, eris_hub_mock, incentive_mock, white_whale_mock,
+  zapper_mock,
 };
 use cosmwasm_std::Empty;
 use cw_multi_test::{Contract, ContractWrapper};
@@ -178,6 +179,16 @@ pub fn incentive_mock() -> Box<dyn Contract<Empty>> {
   Box::new(contract)
 }
 
+pub fn white_whale_mock() -> Box<dyn Contract<Empty>> {
+  let contract = ContractWrapper::new(
+    white_whale_mock::execute,
+    white_whale_mock::instantiate,
+    white_whale_mock::query,
+  );
+
+  Box::new(contract)
+}
+
 pub fn astroport_pair() -> Box<dyn Contract<Empty>> {
   let contract = ContractWrapper::new(
     astroport_pair::contract::execute,
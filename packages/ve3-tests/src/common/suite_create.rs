@@ -1,7 +1,7 @@
 use super::helpers::{u, uluna, Addr};
 use super::suite::{InitOptions, TestingSuite};
 use crate::common::suite_contracts::*;
-use crate::mocks::{alliance_rewards_mock, astroport_pair_mock, incentive_mock};
+use crate::mocks::{alliance_rewards_mock, astroport_pair_mock, incentive_mock, white_whale_mock};
 use cosmwasm_std::{Addr, Decimal, Uint128};
 use cw20::Cw20Coin;
 use cw_asset::{AssetInfoBase, AssetInfoUnchecked, AssetUnchecked};
@@ -120,6 +120,8 @@ impl TestingSuite {
       global_config_addr: self.addresses.ve3_global_config.to_string(),
       whitelist: vec![AssetInfoUnchecked::native("uluna"), ampluna],
       fee: AssetUnchecked::native("uluna", 10_000000u128),
+      fee_options: None,
+      bribe_fee: None,
     };
 
     self.addresses.ve3_bribe_manager = self.init_contract(code_id, msg, "ve3_bribe_manager");
@@ -160,8 +162,12 @@ impl TestingSuite {
       emission_token: AssetInfoBase::Native("uluna".to_string()),
       emissions_per_week: u(100),
       mint_config: msgs_connector_emission::MintConfig::MintDirect,
-      rebase_config: msgs_connector_emission::RebaseConfg::Dynamic {},
+      rebase_config: msgs_connector_emission::RebaseConfg::default_dynamic(),
       team_share: Decimal::percent(10),
+      emission_schedule: None,
+      max_total_emission: None,
+      team_payout: None,
+      gauges: None,
     };
 
     self.addresses.ve3_connector_emissions =
@@ -314,6 +320,31 @@ impl TestingSuite {
       .unwrap();
   }
 
+  pub(super) fn create_white_whale_mock(&mut self) {
+    let code_id = self.app.store_code(white_whale_mock());
+
+    let whale = AssetInfoBase::Native("whale".to_string());
+    let msg = white_whale_mock::InstantiateMsg {
+      config: white_whale_mock::Config {
+        emission: whale.clone(),
+        per_week: u(10000),
+      },
+    };
+
+    self.addresses.white_whale_mock = self.init_contract(code_id, msg, "white_whale_mock");
+
+    self
+      .app
+      .execute(
+        self.address("creator"),
+        whale
+          .with_balance(Uint128::new(1_000_000_000_000u128))
+          .transfer_msg(self.addresses.white_whale_mock.to_string())
+          .unwrap(),
+      )
+      .unwrap();
+  }
+
   pub(super) fn create_astroport_pair_mock(&mut self) {
     let code_id = self.app.store_code(astroport_pair_mock());
     let msg = astroport_pair_mock::InstantiateMsg {
@@ -82,6 +82,10 @@ impl TestingSuite {
       mint_config: msgs_connector_emission::MintConfig::MintDirect,
       rebase_config: msgs_connector_emission::RebaseConfg::Fixed(Decimal::percent(10)),
       team_share: Decimal::percent(10),
+      emission_schedule: None,
+      max_total_emission: None,
+      team_payout: None,
+      gauges: None,
     };
     let emission_connector = self
       .app
@@ -3,4 +3,5 @@ pub mod astroport_pair_mock;
 pub mod eris_hub_mock;
 pub mod incentive_mock;
 pub mod stargate_mock;
+pub mod white_whale_mock;
 pub mod zapper_mock;
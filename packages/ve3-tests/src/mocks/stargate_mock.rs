@@ -6,13 +6,30 @@ use anyhow::Result as AnyResult;
 use cosmwasm_schema::schemars::JsonSchema;
 use cosmwasm_schema::serde::de::DeserializeOwned;
 use cosmwasm_std::{
-  coins, to_json_binary, Addr, Api, BankMsg, Binary, BlockInfo, CustomQuery, Empty, Querier,
-  Storage, SubMsgResponse, Uint128,
+  coins, to_json_binary, Addr, Api, BankMsg, Binary, BlockInfo, CustomQuery, Empty, Order, Querier,
+  StdResult, Storage, SubMsgResponse, Uint128,
 };
 use cw_multi_test::{
   AppResponse, BankSudo, CosmosRouter, Module, Stargate, StargateMsg, StargateQuery,
 };
-use ve3_shared::helpers::denom::{MsgBurn, MsgCreateDenom, MsgCreateDenomResponse, MsgMint};
+use cw_storage_plus::Map;
+use terra_proto_rs::alliance::alliance::{MsgDelegate, MsgRedelegate, MsgUndelegate};
+use terra_proto_rs::traits::Message;
+use ve3_shared::helpers::alliance::{
+  Delegation, DelegationResponse, QueryAllianceDelegationRequest, QueryAllianceDelegationResponse,
+  QueryAllianceDelegationRewardsRequest, QueryAllianceDelegationRewardsResponse,
+  QueryAlliancesDelegationsRequest, QueryAlliancesDelegationsResponse,
+};
+use ve3_shared::helpers::denom::{Coin, MsgBurn, MsgCreateDenom, MsgCreateDenomResponse, MsgMint};
+
+// (delegator, validator, denom) -> amount
+const ALLIANCE_DELEGATIONS: Map<(&str, &str, &str), Uint128> =
+  Map::new("mock_alliance_delegations");
+// (delegator, validator) -> time when the incoming redelegation matured
+const ALLIANCE_REDELEGATIONS: Map<(&str, &str), u64> = Map::new("mock_alliance_redelegations");
+const ALLIANCE_UNBONDING_S: u64 = 21 * 24 * 60 * 60;
+// small page size, so that the pagination of the delegations is used
+const ALLIANCE_DELEGATIONS_MAX_LIMIT: u64 = 2;
 
 pub struct StargateMockModule {}
 
@@ -82,6 +99,56 @@ impl Module for StargateMockModule {
         };
         router.execute(api, storage, block, Addr::unchecked(tf_msg.sender), burn_msg.into())
       },
+      // delegations are only tracked, the alliance tokens are not moved
+      "/alliance.alliance.MsgDelegate" => {
+        let msg = MsgDelegate::decode(msg.value.as_slice())?;
+        let coin = msg.amount.unwrap_or_default();
+        let key =
+          (msg.delegator_address.as_str(), msg.validator_address.as_str(), coin.denom.as_str());
+        let amount = Uint128::from_str(&coin.amount)?;
+        ALLIANCE_DELEGATIONS.update(storage, key, |current| -> StdResult<_> {
+          Ok(current.unwrap_or_default() + amount)
+        })?;
+        Ok(AppResponse::default())
+      },
+      "/alliance.alliance.MsgUndelegate" => {
+        let msg = MsgUndelegate::decode(msg.value.as_slice())?;
+        let coin = msg.amount.unwrap_or_default();
+        let key =
+          (msg.delegator_address.as_str(), msg.validator_address.as_str(), coin.denom.as_str());
+        let amount = Uint128::from_str(&coin.amount)?;
+        let current = ALLIANCE_DELEGATIONS.may_load(storage, key)?.unwrap_or_default();
+        ALLIANCE_DELEGATIONS.save(storage, key, &current.checked_sub(amount)?)?;
+        Ok(AppResponse::default())
+      },
+      "/alliance.alliance.MsgRedelegate" => {
+        let msg = MsgRedelegate::decode(msg.value.as_slice())?;
+        let coin = msg.amount.unwrap_or_default();
+        let delegator = msg.delegator_address.as_str();
+        let src = msg.validator_src_address.as_str();
+        let dst = msg.validator_dst_address.as_str();
+        let amount = Uint128::from_str(&coin.amount)?;
+
+        let matured_s = ALLIANCE_REDELEGATIONS.may_load(storage, (delegator, src))?;
+        if matured_s.map_or(false, |matured_s| matured_s > block.time.seconds()) {
+          return Err(anyhow::anyhow!("redelegation to {src} is not yet matured"));
+        }
+
+        let src_key = (delegator, src, coin.denom.as_str());
+        let current = ALLIANCE_DELEGATIONS.may_load(storage, src_key)?.unwrap_or_default();
+        ALLIANCE_DELEGATIONS.save(storage, src_key, &current.checked_sub(amount)?)?;
+        ALLIANCE_DELEGATIONS.update(
+          storage,
+          (delegator, dst, coin.denom.as_str()),
+          |current| -> StdResult<_> { Ok(current.unwrap_or_default() + amount) },
+        )?;
+        ALLIANCE_REDELEGATIONS.save(
+          storage,
+          (delegator, dst),
+          &(block.time.seconds() + ALLIANCE_UNBONDING_S),
+        )?;
+        Ok(AppResponse::default())
+      },
       _ => Err(anyhow::anyhow!("Unexpected exec msg {type_url} from {sender:?}",)),
     }
   }
@@ -89,12 +156,96 @@ impl Module for StargateMockModule {
   fn query(
     &self,
     _api: &dyn Api,
-    _storage: &dyn Storage,
+    storage: &dyn Storage,
     _querier: &dyn Querier,
     _block: &BlockInfo,
-    _request: Self::QueryT,
+    request: Self::QueryT,
   ) -> AnyResult<Binary> {
-    todo!()
+    match request.path.as_str() {
+      "/alliance.alliance.Query/AlliancesDelegation" => {
+        let request: QueryAlliancesDelegationsRequest = request.data.try_into()?;
+        let delegator = request.delegator_addr.as_str();
+        let (offset, limit) = request
+          .pagination
+          .map(|page| (page.offset, page.limit))
+          .unwrap_or((0, ALLIANCE_DELEGATIONS_MAX_LIMIT));
+        let limit = limit.clamp(1, ALLIANCE_DELEGATIONS_MAX_LIMIT);
+        let delegations = ALLIANCE_DELEGATIONS
+          .sub_prefix(delegator)
+          .range(storage, None, None, Order::Ascending)
+          .filter(|item| !matches!(item, Ok((_, amount)) if amount.is_zero()))
+          .skip(offset as usize)
+          .take(limit as usize)
+          .map(|item| {
+            let ((validator, denom), amount) = item?;
+            Ok(DelegationResponse {
+              delegation: Some(Delegation {
+                delegator_address: delegator.to_string(),
+                validator_address: validator,
+                denom: denom.clone(),
+                shares: amount.to_string(),
+              }),
+              balance: Some(Coin {
+                denom,
+                amount: amount.to_string(),
+              }),
+            })
+          })
+          .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(to_json_binary(&QueryAlliancesDelegationsResponse {
+          delegations,
+        })?)
+      },
+      "/alliance.alliance.Query/AllianceDelegation" => {
+        let request: QueryAllianceDelegationRequest = request.data.try_into()?;
+        let key = (
+          request.delegator_addr.as_str(),
+          request.validator_addr.as_str(),
+          request.denom.as_str(),
+        );
+        let amount = ALLIANCE_DELEGATIONS.may_load(storage, key)?.unwrap_or_default();
+        if amount.is_zero() {
+          return Err(anyhow::anyhow!("alliance delegation not found"));
+        }
+
+        Ok(to_json_binary(&QueryAllianceDelegationResponse {
+          delegation: Some(DelegationResponse {
+            delegation: Some(Delegation {
+              delegator_address: request.delegator_addr,
+              validator_address: request.validator_addr,
+              denom: request.denom.clone(),
+              shares: amount.to_string(),
+            }),
+            balance: Some(Coin {
+              denom: request.denom,
+              amount: amount.to_string(),
+            }),
+          }),
+        })?)
+      },
+      // rewards are mocked as 10% of the delegated amount in uluna
+      "/alliance.alliance.Query/AllianceDelegationRewards" => {
+        let request: QueryAllianceDelegationRewardsRequest = request.data.try_into()?;
+        let key = (
+          request.delegator_addr.as_str(),
+          request.validator_addr.as_str(),
+          request.denom.as_str(),
+        );
+        let amount = ALLIANCE_DELEGATIONS.may_load(storage, key)?.unwrap_or_default();
+        if amount.is_zero() {
+          return Err(anyhow::anyhow!("alliance delegation not found"));
+        }
+
+        Ok(to_json_binary(&QueryAllianceDelegationRewardsResponse {
+          rewards: vec![Coin {
+            denom: "uluna".to_string(),
+            amount: amount.multiply_ratio(1u128, 10u128).to_string(),
+          }],
+        })?)
+      },
+      path => Err(anyhow::anyhow!("Unexpected stargate query request {path}")),
+    }
   }
 
   fn sudo<ExecC, QueryC>(
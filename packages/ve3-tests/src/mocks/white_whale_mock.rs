@@ -0,0 +1,207 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+  attr, entry_point, Addr, Attribute, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order,
+  Response, StdError, StdResult, Uint128,
+};
+use cw_asset::{Asset, AssetInfo};
+use cw_storage_plus::{Item, Map};
+use ve3_shared::{
+  adapters::white_whale::{PositionAction, WhiteWhaleExecuteMsg},
+  constants::SECONDS_PER_WEEK,
+  error::SharedError,
+  extensions::asset_info_ext::AssetInfoExt,
+};
+pub type ContractResult = Result<Response, SharedError>;
+
+pub type ExecuteMsg = WhiteWhaleExecuteMsg;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+  pub config: Config,
+}
+
+#[cw_serde]
+pub enum QueryMsg {}
+
+#[cw_serde]
+pub struct Config {
+  pub emission: AssetInfo,
+  pub per_week: Uint128,
+}
+
+#[cw_serde]
+pub struct Position {
+  pub denom: String,
+  pub amount: Uint128,
+  pub unlocking_duration: u64,
+}
+
+#[cw_serde]
+pub struct Unlocking {
+  pub denom: String,
+  pub amount: Uint128,
+  pub unlocked_s: u64,
+}
+
+#[entry_point]
+pub fn instantiate(
+  deps: DepsMut,
+  _env: Env,
+  _info: MessageInfo,
+  msg: InstantiateMsg,
+) -> ContractResult {
+  CONFIG.save(deps.storage, &msg.config)?;
+  Ok(Response::new())
+}
+
+const POSITIONS: Map<(Addr, &str), Position> = Map::new("positions");
+const UNLOCKING: Map<(Addr, &str), Vec<Unlocking>> = Map::new("unlocking");
+// emissions are shared by all open positions, independent of the denom
+const TOTAL: Item<Uint128> = Item::new("total");
+const LAST_CLAIM: Map<Addr, u64> = Map::new("last_claim");
+const CONFIG: Item<Config> = Item::new("config");
+
+#[entry_point]
+pub fn execute(mut deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> ContractResult {
+  let mut msgs: Vec<CosmosMsg> = vec![];
+  let mut attrs: Vec<Attribute> = vec![];
+  let sender = info.sender;
+
+  let action = match msg {
+    ExecuteMsg::Claim {} => {
+      _claim(&mut msgs, &mut deps, &env, sender)?;
+      "mock/ww_claim"
+    },
+    ExecuteMsg::ManagePosition {
+      action,
+    } => match action {
+      PositionAction::Fill {
+        identifier,
+        unlocking_duration,
+        receiver,
+      } => {
+        if info.funds.len() != 1 {
+          Err(StdError::generic_err("funds wrong"))?;
+        }
+        let coin = info.funds[0].clone();
+        let identifier = identifier.unwrap_or(coin.denom.clone());
+        let owner = match receiver {
+          Some(receiver) => deps.api.addr_validate(&receiver)?,
+          None => sender,
+        };
+
+        _claim(&mut msgs, &mut deps, &env, owner.clone())?;
+
+        let mut position =
+          POSITIONS.may_load(deps.storage, (owner.clone(), &identifier))?.unwrap_or(Position {
+            denom: coin.denom.clone(),
+            amount: Uint128::zero(),
+            unlocking_duration,
+          });
+        if position.denom != coin.denom {
+          Err(StdError::generic_err("wrong lp denom for position"))?;
+        }
+        position.amount += coin.amount;
+        POSITIONS.save(deps.storage, (owner, &identifier), &position)?;
+        let total = TOTAL.may_load(deps.storage)?.unwrap_or_default();
+        TOTAL.save(deps.storage, &(total + coin.amount))?;
+
+        attrs.push(attr("mock/amount", coin.to_string()));
+        "mock/ww_fill"
+      },
+      PositionAction::Close {
+        identifier,
+        lp_asset,
+      } => {
+        _claim(&mut msgs, &mut deps, &env, sender.clone())?;
+
+        let mut position = POSITIONS.load(deps.storage, (sender.clone(), &identifier))?;
+        let amount = lp_asset.map(|coin| coin.amount).unwrap_or(position.amount);
+        position.amount = position
+          .amount
+          .checked_sub(amount)
+          .map_err(|_| StdError::generic_err("position too small"))?;
+
+        if position.amount.is_zero() {
+          POSITIONS.remove(deps.storage, (sender.clone(), &identifier));
+        } else {
+          POSITIONS.save(deps.storage, (sender.clone(), &identifier), &position)?;
+        }
+        let total = TOTAL.load(deps.storage)?;
+        TOTAL.save(deps.storage, &(total - amount))?;
+
+        let mut unlocking =
+          UNLOCKING.may_load(deps.storage, (sender.clone(), &identifier))?.unwrap_or_default();
+        unlocking.push(Unlocking {
+          denom: position.denom.clone(),
+          amount,
+          unlocked_s: env.block.time.seconds() + position.unlocking_duration,
+        });
+        UNLOCKING.save(deps.storage, (sender, &identifier), &unlocking)?;
+
+        attrs.push(attr("mock/amount", format!("{amount}{0}", position.denom)));
+        "mock/ww_close"
+      },
+      PositionAction::Withdraw {
+        identifier,
+        ..
+      } => {
+        let now = env.block.time.seconds();
+        let unlocking =
+          UNLOCKING.may_load(deps.storage, (sender.clone(), &identifier))?.unwrap_or_default();
+        let (unlocked, pending): (Vec<_>, Vec<_>) =
+          unlocking.into_iter().partition(|unlocking| unlocking.unlocked_s <= now);
+
+        if unlocked.is_empty() {
+          Err(StdError::generic_err("nothing unlocked"))?;
+        }
+
+        if pending.is_empty() {
+          UNLOCKING.remove(deps.storage, (sender.clone(), &identifier));
+        } else {
+          UNLOCKING.save(deps.storage, (sender.clone(), &identifier), &pending)?;
+        }
+
+        for unlocked in unlocked {
+          let asset = Asset::native(unlocked.denom, unlocked.amount);
+          attrs.push(attr("mock/amount", asset.to_string()));
+          msgs.push(asset.transfer_msg(sender.clone())?);
+        }
+        "mock/ww_withdraw"
+      },
+    },
+  };
+
+  Ok(Response::new().add_attribute("action", action).add_attributes(attrs).add_messages(msgs))
+}
+
+fn _claim(msgs: &mut Vec<CosmosMsg>, deps: &mut DepsMut, env: &Env, user: Addr) -> StdResult<()> {
+  let now = env.block.time.seconds();
+  let last = LAST_CLAIM.may_load(deps.storage, user.clone())?.unwrap_or(now);
+  LAST_CLAIM.save(deps.storage, user.clone(), &now)?;
+
+  let owned = POSITIONS
+    .prefix(user.clone())
+    .range(deps.storage, None, None, Order::Ascending)
+    .map(|item| item.map(|(_, position)| position.amount))
+    .sum::<StdResult<Uint128>>()?;
+  let total = TOTAL.may_load(deps.storage)?.unwrap_or_default();
+
+  if owned.is_zero() || total.is_zero() {
+    return Ok(());
+  }
+
+  let config = CONFIG.load(deps.storage)?;
+  let emissions = config.per_week.multiply_ratio(now - last, SECONDS_PER_WEEK);
+  let share = emissions.multiply_ratio(owned, total);
+  if !share.is_zero() {
+    msgs.push(config.emission.with_balance(share).transfer_msg(user).unwrap())
+  }
+
+  Ok(())
+}
+
+#[entry_point]
+pub fn query(_deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+  match msg {}
+}
@@ -1,15 +1,17 @@
 use crate::{
   common::{
-    helpers::{native_info, u},
+    helpers::{native, native_info, u},
     suite::TestingSuite,
   },
   extensions::app_response_ext::{EventChecker, Valid},
 };
-use cosmwasm_std::{attr, Decimal};
+use cosmwasm_std::{attr, coin, Addr, Decimal};
 use cw_asset::{AssetInfo, AssetInfoUnchecked};
-use ve3_asset_staking::error::ContractError;
+use ve3_asset_staking::{constants::MAX_PENDING_UNBONDS, error::ContractError};
 use ve3_shared::{
-  constants::AT_ASSET_WHITELIST_CONTROLLER, error::SharedError, msgs_asset_staking::*,
+  constants::{AT_ASSET_WHITELIST_CONTROLLER, AT_VE_GUARDIAN},
+  error::SharedError,
+  msgs_asset_staking::*,
 };
 
 #[test]
@@ -89,6 +91,16 @@ fn test_asset_config() {
               contract: addr.incentive_mock.to_string(),
               reward_infos: vec![AssetInfoUnchecked::native("astro")],
             },
+            reward_stream_seconds: None,
+            unbonding_seconds: None,
+            max_boost: None,
+            tokenized: None,
+            take_tiers: None,
+            take_on_rewards: None,
+            max_total_stake: None,
+            max_stake_per_user: None,
+            apr_cap: None,
+            clear: None,
           }),
         ),
         AssetInfo::cw20(addr.lp_cw20.clone()).into(),
@@ -117,7 +129,16 @@ fn test_asset_config() {
               stake_config: ve3_shared::stake_config::StakeConfig::Default,
               last_taken_s: 0,
               taken: u(0),
-              harvested: u(0)
+              harvested: u(0),
+              reward_stream_seconds: 0,
+              unbonding_seconds: 0,
+              max_boost: None,
+              receipt_denom: None,
+              take_tiers: vec![],
+              take_on_rewards: None,
+              max_total_stake: None,
+              max_stake_per_user: None,
+              apr_cap: None,
             }
           },
           AssetInfoWithRuntime {
@@ -131,7 +152,16 @@ fn test_asset_config() {
               },
               last_taken_s: 0,
               taken: u(0),
-              harvested: u(0)
+              harvested: u(0),
+              reward_stream_seconds: 0,
+              unbonding_seconds: 0,
+              max_boost: None,
+              receipt_denom: None,
+              take_tiers: vec![],
+              take_on_rewards: None,
+              max_total_stake: None,
+              max_stake_per_user: None,
+              apr_cap: None,
             }
           },
         ]
@@ -185,6 +215,16 @@ fn test_asset_set_config() {
             contract: addr.incentive_mock.to_string(),
             reward_infos: vec![AssetInfoUnchecked::native("astro")],
           },
+          reward_stream_seconds: None,
+          unbonding_seconds: None,
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: None,
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: None,
         }),
       },
       "AT_ASSET_WHITELIST_CONTROLLER",
@@ -307,6 +347,15 @@ fn test_asset_unstake() {
               last_taken_s: 1712242800,
               taken: u(0),
               harvested: u(0),
+              reward_stream_seconds: 0,
+              unbonding_seconds: 0,
+              max_boost: None,
+              receipt_denom: None,
+              take_tiers: vec![],
+              take_on_rewards: None,
+              max_total_stake: None,
+              max_stake_per_user: None,
+              apr_cap: None,
               yearly_take_rate: Decimal::percent(10),
               stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
                 contract: addr.incentive_mock.clone(),
@@ -334,6 +383,15 @@ fn test_asset_unstake() {
               last_taken_s: 1712847600,
               taken: u(19178),
               harvested: u(0),
+              reward_stream_seconds: 0,
+              unbonding_seconds: 0,
+              max_boost: None,
+              receipt_denom: None,
+              take_tiers: vec![],
+              take_on_rewards: None,
+              max_total_stake: None,
+              max_stake_per_user: None,
+              apr_cap: None,
               yearly_take_rate: Decimal::percent(10),
               stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
                 contract: addr.incentive_mock.clone(),
@@ -360,6 +418,15 @@ fn test_asset_unstake() {
               last_taken_s: 1712847600,
               taken: u(19178),
               harvested: u(0),
+              reward_stream_seconds: 0,
+              unbonding_seconds: 0,
+              max_boost: None,
+              receipt_denom: None,
+              take_tiers: vec![],
+              take_on_rewards: None,
+              max_total_stake: None,
+              max_stake_per_user: None,
+              apr_cap: None,
               yearly_take_rate: Decimal::percent(10),
               stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
                 contract: addr.incentive_mock.clone(),
@@ -507,6 +574,15 @@ fn test_asset_second_user() {
               last_taken_s: 1712847600,
               taken: u(19178),
               harvested: u(0),
+              reward_stream_seconds: 0,
+              unbonding_seconds: 0,
+              max_boost: None,
+              receipt_denom: None,
+              take_tiers: vec![],
+              take_on_rewards: None,
+              max_total_stake: None,
+              max_stake_per_user: None,
+              apr_cap: None,
               yearly_take_rate: Decimal::percent(10),
               stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
                 contract: addr.incentive_mock.clone(),
@@ -583,6 +659,15 @@ fn test_asset_recipient() {
               last_taken_s: 1712847600,
               taken: u(19178),
               harvested: u(0),
+              reward_stream_seconds: 0,
+              unbonding_seconds: 0,
+              max_boost: None,
+              receipt_denom: None,
+              take_tiers: vec![],
+              take_on_rewards: None,
+              max_total_stake: None,
+              max_stake_per_user: None,
+              apr_cap: None,
               yearly_take_rate: Decimal::percent(10),
               stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
                 contract: addr.incentive_mock.clone(),
@@ -608,6 +693,15 @@ fn test_asset_recipient() {
             last_taken_s: 1712847600,
             taken: u(19178),
             harvested: u(0),
+            reward_stream_seconds: 0,
+            unbonding_seconds: 0,
+            max_boost: None,
+            receipt_denom: None,
+            take_tiers: vec![],
+            take_on_rewards: None,
+            max_total_stake: None,
+            max_stake_per_user: None,
+            apr_cap: None,
             yearly_take_rate: Decimal::percent(10),
             stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
               contract: addr.incentive_mock.clone(),
@@ -638,6 +732,15 @@ fn test_asset_recipient() {
             last_taken_s: 1712847600,
             taken: u(19178),
             harvested: u(0),
+            reward_stream_seconds: 0,
+            unbonding_seconds: 0,
+            max_boost: None,
+            receipt_denom: None,
+            take_tiers: vec![],
+            take_on_rewards: None,
+            max_total_stake: None,
+            max_stake_per_user: None,
+            apr_cap: None,
             yearly_take_rate: Decimal::percent(10),
             stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
               contract: addr.incentive_mock.clone(),
@@ -664,6 +767,15 @@ fn test_asset_recipient() {
               last_taken_s: 1718895600,
               taken: u(19178),
               harvested: u(0),
+              reward_stream_seconds: 0,
+              unbonding_seconds: 0,
+              max_boost: None,
+              receipt_denom: None,
+              take_tiers: vec![],
+              take_on_rewards: None,
+              max_total_stake: None,
+              max_stake_per_user: None,
+              apr_cap: None,
               yearly_take_rate: Decimal::percent(10),
               stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
                 contract: addr.incentive_mock.clone(),
@@ -685,6 +797,15 @@ fn test_asset_recipient() {
             last_taken_s: 1718895600,
             taken: u(19178),
             harvested: u(0),
+            reward_stream_seconds: 0,
+            unbonding_seconds: 0,
+            max_boost: None,
+            receipt_denom: None,
+            take_tiers: vec![],
+            take_on_rewards: None,
+            max_total_stake: None,
+            max_stake_per_user: None,
+            apr_cap: None,
             yearly_take_rate: Decimal::percent(10),
             stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
               contract: addr.incentive_mock.clone(),
@@ -712,3 +833,544 @@ fn test_asset_recipient() {
       },
     );
 }
+
+#[test]
+fn test_asset_unbonding() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+
+  suite
+    .def_setup_staking()
+    .e_staking_update_asset_config(
+      AssetInfoWithConfig {
+        info: addr.lp_cw20_info(),
+        config: Some(AssetConfig {
+          yearly_take_rate: Some(Decimal::zero()),
+          stake_config: ve3_shared::stake_config::StakeConfig::Default,
+          reward_stream_seconds: None,
+          unbonding_seconds: Some(100),
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: None,
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: None,
+        }),
+      },
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    // values that are not provided keep the current config
+    .e_staking_update_asset_config(
+      AssetInfoWithConfig {
+        info: addr.lp_cw20_info(),
+        config: Some(AssetConfig {
+          yearly_take_rate: Some(Decimal::zero()),
+          stake_config: ve3_shared::stake_config::StakeConfig::Default,
+          reward_stream_seconds: None,
+          unbonding_seconds: None,
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: Some(u(10000)),
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: None,
+        }),
+      },
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .q_staking_whitelisted_asset_details(|res| {
+      let details = res.unwrap();
+      let config = &details.iter().find(|a| a.info == addr.lp_cw20_info_checked()).unwrap().config;
+      assert_eq!(config.unbonding_seconds, 100);
+      assert_eq!(config.max_total_stake, Some(u(10000)));
+    })
+    .e_staking_stake(None, addr.lp_cw20(1000), "user1", |res| res.assert_valid());
+
+  let release_at_s = suite.app.block_info().time.seconds() + 100;
+
+  suite
+    .e_staking_unstake(addr.lp_cw20(400), "user1", |res| {
+      res.assert_attribute(attr("action", "asset/unstake"));
+      res.assert_attribute(attr("amount", "400"));
+      res.assert_attribute(attr("release_at_s", release_at_s.to_string()));
+    })
+    .q_staking_pending_unbonds("user1", |res| {
+      assert_eq!(
+        res.unwrap(),
+        vec![PendingUnbond {
+          asset: addr.lp_cw20(400),
+          release_at_s,
+          recipient: None,
+        }]
+      )
+    })
+    // unbonding shares are not staked anymore
+    .q_staking_staked_balance(
+      AssetQuery {
+        address: addr.user1.to_string(),
+        asset: addr.lp_cw20_info_checked(),
+      },
+      |res| assert_eq!(res.unwrap().shares, u(600)),
+    )
+    .e_staking_withdraw_unbonded("user1", |res| res.assert_error(ContractError::NothingUnbonded {}))
+    .add_seconds(100)
+    .e_staking_withdraw_unbonded("user1", |res| {
+      res.assert_attribute(attr("action", "asset/withdraw_unbonded"));
+      res.assert_attribute(attr("withdrawn", addr.lp_cw20(400).to_string()));
+      res.assert_attribute(attr("action", "transfer"));
+      res.assert_attribute(attr("amount", "400"));
+    })
+    .q_staking_pending_unbonds("user1", |res| assert_eq!(res.unwrap(), vec![]))
+    .e_staking_withdraw_unbonded("user1", |res| {
+      res.assert_error(ContractError::NothingUnbonded {})
+    });
+}
+
+#[test]
+fn test_asset_unbonding_many() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+
+  suite
+    .def_setup_staking()
+    .e_staking_update_asset_config(
+      AssetInfoWithConfig {
+        info: addr.lp_cw20_info(),
+        config: Some(AssetConfig {
+          yearly_take_rate: Some(Decimal::zero()),
+          stake_config: ve3_shared::stake_config::StakeConfig::Default,
+          reward_stream_seconds: None,
+          unbonding_seconds: Some(100),
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: None,
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: None,
+        }),
+      },
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .e_staking_stake(None, addr.lp_cw20(1000), "user1", |res| res.assert_valid())
+    .e_staking_stake(None, addr.lp_cw20(1000), "user2", |res| res.assert_valid())
+    // unbonds for another recipient are kept by the sender
+    .e_staking_unstake_recipient(addr.lp_cw20(1), "user2", "user1", |res| res.assert_valid())
+    .q_staking_pending_unbonds("user1", |res| assert_eq!(res.unwrap(), vec![]))
+    .q_staking_pending_unbonds("user2", |res| {
+      assert_eq!(res.unwrap()[0].recipient, Some(addr.user1.clone()))
+    })
+    // unbonds released at the same time are merged
+    .e_staking_unstake(addr.lp_cw20(10), "user1", |res| res.assert_valid())
+    .e_staking_unstake(addr.lp_cw20(10), "user1", |res| res.assert_valid())
+    .q_staking_pending_unbonds("user1", |res| {
+      let unbonds = res.unwrap();
+      assert_eq!(unbonds.len(), 1);
+      assert_eq!(unbonds[0].asset, addr.lp_cw20(20));
+    });
+
+  for _ in 1..MAX_PENDING_UNBONDS {
+    suite.add_seconds(1).e_staking_unstake(addr.lp_cw20(1), "user1", |res| res.assert_valid());
+  }
+
+  suite
+    .add_seconds(1)
+    .e_staking_unstake(addr.lp_cw20(1), "user1", |res| {
+      res.assert_error(ContractError::TooManyPendingUnbonds(MAX_PENDING_UNBONDS))
+    })
+    .add_seconds(100)
+    .e_staking_withdraw_unbonded_limit(Some(10), "user1", |res| {
+      res.assert_attribute(attr("withdrawn", addr.lp_cw20(29).to_string()));
+    })
+    .q_staking_pending_unbonds("user1", |res| {
+      assert_eq!(res.unwrap().len(), MAX_PENDING_UNBONDS - 10)
+    })
+    .e_staking_withdraw_unbonded("user1", |res| {
+      res.assert_attribute(attr("withdrawn", addr.lp_cw20(20).to_string()));
+    })
+    .q_staking_pending_unbonds("user1", |res| assert_eq!(res.unwrap(), vec![]))
+    .e_staking_withdraw_unbonded("user2", |res| {
+      res.assert_attribute(attr("user", addr.user2.to_string()));
+      res.assert_attribute(attr("withdrawn", addr.lp_cw20(1).to_string()));
+      res.assert_attribute(attr("to", addr.user1.to_string()));
+    });
+}
+
+#[test]
+fn test_transfer_and_wrap_stake() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  let receipt_denom = format!("factory/{0}/0/stake", addr.ve3_asset_staking_2);
+  let tokenized_config = |tokenized: bool| AssetInfoWithConfig {
+    info: addr.lp_cw20_info(),
+    config: Some(AssetConfig {
+      yearly_take_rate: Some(Decimal::zero()),
+      stake_config: ve3_shared::stake_config::StakeConfig::Default,
+      reward_stream_seconds: None,
+      unbonding_seconds: None,
+      max_boost: None,
+      tokenized: Some(tokenized),
+      take_tiers: None,
+      take_on_rewards: None,
+      max_total_stake: None,
+      max_stake_per_user: None,
+      apr_cap: None,
+      clear: None,
+    }),
+  };
+  let shares_of = |user: &Addr| AssetQuery {
+    address: user.to_string(),
+    asset: addr.lp_cw20_info_checked(),
+  };
+
+  suite
+    .def_setup_staking()
+    .e_staking_stake(None, addr.lp_cw20(1000), "user1", |res| res.assert_valid())
+    .e_staking_transfer_stake(addr.lp_cw20_info_checked(), 1001, "user2", "user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::InsufficientBalance(
+        "shares".to_string(),
+      )))
+    })
+    .e_staking_transfer_stake(addr.lp_cw20_info_checked(), 400, "user2", "user1", |res| {
+      res.assert_attribute(attr("action", "asset/transfer_stake"));
+      res.assert_attribute(attr("share", "400"));
+    })
+    .q_staking_staked_balance(shares_of(&addr.user1), |res| assert_eq!(res.unwrap().shares, u(600)))
+    .q_staking_staked_balance(shares_of(&addr.user2), |res| assert_eq!(res.unwrap().shares, u(400)))
+    .e_staking_wrap_stake(addr.lp_cw20_info_checked(), 100, "user1", |res| {
+      res.assert_error(ContractError::AssetNotTokenized(addr.lp_cw20_info_checked().to_string()))
+    })
+    .e_staking_update_asset_config(tokenized_config(true), "AT_ASSET_WHITELIST_CONTROLLER", |res| {
+      res.assert_valid()
+    })
+    .e_staking_wrap_stake(addr.lp_cw20_info_checked(), 100, "user1", |res| {
+      res.assert_attribute(attr("action", "asset/wrap_stake"));
+      res.assert_attribute(attr("share", "100"));
+    })
+    .q_staking_staked_balance(shares_of(&addr.user1), |res| {
+      assert_eq!(res.unwrap().shares, u(500))
+    });
+
+  let receipt = suite.app.wrap().query_balance(addr.user1.to_string(), &receipt_denom).unwrap();
+  assert_eq!(receipt, coin(100, &receipt_denom));
+
+  suite
+    // receipt tokens can be moved and unwrapped by the new holder
+    .def_send("user1", addr.user2.clone(), native(&receipt_denom, 100u128))
+    .e_staking_unwrap_stake(coin(100, &receipt_denom), "user2", |res| {
+      res.assert_attribute(attr("action", "asset/unwrap_stake"));
+      res.assert_attribute(attr("share", "100"));
+    })
+    .q_staking_staked_balance(shares_of(&addr.user2), |res| assert_eq!(res.unwrap().shares, u(500)))
+    .e_staking_update_asset_config(
+      tokenized_config(false),
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_error(ContractError::ReceiptDenomCannotBeRemoved),
+    );
+
+  let supply = suite.app.wrap().query_supply(&receipt_denom).unwrap();
+  assert_eq!(supply.amount, u(0));
+}
+
+#[test]
+fn test_asset_stake_caps() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  let receipt_denom = format!("factory/{0}/0/stake", addr.ve3_asset_staking_2);
+  let caps_config = |max_total_stake: u128| AssetInfoWithConfig {
+    info: addr.lp_cw20_info(),
+    config: Some(AssetConfig {
+      yearly_take_rate: Some(Decimal::zero()),
+      stake_config: ve3_shared::stake_config::StakeConfig::Default,
+      reward_stream_seconds: None,
+      unbonding_seconds: None,
+      max_boost: None,
+      tokenized: None,
+      take_tiers: None,
+      take_on_rewards: None,
+      max_total_stake: Some(u(max_total_stake)),
+      max_stake_per_user: Some(u(1000)),
+      apr_cap: None,
+      clear: None,
+    }),
+  };
+
+  suite
+    .def_setup_staking()
+    .e_staking_update_asset_config(caps_config(1500), "AT_ASSET_WHITELIST_CONTROLLER", |res| {
+      res.assert_valid()
+    })
+    .q_staking_whitelisted_asset_details(|res| {
+      let details = res.unwrap();
+      let config = &details.iter().find(|a| a.info == addr.lp_cw20_info_checked()).unwrap().config;
+      assert_eq!(config.max_total_stake, Some(u(1500)));
+      assert_eq!(config.max_stake_per_user, Some(u(1000)));
+    })
+    .e_staking_stake(None, addr.lp_cw20(1000), "user1", |res| res.assert_valid())
+    .e_staking_stake(None, addr.lp_cw20(1), "user1", |res| {
+      res.assert_error(ContractError::MaxStakePerUserExceeded(u(1000)))
+    })
+    .e_staking_stake(None, addr.lp_cw20(600), "user2", |res| {
+      res.assert_error(ContractError::MaxTotalStakeExceeded(u(1500)))
+    })
+    .e_staking_stake(None, addr.lp_cw20(500), "user2", |res| res.assert_valid())
+    // caps are raised over time
+    .e_staking_update_asset_config(caps_config(2000), "AT_ASSET_WHITELIST_CONTROLLER", |res| {
+      res.assert_valid()
+    })
+    .e_staking_stake(None, addr.lp_cw20(500), "user2", |res| res.assert_valid())
+    .e_staking_stake(None, addr.lp_cw20(1), "user2", |res| {
+      res.assert_error(ContractError::MaxStakePerUserExceeded(u(1000)))
+    })
+    // the cap also applies to the receiver of a transfer
+    .e_staking_transfer_stake(addr.lp_cw20_info_checked(), 1, "user2", "user1", |res| {
+      res.assert_error(ContractError::MaxStakePerUserExceeded(u(1000)))
+    })
+    .e_staking_transfer_stake(addr.lp_cw20_info_checked(), 1, "user3", "user1", |res| {
+      res.assert_valid()
+    })
+    // unrelated updates keep the caps
+    .e_staking_update_asset_config(
+      AssetInfoWithConfig {
+        info: addr.lp_cw20_info(),
+        config: Some(AssetConfig {
+          yearly_take_rate: Some(Decimal::zero()),
+          stake_config: ve3_shared::stake_config::StakeConfig::Default,
+          reward_stream_seconds: None,
+          unbonding_seconds: Some(100),
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: None,
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: None,
+        }),
+      },
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .q_staking_whitelisted_asset_details(|res| {
+      let details = res.unwrap();
+      let config = &details.iter().find(|a| a.info == addr.lp_cw20_info_checked()).unwrap().config;
+      assert_eq!(config.unbonding_seconds, 100);
+      assert_eq!(config.max_total_stake, Some(u(2000)));
+      assert_eq!(config.max_stake_per_user, Some(u(1000)));
+    })
+    .e_staking_update_asset_config(
+      AssetInfoWithConfig {
+        info: addr.lp_cw20_info(),
+        config: Some(AssetConfig {
+          yearly_take_rate: Some(Decimal::zero()),
+          stake_config: ve3_shared::stake_config::StakeConfig::Default,
+          reward_stream_seconds: None,
+          unbonding_seconds: None,
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: None,
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: Some(vec![AssetConfigField::MaxTotalStake]),
+        }),
+      },
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .q_staking_whitelisted_asset_details(|res| {
+      let details = res.unwrap();
+      let config = &details.iter().find(|a| a.info == addr.lp_cw20_info_checked()).unwrap().config;
+      assert_eq!(config.max_total_stake, None);
+      assert_eq!(config.max_stake_per_user, Some(u(1000)));
+    })
+    .e_staking_stake(Some("user3"), addr.lp_cw20(999), "user1", |res| res.assert_valid())
+    .e_staking_update_asset_config(
+      AssetInfoWithConfig {
+        info: addr.lp_cw20_info(),
+        config: Some(AssetConfig {
+          yearly_take_rate: Some(Decimal::zero()),
+          stake_config: ve3_shared::stake_config::StakeConfig::Default,
+          reward_stream_seconds: None,
+          unbonding_seconds: None,
+          max_boost: None,
+          tokenized: Some(true),
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: None,
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: None,
+        }),
+      },
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .e_staking_wrap_stake(addr.lp_cw20_info_checked(), 100, "user1", |res| res.assert_valid())
+    .def_send("user1", addr.user2.clone(), native(&receipt_denom, 100u128))
+    // the cap also applies to the receiver of unwrapped receipt tokens
+    .e_staking_unwrap_stake(coin(100, &receipt_denom), "user2", |res| {
+      res.assert_error(ContractError::MaxStakePerUserExceeded(u(1000)))
+    });
+}
+
+#[test]
+fn test_asset_emergency_unstake() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+
+  suite
+    .def_setup_staking()
+    // native lp is staked in the incentive mock
+    .e_staking_stake(None, addr.lp_native(1000), "user1", |res| res.assert_valid())
+    .e_staking_stake(None, addr.lp_native(1000), "user2", |res| res.assert_valid())
+    .def_add_staking_rewards(120000)
+    .e_staking_emergency_unstake(addr.lp_native_info_checked(), "user1", |res| {
+      res.assert_error(ContractError::AssetNotPaused(addr.lp_native_info_checked().to_string()))
+    })
+    .e_staking_set_asset_paused(addr.lp_native_info_checked(), true, "user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::UnauthorizedMissingRight(
+        AT_VE_GUARDIAN.to_string(),
+        addr.user1.to_string(),
+      )))
+    })
+    .e_staking_set_asset_paused(addr.lp_native_info_checked(), true, "AT_VE_GUARDIAN", |res| {
+      res.assert_attribute(attr("action", "asset/set_asset_paused"));
+      res.assert_attribute(attr("paused", "true"));
+    })
+    .q_staking_paused_assets(|res| {
+      assert_eq!(res.unwrap(), vec![addr.lp_native_info_checked()]);
+    })
+    .e_staking_stake(None, addr.lp_native(1000), "user1", |res| {
+      res.assert_error(ContractError::AssetPaused(addr.lp_native_info_checked().to_string()))
+    })
+    .e_staking_unstake(addr.lp_native(1000), "user1", |res| {
+      res.assert_error(ContractError::AssetPaused(addr.lp_native_info_checked().to_string()))
+    })
+    // funds are still held by the external staker
+    .e_staking_emergency_unstake(addr.lp_native_info_checked(), "user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::InsufficientBalance(
+        "contract balance".to_string(),
+      )))
+    })
+    .e_staking_emergency_withdraw_from_external(addr.lp_native_info_checked(), "user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::UnauthorizedMissingRight(
+        AT_VE_GUARDIAN.to_string(),
+        addr.user1.to_string(),
+      )))
+    })
+    .e_staking_emergency_withdraw_from_external(
+      addr.lp_native_info_checked(),
+      "AT_VE_GUARDIAN",
+      |res| {
+        res.assert_attribute(attr("action", "asset/emergency_withdraw_from_external"));
+        res.assert_attribute(attr("amount", "2000"));
+      },
+    )
+    .e_staking_emergency_unstake(addr.lp_native_info_checked(), "user1", |res| {
+      res.assert_attribute(attr("action", "asset/emergency_unstake"));
+      res.assert_attribute(attr("share", "1000"));
+      res.assert_transfer(addr.user1.to_string(), addr.lp_native(1000));
+    })
+    .e_staking_emergency_unstake(addr.lp_native_info_checked(), "user1", |res| {
+      res.assert_error(ContractError::NothingStaked {})
+    })
+    // pending rewards are forfeited
+    .q_staking_all_pending_rewards(
+      AllPendingRewardsQuery {
+        address: addr.user1.to_string(),
+      },
+      |res| assert_eq!(res.unwrap(), vec![]),
+    )
+    .e_staking_set_asset_paused(addr.lp_native_info_checked(), false, "AT_VE_GUARDIAN", |res| {
+      res.assert_valid()
+    })
+    .q_staking_paused_assets(|res| assert_eq!(res.unwrap(), vec![]))
+    .e_staking_unstake(addr.lp_native(1000), "user2", |res| {
+      res.assert_attribute(attr("action", "asset/unstake"));
+    });
+}
+
+#[test]
+fn test_asset_emergency_unstake_pending_unbonds() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+
+  suite
+    .def_setup_staking()
+    .e_staking_update_asset_config(
+      AssetInfoWithConfig {
+        info: addr.lp_native_info(),
+        config: Some(AssetConfig {
+          yearly_take_rate: Some(Decimal::zero()),
+          stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
+            contract: addr.incentive_mock.to_string(),
+            reward_infos: vec![AssetInfoUnchecked::native("astro")],
+          },
+          reward_stream_seconds: None,
+          unbonding_seconds: Some(100),
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: None,
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: None,
+        }),
+      },
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .e_staking_stake(None, addr.lp_native(1000), "user1", |res| res.assert_valid())
+    .e_staking_stake(None, addr.lp_native(1000), "user2", |res| res.assert_valid())
+    // the unbonding amount is withdrawn from the external staker and held by the contract
+    .e_staking_unstake(addr.lp_native(1000), "user2", |res| {
+      res.assert_attribute(attr("action", "asset/unstake"));
+      res.assert_attribute(attr("action", "mock/withdraw"));
+    })
+    .e_staking_set_asset_paused(addr.lp_native_info_checked(), true, "AT_VE_GUARDIAN", |res| {
+      res.assert_valid()
+    })
+    // the pending unbond of user2 can't be used for the emergency unstake
+    .e_staking_emergency_unstake(addr.lp_native_info_checked(), "user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::InsufficientBalance(
+        "contract balance".to_string(),
+      )))
+    })
+    .e_staking_emergency_withdraw_from_external(
+      addr.lp_native_info_checked(),
+      "AT_VE_GUARDIAN",
+      |res| res.assert_attribute(attr("amount", "1000")),
+    )
+    .e_staking_emergency_unstake(addr.lp_native_info_checked(), "user1", |res| {
+      res.assert_attribute(attr("action", "asset/emergency_unstake"));
+      res.assert_transfer(addr.user1.to_string(), addr.lp_native(1000));
+    })
+    .add_seconds(100)
+    .e_staking_withdraw_unbonded("user2", |res| {
+      res.assert_attribute(attr("action", "asset/withdraw_unbonded"));
+      res.assert_transfer(addr.user2.to_string(), addr.lp_native(1000));
+    });
+}
@@ -1,9 +1,23 @@
 use crate::{
-  common::{helpers::u, suite::TestingSuite},
+  common::{
+    helpers::u,
+    suite::TestingSuite,
+    suite_astroport::{to_astro, to_astro_info},
+  },
   extensions::app_response_ext::{EventChecker, Valid},
 };
-use cosmwasm_std::{attr, Decimal};
-use ve3_shared::msgs_asset_staking::*;
+use cosmwasm_std::{attr, Addr, Decimal, Uint128};
+use cw_asset::{Asset, AssetInfo};
+use cw_multi_test::Executor;
+use ve3_asset_staking::error::ContractError;
+use ve3_shared::{
+  constants::AT_ASSET_WHITELIST_CONTROLLER,
+  error::SharedError,
+  extensions::asset_ext::AssetExt,
+  helpers::oracle::Oracle,
+  msgs_asset_staking::*,
+  msgs_zapper::{RouteInit, Stage, StageType},
+};
 
 #[test]
 fn test_staking_claim() {
@@ -41,6 +55,7 @@ fn test_staking_claim() {
           vec![PendingRewardsRes {
             staked_asset_share: addr.lp_cw20(1000),
             reward_asset: addr.zasset(33332),
+            extra_rewards: vec![],
           }]
         )
       },
@@ -78,6 +93,7 @@ fn test_staking_claim_native() {
           vec![PendingRewardsRes {
             staked_asset_share: addr.lp_native(1000),
             reward_asset: addr.zasset(66666),
+            extra_rewards: vec![],
           }]
         )
       },
@@ -95,6 +111,7 @@ fn test_staking_claim_native() {
             // rounding difference
             staked_asset: addr.lp_native(999),
             reward_asset: addr.zasset(0),
+            extra_rewards: vec![],
           }]
         );
       },
@@ -133,10 +150,12 @@ fn test_staking_claim_both() {
             PendingRewardsRes {
               staked_asset_share: addr.lp_cw20(1000),
               reward_asset: addr.zasset(16666),
+              extra_rewards: vec![],
             },
             PendingRewardsRes {
               staked_asset_share: addr.lp_native(1000),
               reward_asset: addr.zasset(66666),
+              extra_rewards: vec![],
             },
           ]
         )
@@ -152,6 +171,7 @@ fn test_staking_claim_both() {
           vec![PendingRewardsRes {
             staked_asset_share: addr.lp_cw20(2001),
             reward_asset: addr.zasset(16666),
+            extra_rewards: vec![],
           }]
         )
       },
@@ -209,11 +229,13 @@ fn test_staking_claim_both() {
               share: u(1000),
               staked_asset: addr.lp_cw20(998),
               reward_asset: addr.zasset(0),
+              extra_rewards: vec![],
             },
             PendingRewardsDetailRes {
               share: u(1000),
               staked_asset: addr.lp_native(997),
               reward_asset: addr.zasset(0),
+              extra_rewards: vec![],
             },
           ]
         )
@@ -230,6 +252,7 @@ fn test_staking_claim_both() {
             share: u(2001),
             staked_asset: addr.lp_cw20(1998),
             reward_asset: addr.zasset(0),
+            extra_rewards: vec![],
           },]
         )
       },
@@ -244,3 +267,485 @@ fn test_staking_claim_both() {
       res.assert_attribute(attr("to", addr.user1.to_string()));
     });
 }
+
+#[test]
+fn test_staking_extra_rewards() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  let controller = suite.address("AT_ASSET_WHITELIST_CONTROLLER");
+
+  suite
+    .def_setup_staking()
+    .e_staking_stake(None, addr.lp_cw20(1000), "user2", |res| res.assert_valid())
+    .e_staking_add_rewards(addr.lp_cw20_info_checked(), addr.uluna(1000), 0, "user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::UnauthorizedMissingRight(
+        AT_ASSET_WHITELIST_CONTROLLER.to_string(),
+        addr.user1.to_string(),
+      )))
+    })
+    .def_send("creator", controller, addr.uluna(2000))
+    .e_staking_add_rewards(
+      addr.lp_native_info_checked(),
+      addr.uluna(1000),
+      0,
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_error(ContractError::NothingStaked {}),
+    )
+    .e_staking_add_rewards(
+      addr.lp_cw20_info_checked(),
+      addr.uluna(1000),
+      0,
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| {
+        res.assert_attribute(attr("action", "asset/add_rewards"));
+        res.assert_attribute(attr("rewards", "native:uluna:1000"));
+      },
+    )
+    .e_staking_stake(None, addr.lp_cw20(1000), "user1", |res| res.assert_valid())
+    // once added, anyone can add more rewards
+    .e_staking_add_rewards(addr.lp_cw20_info_checked(), addr.uluna(2000), 100, "user1", |res| {
+      res.assert_valid()
+    })
+    .add_seconds(50)
+    .q_staking_all_pending_rewards(
+      AllPendingRewardsQuery {
+        address: addr.user1.to_string(),
+      },
+      |res| {
+        assert_eq!(
+          res.unwrap(),
+          vec![PendingRewardsRes {
+            staked_asset_share: addr.lp_cw20(1000),
+            reward_asset: addr.zasset(0),
+            extra_rewards: vec![addr.uluna(500)],
+          }]
+        )
+      },
+    )
+    .add_seconds(100)
+    .q_staking_all_pending_rewards(
+      AllPendingRewardsQuery {
+        address: addr.user2.to_string(),
+      },
+      |res| {
+        assert_eq!(
+          res.unwrap(),
+          vec![PendingRewardsRes {
+            staked_asset_share: addr.lp_cw20(1000),
+            reward_asset: addr.zasset(0),
+            extra_rewards: vec![addr.uluna(2000)],
+          }]
+        )
+      },
+    )
+    .e_staking_claim_rewards(None, "user2", |res| {
+      res.assert_attribute(attr("action", "asset/claim_rewards"));
+      res.assert_attribute(attr("extra_rewards", "native:uluna:2000"));
+      res.assert_attribute_ty("transfer", attr("amount", "2000uluna"));
+    })
+    .e_staking_claim_rewards(None, "user1", |res| {
+      res.assert_attribute(attr("extra_rewards", "native:uluna:1000"));
+    })
+    .q_staking_all_pending_rewards(
+      AllPendingRewardsQuery {
+        address: addr.user2.to_string(),
+      },
+      |res| assert_eq!(res.unwrap(), vec![]),
+    );
+}
+
+#[test]
+fn test_staking_boost() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  let controller = suite.address("AT_ASSET_WHITELIST_CONTROLLER");
+  let boost_config = |max_boost: Decimal| AssetInfoWithConfig {
+    info: addr.lp_cw20_info(),
+    config: Some(AssetConfig {
+      yearly_take_rate: Some(Decimal::zero()),
+      stake_config: ve3_shared::stake_config::StakeConfig::Default,
+      reward_stream_seconds: None,
+      unbonding_seconds: None,
+      max_boost: Some(max_boost),
+      tokenized: None,
+      take_tiers: None,
+      take_on_rewards: None,
+      max_total_stake: None,
+      max_stake_per_user: None,
+      apr_cap: None,
+      clear: None,
+    }),
+  };
+
+  suite
+    .def_setup_staking()
+    .e_staking_update_asset_config(
+      boost_config(Decimal::percent(50)),
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_error(ContractError::InvalidMaxBoost),
+    )
+    .e_staking_update_asset_config(
+      boost_config(Decimal::percent(250)),
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    // creator has no voting power and only earns on 1 / 2.5 of the shares
+    .e_staking_stake(None, addr.lp_cw20(1000), "creator", |res| res.assert_valid())
+    .e_staking_stake(None, addr.lp_cw20(1000), "user1", |res| res.assert_valid())
+    .e_staking_kick("creator", "user2", |res| {
+      res.assert_attribute(attr("action", "asset/kick"));
+      res.assert_attribute(attr("working_balance", "400"));
+    })
+    .q_staking_working_balance(
+      AssetQuery {
+        address: addr.creator.to_string(),
+        asset: addr.lp_cw20_info_checked(),
+      },
+      |res| {
+        let res = res.unwrap();
+        assert_eq!(res.shares, u(1000));
+        assert_eq!(res.working_balance, u(400));
+      },
+    )
+    .q_staking_working_balance(
+      AssetQuery {
+        address: addr.user1.to_string(),
+        asset: addr.lp_cw20_info_checked(),
+      },
+      |res| {
+        let res = res.unwrap();
+        assert_eq!(res.shares, u(1000));
+        assert!(res.working_balance > u(400));
+        assert!(res.working_balance <= u(1000));
+        assert_eq!(res.total_working_balance, res.working_balance + u(400));
+      },
+    )
+    .def_send("creator", controller, addr.uluna(1000))
+    .e_staking_add_rewards(
+      addr.lp_cw20_info_checked(),
+      addr.uluna(1000),
+      0,
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    );
+
+  let staking = suite.addresses.active_asset_staking.clone();
+  let pending_rewards = |suite: &TestingSuite, user: &Addr| -> PendingRewardsRes {
+    let msg = QueryMsg::PendingRewards(AssetQuery {
+      address: user.to_string(),
+      asset: addr.lp_cw20_info_checked(),
+    });
+    suite.app.wrap().query_wasm_smart(staking.clone(), &msg).unwrap()
+  };
+
+  // rewards are distributed by the working balance instead of the shares
+  let creator_rewards = pending_rewards(&suite, &addr.creator).extra_rewards;
+  let user1_rewards = pending_rewards(&suite, &addr.user1).extra_rewards;
+  assert!(user1_rewards[0].amount > creator_rewards[0].amount);
+  assert!(user1_rewards[0].amount + creator_rewards[0].amount <= u(1000));
+
+  suite
+    .e_staking_update_asset_config(
+      AssetInfoWithConfig {
+        info: addr.lp_cw20_info(),
+        config: Some(AssetConfig {
+          yearly_take_rate: Some(Decimal::zero()),
+          stake_config: ve3_shared::stake_config::StakeConfig::Default,
+          reward_stream_seconds: None,
+          unbonding_seconds: None,
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: None,
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: Some(vec![AssetConfigField::MaxBoost]),
+        }),
+      },
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .q_staking_whitelisted_asset_details(|res| {
+      let details = res.unwrap();
+      let config = &details.iter().find(|a| a.info == addr.lp_cw20_info_checked()).unwrap().config;
+      assert_eq!(config.max_boost, None);
+    })
+    .e_staking_kick("creator", "creator", |res| {
+      res.assert_attribute(attr("working_balance", "1000"));
+    });
+}
+
+#[test]
+fn test_staking_apr_cap() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  let capped_config = |excess_recipient: Option<String>| AssetInfoWithConfig {
+    info: addr.lp_cw20_info(),
+    config: Some(AssetConfig {
+      yearly_take_rate: Some(Decimal::zero()),
+      stake_config: ve3_shared::stake_config::StakeConfig::Default,
+      reward_stream_seconds: None,
+      unbonding_seconds: None,
+      max_boost: None,
+      tokenized: None,
+      take_tiers: None,
+      take_on_rewards: None,
+      max_total_stake: None,
+      max_stake_per_user: None,
+      // 1 lp = 1 zasset, 1000 staked allow 191 zasset per week
+      apr_cap: Some(AprCap {
+        max_apr: Decimal::percent(1000),
+        oracle: Oracle::Usdc,
+        excess_recipient,
+      }),
+      clear: None,
+    }),
+  };
+
+  suite
+    // rewards: 2 for native : 1 for cw20
+    .def_setup_staking()
+    .e_staking_update_asset_config(capped_config(None), "AT_ASSET_WHITELIST_CONTROLLER", |res| {
+      res.assert_valid()
+    })
+    .e_staking_stake(None, addr.lp_cw20(1000), "user2", |res| res.assert_valid())
+    .e_staking_stake(None, addr.lp_native(1000), "user1", |res| res.assert_valid())
+    // the first update has no known duration and is not capped
+    .def_add_staking_rewards(120000);
+
+  let staking = suite.addresses.active_asset_staking.clone();
+  let pending_rewards = |suite: &TestingSuite, user: &Addr, asset: AssetInfo| -> Uint128 {
+    let msg = QueryMsg::PendingRewards(AssetQuery {
+      address: user.to_string(),
+      asset,
+    });
+    let res: PendingRewardsRes = suite.app.wrap().query_wasm_smart(staking.clone(), &msg).unwrap();
+    res.reward_asset.amount
+  };
+
+  let capped_before = pending_rewards(&suite, &addr.user2, addr.lp_cw20_info_checked());
+  let uncapped_before = pending_rewards(&suite, &addr.user1, addr.lp_native_info_checked());
+  assert!(capped_before > u(30000));
+
+  suite.add_one_period().def_add_staking_rewards(120000);
+
+  // the excess above the cap is redistributed to the uncapped native lp
+  let capped_after = pending_rewards(&suite, &addr.user2, addr.lp_cw20_info_checked());
+  let uncapped_after = pending_rewards(&suite, &addr.user1, addr.lp_native_info_checked());
+  assert!(capped_after - capped_before <= u(191));
+  assert!(capped_after - capped_before >= u(190));
+  assert!(uncapped_after - uncapped_before > uncapped_before + u(30000));
+
+  let connector = addr.ve3_connector_alliance_eris.clone();
+  suite
+    .e_staking_update_asset_config(
+      capped_config(Some(addr.creator.to_string())),
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .add_one_period()
+    .def_send("creator", connector, addr.uluna(120000))
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "asset/update_rewards_callback"));
+      res.get_attribute_value("wasm", "excess".to_string());
+      res.assert_attribute_ty("transfer", attr("recipient", addr.creator.to_string()));
+    });
+
+  // the excess is sent to the recipient instead of the uncapped assets
+  let capped_sent = pending_rewards(&suite, &addr.user2, addr.lp_cw20_info_checked());
+  let uncapped_sent = pending_rewards(&suite, &addr.user1, addr.lp_native_info_checked());
+  assert!(capped_sent - capped_after <= u(191));
+  assert!(uncapped_sent - uncapped_after < uncapped_after - uncapped_before);
+
+  suite
+    .e_staking_update_asset_config(
+      AssetInfoWithConfig {
+        info: addr.lp_cw20_info(),
+        config: Some(AssetConfig {
+          yearly_take_rate: Some(Decimal::zero()),
+          stake_config: ve3_shared::stake_config::StakeConfig::Default,
+          reward_stream_seconds: None,
+          unbonding_seconds: None,
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: None,
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: Some(vec![AssetConfigField::AprCap]),
+        }),
+      },
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .q_staking_whitelisted_asset_details(|res| {
+      let details = res.unwrap();
+      let config = &details.iter().find(|a| a.info == addr.lp_cw20_info_checked()).unwrap().config;
+      assert_eq!(config.apr_cap, None);
+    })
+    .add_one_period()
+    .def_send("creator", addr.ve3_connector_alliance_eris.clone(), addr.uluna(120000))
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "asset/update_rewards_callback"));
+    });
+
+  let uncapped = pending_rewards(&suite, &addr.user2, addr.lp_cw20_info_checked());
+  assert!(uncapped - capped_sent > u(191));
+}
+
+#[test]
+fn test_staking_apr_cap_oracle_error() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  let connector = addr.ve3_connector_alliance_eris.clone();
+
+  suite
+    .def_setup_staking()
+    .e_staking_update_asset_config(
+      AssetInfoWithConfig {
+        info: addr.lp_cw20_info(),
+        config: Some(AssetConfig {
+          yearly_take_rate: Some(Decimal::zero()),
+          stake_config: ve3_shared::stake_config::StakeConfig::Default,
+          reward_stream_seconds: None,
+          unbonding_seconds: None,
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: None,
+          max_stake_per_user: None,
+          // not a pair, every price query fails
+          apr_cap: Some(AprCap {
+            max_apr: Decimal::percent(1000),
+            oracle: Oracle::Pair {
+              contract: addr.creator.to_string(),
+              simulation_amount: u(1_000000),
+              from_decimals: None,
+            },
+            excess_recipient: None,
+          }),
+          clear: None,
+        }),
+      },
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .e_staking_stake(None, addr.lp_cw20(1000), "user2", |res| res.assert_valid())
+    .e_staking_stake(None, addr.lp_native(1000), "user1", |res| res.assert_valid())
+    .def_add_staking_rewards(120000)
+    .add_one_period()
+    .def_send("creator", connector, addr.uluna(120000))
+    // the asset stays uncapped instead of blocking the rewards of all assets
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "asset/update_rewards_callback"));
+      res.assert_attribute(attr("apr_cap_oracle_error", addr.lp_cw20_info_checked().to_string()));
+    });
+}
+
+#[test]
+fn test_staking_claim_and_compound() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+
+  suite
+    .def_setup_staking()
+    .def_setup_zapper()
+    .e_staking_stake(None, addr.lp_native(1000), "user1", |res| res.assert_valid())
+    .e_staking_stake(None, addr.lp_native(1000), "user2", |res| res.assert_valid())
+    .def_add_staking_rewards(120000)
+    .add_one_period()
+    .e_staking_claim_rewards(None, "user2", |res| res.assert_valid())
+    .e_staking_claim_and_compound(None, addr.lp_native_info_checked(), None, "user2", |res| {
+      res.assert_error(ContractError::NothingToCompound {})
+    })
+    .e_staking_claim_and_compound(None, addr.usdc_info_checked(), None, "user1", |res| {
+      res.assert_error(ContractError::AssetNotWhitelisted)
+    });
+
+  // the zasset rewards of user2 provide the liquidity to swap the rewards of user1
+  let zasset = suite.app.wrap().query_balance(addr.user2.clone(), &addr.zasset_denom).unwrap();
+  let res = suite
+    .app
+    .execute_contract(
+      addr.user1.clone(),
+      addr.astroport_factory.clone(),
+      &astroport::factory::ExecuteMsg::CreatePair {
+        pair_type: astroport::factory::PairType::Xyk {},
+        asset_infos: vec![
+          to_astro_info(addr.zasset_info_checked()),
+          to_astro_info(addr.uluna_info_checked()),
+        ],
+        init_params: None,
+      },
+      &[],
+    )
+    .unwrap();
+  let pair = Addr::unchecked(res.get_attribute_value("wasm", "pair_contract_addr".to_string()));
+
+  let uluna = addr.uluna(zasset.amount.u128());
+  let zasset = Asset::native(addr.zasset_denom.clone(), zasset.amount);
+  suite
+    .app
+    .execute_contract(
+      addr.user2.clone(),
+      pair.clone(),
+      &astroport::pair::ExecuteMsg::ProvideLiquidity {
+        assets: vec![to_astro(zasset.clone()), to_astro(uluna.clone())],
+        slippage_tolerance: None,
+        auto_stake: None,
+        receiver: None,
+        min_lp_to_receive: None,
+      },
+      &[zasset.to_coin().unwrap(), uluna.to_coin().unwrap()],
+    )
+    .unwrap();
+
+  suite
+    .e_zapper_update_config(
+      Some(vec![RouteInit {
+        routes: vec![Stage {
+          from: addr.zasset_info_checked(),
+          to: addr.uluna_info_checked(),
+          stage_type: StageType::Astroport {
+            pair,
+          },
+        }],
+      }]),
+      None,
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .e_staking_claim_and_compound(
+      Some(vec![addr.lp_native_info_checked()]),
+      addr.lp_native_info_checked(),
+      None,
+      "user1",
+      |res| {
+        res.assert_attribute(attr("action", "asset/claim_and_compound"));
+        res.assert_attribute(attr("into", addr.lp_native_info_checked().to_string()));
+        res.assert_attribute(attr("action", "asset/stake"));
+        res.assert_attribute(attr("user", addr.user1.to_string()));
+      },
+    )
+    .q_staking_staked_balance(
+      AssetQuery {
+        address: addr.user1.to_string(),
+        asset: addr.lp_native_info_checked(),
+      },
+      |res| assert!(res.unwrap().asset.amount > u(1000)),
+    );
+}
@@ -0,0 +1,259 @@
+use crate::{
+  common::{helpers::native_info, suite::TestingSuite},
+  extensions::app_response_ext::{EventChecker, Valid},
+};
+use cosmwasm_std::{attr, Addr, Decimal, Uint128};
+use cw_asset::{Asset, AssetInfoUnchecked};
+use ve3_asset_staking::error::ContractError;
+use ve3_shared::{error::SharedError, msgs_asset_staking::*, stake_config::StakeConfig};
+
+fn external_config(stake_config: StakeConfig<String>) -> AssetConfig<String> {
+  AssetConfig {
+    yearly_take_rate: Some(Decimal::zero()),
+    stake_config,
+    reward_stream_seconds: None,
+    unbonding_seconds: None,
+    max_boost: None,
+    tokenized: None,
+    take_tiers: None,
+    take_on_rewards: None,
+    max_total_stake: None,
+    max_stake_per_user: None,
+    apr_cap: None,
+    clear: None,
+  }
+}
+
+#[test]
+fn test_asset_stake_white_whale() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  let white_whale = StakeConfig::WhiteWhale {
+    contract: addr.white_whale_mock.to_string(),
+    reward_infos: vec![AssetInfoUnchecked::native("whale")],
+  };
+
+  suite
+    // the claimed rewards of a shared incentive contract can't be attributed to one asset
+    .e_staking_whitelist_assets(
+      vec![
+        AssetInfoWithConfig::new(addr.lp_native_info(), Some(external_config(white_whale.clone()))),
+        AssetInfoWithConfig::new(addr.lp_cw20_info(), Some(external_config(white_whale.clone()))),
+      ],
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| {
+        res.assert_error(ContractError::WhiteWhaleContractShared(addr.white_whale_mock.to_string()))
+      },
+    )
+    .e_staking_whitelist_assets(
+      vec![
+        AssetInfoWithConfig::new(addr.lp_native_info(), Some(external_config(white_whale))),
+        AssetInfoWithConfig::new(
+          addr.lp_cw20_info(),
+          Some(external_config(StakeConfig::WhiteWhale {
+            contract: addr.incentive_mock.to_string(),
+            reward_infos: vec![AssetInfoUnchecked::native("whale")],
+          })),
+        ),
+      ],
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .e_staking_stake(None, addr.lp_cw20(1000), "user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::NotSupported(
+        "only native lp tokens".to_string(),
+      )))
+    })
+    .e_staking_stake(None, addr.lp_native(1000), "user1", |res| {
+      res.assert_attribute(attr("action", "asset/stake"));
+      res.assert_attribute(attr("action", "mock/ww_fill"));
+      res.assert_attribute(attr("mock/amount", addr.lp_native_str(1000)));
+      res.assert_attribute(attr("action", "asset/track_bribes_callback"));
+    })
+    .add_one_period()
+    .e_staking_unstake(addr.lp_native(1000), "user1", |res| {
+      res.assert_attribute(attr("action", "asset/unstake"));
+      res.assert_attribute(attr("action", "mock/ww_close"));
+      res.assert_attribute(attr("action", "mock/ww_withdraw"));
+      res.assert_attribute(attr("mock/amount", addr.lp_native_str(1000)));
+      // setup to receive 10000 whale per week
+      res.assert_attribute(attr("bribe", "native:whale:10000"));
+      res.assert_transfer(addr.user1.to_string(), addr.lp_native(1000));
+    });
+}
+
+#[test]
+fn test_asset_stake_custom() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  // same messages as the astroport incentives, expressed as templates
+  let custom = StakeConfig::Custom {
+    contract: addr.incentive_mock.to_string(),
+    deposit_msg: r#"{"deposit":{}}"#.to_string(),
+    withdraw_msg: r#"{"withdraw":{"lp_token":"{lp_token}","amount":"{amount}"}}"#.to_string(),
+    claim_msg: r#"{"claim_rewards":{"lp_tokens":["{lp_token}"]}}"#.to_string(),
+    reward_infos: vec![AssetInfoUnchecked::native("astro")],
+  };
+  let invalid_custom = StakeConfig::Custom {
+    contract: addr.incentive_mock.to_string(),
+    deposit_msg: r#"{"deposit":{}}"#.to_string(),
+    withdraw_msg: r#"{"withdraw":{"amount":{amount}"#.to_string(),
+    claim_msg: r#"{"claim_rewards":{"lp_tokens":["{lp_token}"]}}"#.to_string(),
+    reward_infos: vec![AssetInfoUnchecked::native("astro")],
+  };
+
+  suite
+    .e_staking_whitelist_assets(
+      vec![AssetInfoWithConfig::new(addr.lp_native_info(), Some(external_config(invalid_custom)))],
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| {
+        res.assert_error(ContractError::SharedError(SharedError::InvalidStakeConfig(
+          r#"template is not valid json: {"withdraw":{"amount":{amount}"#.to_string(),
+        )))
+      },
+    )
+    .e_staking_whitelist_assets(
+      vec![
+        AssetInfoWithConfig::new(addr.lp_native_info(), Some(external_config(custom.clone()))),
+        AssetInfoWithConfig::new(addr.lp_cw20_info(), Some(external_config(custom))),
+      ],
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .e_staking_stake(None, addr.lp_native(1000), "user1", |res| {
+      res.assert_attribute(attr("action", "asset/stake"));
+      res.assert_attribute(attr("action", "mock/deposit"));
+      res.assert_attribute(attr("mock/amount", addr.lp_native_str(1000)));
+    })
+    // cw20 is deposited through a send hook
+    .e_staking_stake(None, addr.lp_cw20(1000), "user1", |res| {
+      res.assert_attribute(attr("action", "asset/stake"));
+      res.assert_attribute(attr("mock/amount", addr.lp_cw20(1000).to_string()));
+    })
+    .add_one_period()
+    .e_staking_unstake(addr.lp_native(500), "user1", |res| {
+      res.assert_attribute(attr("action", "mock/withdraw"));
+      res.assert_attribute(attr("mock/amount", addr.lp_native_str(500)));
+      res.assert_attribute(attr("bribe", "native:astro:10000"));
+      res.assert_transfer(addr.user1.to_string(), addr.lp_native(500));
+    })
+    .e_bribe_whitelist_assets(
+      vec![native_info("astro").into()],
+      "AT_BRIBE_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .add_one_period()
+    .e_staking_distribute_bribes(Some(true), None, "user1", |res| {
+      res.assert_attribute(attr("action", "asset/distribute_bribes"));
+      res.assert_attribute(attr("action", "mock/claimrewards"));
+      res.assert_attribute(attr("action", "asset/track_bribes_callback"));
+      res.assert_attribute(attr("asset", addr.lp_native_info_checked().to_string()));
+    });
+}
+
+#[test]
+fn test_asset_migrate_stake_config() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  // keeps the default take rate of the cw20 lp
+  let migrate_config = |stake_config: StakeConfig<String>| AssetInfoWithConfig {
+    info: addr.lp_cw20_info(),
+    config: Some(AssetConfig {
+      yearly_take_rate: None,
+      ..external_config(stake_config)
+    }),
+  };
+
+  suite
+    .def_setup_staking()
+    .e_staking_stake(None, addr.lp_cw20(1000), "user1", |res| res.assert_valid())
+    .e_staking_stake(None, addr.lp_cw20(2000), "user2", |res| res.assert_valid())
+    .def_add_staking_rewards(120000);
+
+  let staking = suite.addresses.active_asset_staking.clone();
+  let snapshot =
+    |suite: &TestingSuite, user: &Addr| -> (Vec<(Asset, Uint128)>, Vec<PendingRewardsRes>) {
+      let balances: Vec<StakedBalanceRes> = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+          staking.clone(),
+          &QueryMsg::AllStakedBalances(AllStakedBalancesQuery {
+            address: user.to_string(),
+          }),
+        )
+        .unwrap();
+      let pending: Vec<PendingRewardsRes> = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+          staking.clone(),
+          &QueryMsg::AllPendingRewards(AllPendingRewardsQuery {
+            address: user.to_string(),
+          }),
+        )
+        .unwrap();
+      (balances.into_iter().map(|b| (b.asset, b.shares)).collect(), pending)
+    };
+
+  let user1_before = snapshot(&suite, &addr.user1);
+  let user2_before = snapshot(&suite, &addr.user2);
+  assert!(!user2_before.1.is_empty());
+
+  suite.e_staking_update_asset_config(
+    migrate_config(StakeConfig::Astroport {
+      contract: addr.incentive_mock.to_string(),
+      reward_infos: vec![AssetInfoUnchecked::native("astro")],
+    }),
+    "AT_ASSET_WHITELIST_CONTROLLER",
+    |res| {
+      res.assert_attribute(attr("action", "asset/migrate_stake_callback"));
+      res.assert_attribute(attr("mock/amount", addr.lp_cw20(3000).to_string()));
+      res.assert_attribute(attr("action", "asset/track_bribes_callback"));
+    },
+  );
+
+  assert_eq!(snapshot(&suite, &addr.user1), user1_before);
+  assert_eq!(snapshot(&suite, &addr.user2), user2_before);
+
+  suite.add_one_period();
+  let user1_before = snapshot(&suite, &addr.user1);
+  let user2_before = snapshot(&suite, &addr.user2);
+
+  // both configs receive astro from the incentive mock, it is only tracked once
+  suite.e_staking_update_asset_config(
+    migrate_config(StakeConfig::Custom {
+      contract: addr.incentive_mock.to_string(),
+      deposit_msg: r#"{"deposit":{}}"#.to_string(),
+      withdraw_msg: r#"{"withdraw":{"lp_token":"{lp_token}","amount":"{amount}"}}"#.to_string(),
+      claim_msg: r#"{"claim_rewards":{"lp_tokens":["{lp_token}"]}}"#.to_string(),
+      reward_infos: vec![AssetInfoUnchecked::native("astro")],
+    }),
+    "AT_ASSET_WHITELIST_CONTROLLER",
+    |res| {
+      res.assert_attribute(attr("action", "mock/withdraw"));
+      res.assert_attribute(attr("action", "asset/migrate_stake_callback"));
+      res.assert_attribute(attr("action", "mock/deposit"));
+      res.assert_attribute(attr("bribe", "native:astro:10000"));
+
+      let bribes = res
+        .as_ref()
+        .unwrap()
+        .events
+        .iter()
+        .flat_map(|event| event.attributes.iter())
+        .filter(|attr| attr.key == "bribe")
+        .count();
+      assert_eq!(bribes, 1);
+    },
+  );
+
+  assert_eq!(snapshot(&suite, &addr.user1), user1_before);
+  assert_eq!(snapshot(&suite, &addr.user2), user2_before);
+}
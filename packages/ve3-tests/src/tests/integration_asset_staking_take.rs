@@ -2,9 +2,10 @@ use crate::{
   common::suite::TestingSuite,
   extensions::app_response_ext::{EventChecker, Valid},
 };
-use cosmwasm_std::{attr, Decimal};
-use cw_asset::AssetInfoUnchecked;
-use ve3_shared::msgs_asset_staking::*;
+use cosmwasm_std::{attr, Decimal, Uint128};
+use cw_asset::{Asset, AssetInfoUnchecked};
+use ve3_asset_staking::error::ContractError;
+use ve3_shared::{constants::SECONDS_PER_YEAR, msgs_asset_staking::*};
 
 #[test]
 fn test_asset_take_rate_multi_rebase() {
@@ -27,6 +28,16 @@ fn test_asset_take_rate_multi_rebase() {
             contract: addr.incentive_mock.to_string(),
             reward_infos: vec![AssetInfoUnchecked::native("astro")],
           },
+          reward_stream_seconds: None,
+          unbonding_seconds: None,
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: None,
+          max_total_stake: None,
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: None,
         }),
       )],
       "AT_ASSET_WHITELIST_CONTROLLER",
@@ -64,3 +75,188 @@ fn test_asset_take_rate_multi_rebase() {
       );
     });
 }
+
+#[test]
+fn test_asset_take_tiers() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  let tiers_config = |high_tier_rate: Decimal| {
+    AssetInfoWithConfig::new(
+      addr.lp_native_info(),
+      Some(AssetConfig {
+        yearly_take_rate: Some(Decimal::percent(10)),
+        stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
+          contract: addr.incentive_mock.to_string(),
+          reward_infos: vec![AssetInfoUnchecked::native("astro")],
+        },
+        reward_stream_seconds: None,
+        unbonding_seconds: None,
+        max_boost: None,
+        tokenized: None,
+        take_tiers: Some(vec![
+          TakeTier {
+            min_balance: Uint128::new(5_000_000),
+            yearly_take_rate: Decimal::percent(5),
+          },
+          TakeTier {
+            min_balance: Uint128::new(20_000_000),
+            yearly_take_rate: high_tier_rate,
+          },
+        ]),
+        take_on_rewards: None,
+        max_total_stake: None,
+        max_stake_per_user: None,
+        apr_cap: None,
+        clear: None,
+      }),
+    )
+  };
+
+  suite
+    .def_asset_config_astro(|res| res.assert_valid())
+    .e_staking_update_asset_config(
+      tiers_config(Decimal::percent(60)),
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_error(ContractError::TakeRateLessOrEqual50),
+    )
+    .e_staking_update_asset_config(
+      tiers_config(Decimal::percent(2)),
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .e_staking_stake(None, addr.lp_native(1_000_000), "user1", |res| res.assert_valid())
+    // below the first tier the yearly_take_rate applies
+    .q_staking_simulate_take(addr.lp_native_info_checked(), SECONDS_PER_YEAR, |res| {
+      let res = res.unwrap();
+      assert_eq!(res.yearly_take_rate, Decimal::percent(10));
+      assert_eq!(res.take, addr.lp_native(100_000));
+    })
+    .e_staking_stake(None, addr.lp_native(9_000_000), "user1", |res| res.assert_valid())
+    .q_staking_simulate_take(addr.lp_native_info_checked(), SECONDS_PER_YEAR, |res| {
+      let res = res.unwrap();
+      assert_eq!(res.yearly_take_rate, Decimal::percent(5));
+      assert_eq!(res.take, addr.lp_native(500_000));
+    })
+    .e_staking_stake(None, addr.lp_native(10_000_000), "user1", |res| res.assert_valid())
+    .q_staking_simulate_take(addr.lp_native_info_checked(), SECONDS_PER_YEAR / 2, |res| {
+      let res = res.unwrap();
+      assert_eq!(res.yearly_take_rate, Decimal::percent(2));
+      assert_eq!(res.take, addr.lp_native(200_000));
+    });
+}
+
+#[test]
+fn test_asset_take_on_rewards() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  let take_recipient = suite.address("AT_TAKE_RECIPIENT");
+
+  suite
+    .def_asset_config_astro(|res| res.assert_valid())
+    .e_staking_update_asset_config(
+      AssetInfoWithConfig::new(
+        addr.lp_native_info(),
+        Some(AssetConfig {
+          yearly_take_rate: Some(Decimal::percent(10)),
+          stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
+            contract: addr.incentive_mock.to_string(),
+            reward_infos: vec![AssetInfoUnchecked::native("astro")],
+          },
+          reward_stream_seconds: None,
+          unbonding_seconds: None,
+          max_boost: None,
+          tokenized: None,
+          take_tiers: None,
+          take_on_rewards: Some(Decimal::percent(20)),
+          max_total_stake: None,
+          max_stake_per_user: None,
+          apr_cap: None,
+          clear: None,
+        }),
+      ),
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .e_staking_stake(None, addr.lp_native(10_000_000), "user1", |res| res.assert_valid())
+    .add_one_period()
+    // the principal is not taken anymore
+    .q_staking_simulate_take(addr.lp_native_info_checked(), SECONDS_PER_YEAR, |res| {
+      let res = res.unwrap();
+      assert_eq!(res.yearly_take_rate, Decimal::zero());
+      assert_eq!(res.take, addr.lp_native(0));
+      assert_eq!(res.reward_take, vec![]);
+    })
+    .e_staking_stake(None, addr.lp_native(1), "user1", |res| {
+      res.assert_attribute(attr("action", "asset/track_bribes_callback"));
+      res.assert_attribute(attr("reward_take", "native:astro:2000"));
+      res.assert_attribute(attr("bribe", "native:astro:8000"));
+    })
+    .q_staking_simulate_take(addr.lp_native_info_checked(), SECONDS_PER_YEAR, |res| {
+      assert_eq!(res.unwrap().reward_take, vec![Asset::native("astro", 2000u128)]);
+    })
+    .e_staking_distribute_take_rate(Some(true), None, "user1", |res| {
+      res.assert_attribute(attr("action", "asset/distribute_take_rate"));
+      res.assert_attribute(attr("take", "native:astro:2000"));
+      res.assert_attribute_ty("transfer", attr("recipient", take_recipient.to_string()));
+      res.assert_attribute_ty("transfer", attr("amount", "2000astro"));
+    })
+    .q_staking_simulate_take(addr.lp_native_info_checked(), SECONDS_PER_YEAR, |res| {
+      assert_eq!(res.unwrap().reward_take, vec![]);
+    });
+}
+
+#[test]
+fn test_asset_take_mode_switch_after_idle() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  let take_config = |take_on_rewards: Option<Decimal>, clear: Option<Vec<AssetConfigField>>| {
+    AssetInfoWithConfig::new(
+      addr.lp_native_info(),
+      Some(AssetConfig {
+        yearly_take_rate: Some(Decimal::percent(10)),
+        stake_config: ve3_shared::stake_config::StakeConfig::Astroport {
+          contract: addr.incentive_mock.to_string(),
+          reward_infos: vec![AssetInfoUnchecked::native("astro")],
+        },
+        reward_stream_seconds: None,
+        unbonding_seconds: None,
+        max_boost: None,
+        tokenized: None,
+        take_tiers: None,
+        take_on_rewards,
+        max_total_stake: None,
+        max_stake_per_user: None,
+        apr_cap: None,
+        clear,
+      }),
+    )
+  };
+
+  suite
+    .def_asset_config_astro(|res| res.assert_valid())
+    .e_staking_update_asset_config(
+      take_config(Some(Decimal::percent(20)), None),
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .e_staking_stake(None, addr.lp_native(10_000_000), "user1", |res| res.assert_valid())
+    // idle for a year while only rewards are taken
+    .add_periods(52)
+    .e_staking_update_asset_config(
+      take_config(None, Some(vec![AssetConfigField::TakeOnRewards])),
+      "AT_ASSET_WHITELIST_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .add_one_period()
+    .e_staking_distribute_take_rate(Some(true), None, "user1", |res| {
+      res.assert_attribute(attr("action", "asset/distribute_take_rate"));
+      // only the week after the switch is taken: 7 / 365 * 10% * 10_000000 = 19,178
+      res.assert_attribute(attr("take", addr.lp_native_str(19178)));
+    });
+}
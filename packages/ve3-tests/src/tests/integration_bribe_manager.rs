@@ -2,7 +2,7 @@ use crate::{
   common::{helpers::u, suite::TestingSuite},
   extensions::app_response_ext::{EventChecker, Valid},
 };
-use cosmwasm_std::{attr, StdError};
+use cosmwasm_std::{attr, Decimal, StdError};
 use ve3_bribe_manager::error::ContractError;
 use ve3_shared::{
   constants::SECONDS_PER_WEEK,
@@ -33,7 +33,7 @@ fn test_lock_add_bribes() {
     .e_bribe_add_bribe_native(
       addr.uluna(1000),
       &addr.gauge_1,
-      addr.lp_cw20_info(),
+      Some(addr.lp_cw20_info()),
       ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
       None,
       "creator",
@@ -44,7 +44,7 @@ fn test_lock_add_bribes() {
     .e_bribe_add_bribe_native(
       addr.uluna(1000),
       &addr.gauge_1,
-      addr.lp_cw20_info(),
+      Some(addr.lp_cw20_info()),
       ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
       Some(addr.uluna(10000000)),
       "user1",
@@ -58,7 +58,7 @@ fn test_lock_add_bribes() {
     .e_bribe_add_bribe_native(
       addr.uluna(1000000),
       &addr.gauge_1,
-      addr.lp_native_info(),
+      Some(addr.lp_native_info()),
       ve3_shared::msgs_bribe_manager::BribeDistribution::Func {
         start: Some(80),
         end: 89,
@@ -73,7 +73,7 @@ fn test_lock_add_bribes() {
     .e_bribe_add_bribe_cw20(
       addr.ampluna(1000000),
       &addr.gauge_1,
-      addr.lp_native_info(),
+      Some(addr.lp_native_info()),
       ve3_shared::msgs_bribe_manager::BribeDistribution::Func {
         start: Some(80),
         end: 179,
@@ -353,7 +353,7 @@ fn test_errors() {
     .e_bribe_add_bribe_native(
       addr.uluna(1000),
       &addr.gauge_1,
-      addr.lp_cw20_info(),
+      Some(addr.lp_cw20_info()),
       ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
       None,
       "user1",
@@ -366,7 +366,7 @@ fn test_errors() {
     .e_bribe_add_bribe_native(
       addr.fake_native(10000),
       &addr.gauge_1,
-      addr.lp_native_info(),
+      Some(addr.lp_native_info()),
       ve3_shared::msgs_bribe_manager::BribeDistribution::Func {
         start: Some(80),
         end: 179,
@@ -379,7 +379,7 @@ fn test_errors() {
     .e_bribe_add_bribe_native(
       addr.uluna(1000),
       &addr.gauge_1,
-      addr.lp_cw20_info(),
+      Some(addr.lp_cw20_info()),
       ve3_shared::msgs_bribe_manager::BribeDistribution::Specific(vec![(10u64, u(1000))]),
       None,
       // creator part of AT_FREE_BRIBES
@@ -391,7 +391,7 @@ fn test_errors() {
     .e_bribe_add_bribe_native(
       addr.uluna(1000),
       &addr.gauge_1,
-      addr.lp_cw20_info(),
+      Some(addr.lp_cw20_info()),
       ve3_shared::msgs_bribe_manager::BribeDistribution::Specific(vec![
         (100u64, u(1000)),
         (100u64, u(1000)),
@@ -431,7 +431,7 @@ fn test_errors() {
     .e_bribe_add_bribe_native(
       addr.uluna(0),
       &addr.gauge_1,
-      addr.lp_cw20_info(),
+      Some(addr.lp_cw20_info()),
       ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
       None,
       // creator part of AT_FREE_BRIBES
@@ -467,7 +467,7 @@ fn test_try_withdraw_late() {
     .e_bribe_add_bribe_native(
       addr.uluna(1000),
       &addr.gauge_1,
-      addr.lp_cw20_info(),
+      Some(addr.lp_cw20_info()),
       ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
       None,
       // creator part of AT_FREE_BRIBES
@@ -500,15 +500,26 @@ fn test_update_config() {
           whitelist: vec![addr.uluna_info_checked(), addr.ampluna_info_checked()],
           allow_any: false,
           fee: addr.uluna(10000000),
+          fee_options: vec![],
+          bribe_fee: Decimal::zero(),
           global_config_addr: addr.ve3_global_config.clone()
         }
       )
     })
     .e_bribe_update_config(Some(addr.ampluna(10).into()), None, "creator", |res| {
-      res.assert_error(ContractError::SharedError(SharedError::NotSupported(
-        "must be native".to_string(),
-      )))
+      res.assert_attribute(attr("action", "bribe/update_config"));
+    })
+    .e_bribe_update_fees(None, Some(Decimal::percent(51)), "creator", |res| {
+      res.assert_error(ContractError::BribeFeeTooHigh)
     })
+    .e_bribe_update_fees(
+      Some(vec![addr.uluna(100).into()]),
+      Some(Decimal::percent(1)),
+      "creator",
+      |res| {
+        res.assert_attribute(attr("action", "bribe/update_config"));
+      },
+    )
     .e_bribe_update_config(Some(addr.uluna(10).into()), Some(true), "creator", |res| {
       res.assert_attribute(attr("action", "bribe/update_config"));
     })
@@ -519,12 +530,95 @@ fn test_update_config() {
           whitelist: vec![addr.uluna_info_checked(), addr.ampluna_info_checked()],
           allow_any: true,
           fee: addr.uluna(10),
+          fee_options: vec![addr.uluna(100)],
+          bribe_fee: Decimal::percent(1),
           global_config_addr: addr.ve3_global_config.clone()
         }
       )
     });
 }
 
+#[test]
+fn test_bribe_fees() {
+  let mut suite = TestingSuite::def();
+  let addr = suite.init();
+
+  suite
+    .e_bribe_update_fees(
+      Some(vec![addr.ampluna(100).into()]),
+      Some(Decimal::percent(10)),
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .e_bribe_add_bribe_cw20_fee(
+      addr.uluna(1000),
+      &addr.gauge_1,
+      Some(addr.lp_cw20_info()),
+      ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
+      addr.ampluna(100),
+      "user1",
+      |res| {
+        res.assert_attribute(attr("action", "bribe/add_bribe"));
+        // cw20 fee
+        res.assert_attribute(attr("action", "transfer_from"));
+        res.assert_attribute(attr("to", addr.fee_recipient.to_string()));
+        res.assert_attribute(attr("amount", "100"));
+        // percentage fee
+        res.assert_attribute_ty("transfer", attr("recipient", addr.fee_recipient.to_string()));
+        res.assert_attribute_ty("transfer", attr("amount", "100uluna"));
+      },
+    )
+    .e_bribe_add_bribe_cw20_fee(
+      addr.uluna(1000),
+      &addr.gauge_1,
+      Some(addr.lp_cw20_info()),
+      ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
+      addr.lp_cw20(100),
+      "user1",
+      |res| {
+        res.assert_error(ContractError::SharedError(SharedError::NotSupported(format!(
+          "fee asset {0}",
+          addr.lp_cw20_info_checked()
+        ))))
+      },
+    )
+    .e_bribe_add_bribe_native(
+      addr.uluna(2000),
+      &addr.gauge_1,
+      Some(addr.lp_cw20_info()),
+      ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
+      Some(addr.uluna(10000000)),
+      "user2",
+      |res| {
+        res.assert_attribute(attr("action", "bribe/add_bribe"));
+        res.assert_attribute_ty("transfer", attr("amount", "10000000uluna"));
+        res.assert_attribute_ty("transfer", attr("amount", "200uluna"));
+      },
+    )
+    // creator part of AT_FREE_BRIBES, no percentage fee
+    .e_bribe_add_bribe_native(
+      addr.uluna(1000),
+      &addr.gauge_1,
+      Some(addr.lp_cw20_info()),
+      ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .q_bribe_bribes(Some(Time::Next), |res| {
+      assert_eq!(
+        res.unwrap(),
+        BribeBuckets {
+          buckets: vec![BribeBucket {
+            gauge: addr.gauge_1.to_string(),
+            asset: Some(addr.lp_cw20_info_checked()),
+            assets: Assets(vec![addr.uluna(900 + 1800 + 1000)])
+          }]
+        }
+      )
+    });
+}
+
 #[test]
 fn test_any_bribe() {
   let mut suite = TestingSuite::def();
@@ -544,7 +638,7 @@ fn test_any_bribe() {
     .e_bribe_add_bribe_native(
       addr.lp_native(1000),
       &addr.gauge_1,
-      addr.lp_cw20_info(),
+      Some(addr.lp_cw20_info()),
       ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
       None,
       "creator",
@@ -627,7 +721,7 @@ fn test_query_claimable_without_distribution() {
     .e_bribe_add_bribe_native(
       addr.uluna(1000),
       &addr.gauge_1,
-      addr.lp_cw20_info(),
+      Some(addr.lp_cw20_info()),
       ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
       None,
       // creator part of AT_FREE_BRIBES
@@ -709,3 +803,330 @@ fn test_query_claimable_without_distribution() {
       res.assert_error(ContractError::Std(StdError::generic_err("Querier contract error: User 'terra1vqjarrly327529599rcc4qhzvhwe34pp5uyy4gylvxe5zupeqx3sl7x356' has no voting power in period 75".to_string())));
     });
 }
+
+#[test]
+fn test_withdraw_single_bribe() {
+  let mut suite = TestingSuite::def();
+  let addr = suite.init();
+
+  suite
+    .e_bribe_add_bribe_native(
+      addr.uluna(1000),
+      &addr.gauge_1,
+      Some(addr.lp_cw20_info()),
+      ve3_shared::msgs_bribe_manager::BribeDistribution::Specific(vec![(80u64, u(1000))]),
+      None,
+      // creator part of AT_FREE_BRIBES
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .e_bribe_add_bribe_native(
+      addr.uluna(2000),
+      &addr.gauge_1,
+      Some(addr.lp_native_info()),
+      ve3_shared::msgs_bribe_manager::BribeDistribution::Specific(vec![
+        (80u64, u(1000)),
+        (81u64, u(1000)),
+      ]),
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .q_bribe_creator_bribes("creator", None, None, |res| {
+      assert_eq!(
+        res.unwrap(),
+        vec![
+          (
+            80,
+            BribeBuckets {
+              buckets: vec![
+                BribeBucket {
+                  gauge: addr.gauge_1.to_string(),
+                  asset: Some(addr.lp_cw20_info_checked()),
+                  assets: Assets(vec![addr.uluna(1000)])
+                },
+                BribeBucket {
+                  gauge: addr.gauge_1.to_string(),
+                  asset: Some(addr.lp_native_info_checked()),
+                  assets: Assets(vec![addr.uluna(1000)])
+                }
+              ]
+            }
+          ),
+          (
+            81,
+            BribeBuckets {
+              buckets: vec![BribeBucket {
+                gauge: addr.gauge_1.to_string(),
+                asset: Some(addr.lp_native_info_checked()),
+                assets: Assets(vec![addr.uluna(1000)])
+              }]
+            }
+          )
+        ]
+      )
+    })
+    .e_bribe_withdraw_bribe(
+      80,
+      &addr.gauge_1,
+      Some(addr.lp_native_info()),
+      addr.uluna_info(),
+      "user1",
+      |res| res.assert_error(ContractError::NoBribes),
+    )
+    .e_bribe_withdraw_bribe(
+      80,
+      &addr.gauge_1,
+      Some(addr.lp_native_info()),
+      addr.uluna_info(),
+      "creator",
+      |res| {
+        res.assert_attribute(attr("action", "bribe/withdraw_bribe"));
+        res.assert_attribute(attr("period", "80"));
+        res.assert_attribute_ty("transfer", attr("recipient", addr.creator.to_string()));
+        res.assert_attribute_ty("transfer", attr("amount", "1000uluna"));
+      },
+    )
+    .e_bribe_withdraw_bribe(
+      80,
+      &addr.gauge_1,
+      Some(addr.lp_native_info()),
+      addr.uluna_info(),
+      "creator",
+      |res| res.assert_error(ContractError::NoBribes),
+    )
+    .q_bribe_creator_bribes("creator", Some(80), Some(1), |res| {
+      assert_eq!(
+        res.unwrap(),
+        vec![(
+          81,
+          BribeBuckets {
+            buckets: vec![BribeBucket {
+              gauge: addr.gauge_1.to_string(),
+              asset: Some(addr.lp_native_info_checked()),
+              assets: Assets(vec![addr.uluna(1000)])
+            }]
+          }
+        )]
+      )
+    })
+    .q_bribe_bribes(Some(Time::Period(80)), |res| {
+      assert_eq!(
+        res.unwrap(),
+        BribeBuckets {
+          buckets: vec![BribeBucket {
+            gauge: addr.gauge_1.to_string(),
+            asset: Some(addr.lp_cw20_info_checked()),
+            assets: Assets(vec![addr.uluna(1000)])
+          }]
+        }
+      )
+    })
+    .add_periods(6)
+    .e_bribe_withdraw_bribe(
+      80,
+      &addr.gauge_1,
+      Some(addr.lp_cw20_info()),
+      addr.uluna_info(),
+      "creator",
+      |res| res.assert_error(ContractError::BribesAlreadyDistributing),
+    );
+}
+
+#[test]
+fn test_gauge_bribes() {
+  let mut suite = TestingSuite::def();
+  let addr = suite.init();
+
+  suite
+    .e_ve_create_lock_time(SECONDS_PER_WEEK * 2, addr.uluna(1000), "user1", |res| {
+      res.assert_valid()
+    })
+    .e_ve_create_lock_time(SECONDS_PER_WEEK * 2, addr.uluna(1000), "user2", |res| {
+      res.assert_valid()
+    })
+    .def_staking_whitelist_recapture()
+    .def_gauge_1_vote(10000, 0, "user1", |res| res.assert_valid())
+    .def_gauge_1_vote(0, 10000, "user2", |res| res.assert_valid())
+    .add_one_period()
+    .e_gauge_set_distribution("user1", |res| res.assert_valid())
+    .e_bribe_add_bribe_native(
+      addr.uluna(1000),
+      &addr.gauge_1,
+      None,
+      ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
+      None,
+      // creator part of AT_FREE_BRIBES
+      "creator",
+      |res| {
+        res.assert_attribute(attr("action", "bribe/add_bribe"));
+        res.assert_attribute(attr("start", "76"));
+      },
+    )
+    .e_bribe_add_bribe_native(
+      addr.uluna(1000),
+      &addr.gauge_1,
+      Some(addr.lp_cw20_info()),
+      ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .e_bribe_add_bribe_native(
+      addr.uluna(1000),
+      &addr.gauge_1,
+      None,
+      ve3_shared::msgs_bribe_manager::BribeDistribution::Specific(vec![(80u64, u(1000))]),
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .e_bribe_withdraw_bribe(80, &addr.gauge_1, None, addr.uluna_info(), "creator", |res| {
+      res.assert_attribute(attr("action", "bribe/withdraw_bribe"));
+      res.assert_attribute_ty("transfer", attr("amount", "1000uluna"));
+    })
+    .q_bribe_bribes(Some(Time::Next), |res| {
+      assert_eq!(
+        res.unwrap(),
+        BribeBuckets {
+          buckets: vec![
+            BribeBucket {
+              gauge: addr.gauge_1.to_string(),
+              asset: None,
+              assets: Assets(vec![addr.uluna(1000)])
+            },
+            BribeBucket {
+              gauge: addr.gauge_1.to_string(),
+              asset: Some(addr.lp_cw20_info_checked()),
+              assets: Assets(vec![addr.uluna(1000)])
+            }
+          ]
+        }
+      )
+    })
+    .add_one_period()
+    .e_gauge_set_distribution("user1", |res| res.assert_valid())
+    // both users have the same vp in the gauge, but only user2 voted for the cw20 lp
+    .q_bribe_user_claimable("user1", None, |res| {
+      assert_eq!(
+        res.unwrap(),
+        UserClaimableResponse {
+          start: 75,
+          end: 76,
+          buckets: vec![BribeBucket {
+            gauge: addr.gauge_1.to_string(),
+            asset: None,
+            assets: Assets(vec![addr.uluna(500)])
+          }]
+        }
+      )
+    })
+    .q_bribe_user_claimable("user2", None, |res| {
+      assert_eq!(
+        res.unwrap(),
+        UserClaimableResponse {
+          start: 75,
+          end: 76,
+          buckets: vec![
+            BribeBucket {
+              gauge: addr.gauge_1.to_string(),
+              asset: Some(addr.lp_cw20_info_checked()),
+              assets: Assets(vec![addr.uluna(1000)])
+            },
+            BribeBucket {
+              gauge: addr.gauge_1.to_string(),
+              asset: None,
+              assets: Assets(vec![addr.uluna(500)])
+            }
+          ]
+        }
+      )
+    })
+    .e_bribe_claim_bribes(None, "user1", |res| {
+      res.assert_attribute(attr("action", "bribe/claim_bribes"));
+      res.assert_attribute(attr("periods", "75,76"));
+      res.assert_attribute_ty("transfer", attr("recipient", addr.user1.to_string()));
+      res.assert_attribute_ty("transfer", attr("amount", "500uluna"));
+    })
+    .e_bribe_claim_bribes(None, "user2", |res| {
+      res.assert_attribute(attr("action", "bribe/claim_bribes"));
+      res.assert_attribute(attr("periods", "75,76"));
+      res.assert_attribute_ty("transfer", attr("recipient", addr.user2.to_string()));
+      res.assert_attribute_ty("transfer", attr("amount", "1500uluna"));
+    });
+}
+
+#[test]
+fn test_receive_bribe() {
+  let mut suite = TestingSuite::def();
+  let addr = suite.init();
+
+  suite
+    .e_bribe_update_fees(Some(vec![addr.ampluna(100).into()]), None, "creator", |res| {
+      res.assert_valid()
+    })
+    // default fee is native, which can't be sent with the hook
+    .e_bribe_send_bribe_cw20(
+      addr.ampluna(1000),
+      &addr.gauge_1,
+      Some(addr.lp_cw20_info()),
+      ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
+      None,
+      "user1",
+      |res| {
+        res.assert_error(ContractError::SharedError(SharedError::WrongDeposit(
+          "expected 1 coins".to_string(),
+        )))
+      },
+    )
+    .e_bribe_send_bribe_cw20(
+      addr.ampluna(1000),
+      &addr.gauge_1,
+      Some(addr.lp_cw20_info()),
+      ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
+      Some(addr.ampluna_info()),
+      "user1",
+      |res| {
+        res.assert_attribute(attr("action", "bribe/add_bribe"));
+        res.assert_attribute(attr("to", addr.fee_recipient.to_string()));
+        res.assert_attribute(attr("amount", "100"));
+      },
+    )
+    // creator part of AT_FREE_BRIBES
+    .e_bribe_send_bribe_cw20(
+      addr.ampluna(1000),
+      &addr.gauge_1,
+      Some(addr.lp_cw20_info()),
+      ve3_shared::msgs_bribe_manager::BribeDistribution::Next,
+      None,
+      "creator",
+      |res| res.assert_attribute(attr("action", "bribe/add_bribe")),
+    )
+    .q_bribe_bribes(Some(Time::Next), |res| {
+      assert_eq!(
+        res.unwrap(),
+        BribeBuckets {
+          buckets: vec![BribeBucket {
+            gauge: addr.gauge_1.to_string(),
+            asset: Some(addr.lp_cw20_info_checked()),
+            assets: Assets(vec![addr.ampluna(900 + 1000)])
+          }]
+        }
+      )
+    })
+    .q_bribe_creator_bribes("user1", None, None, |res| {
+      assert_eq!(
+        res.unwrap(),
+        vec![(
+          75,
+          BribeBuckets {
+            buckets: vec![BribeBucket {
+              gauge: addr.gauge_1.to_string(),
+              asset: Some(addr.lp_cw20_info_checked()),
+              assets: Assets(vec![addr.ampluna(900)])
+            }]
+          }
+        )]
+      )
+    });
+}
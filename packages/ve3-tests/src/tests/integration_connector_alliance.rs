@@ -5,10 +5,13 @@ use crate::{
     helpers::assert_close,
   },
 };
-use cosmwasm_std::{attr, Decimal};
+use cosmwasm_std::{attr, coin, Decimal};
 use ve3_connector_alliance::error::ContractError;
 use ve3_shared::{
-  constants::at_asset_staking, error::SharedError, msgs_asset_gauge::UserPendingRebaseResponse,
+  constants::{at_asset_staking, AT_DELEGATION_CONTROLLER},
+  error::SharedError,
+  helpers::alliance::query_alliance_delegations,
+  msgs_asset_gauge::UserPendingRebaseResponse,
   msgs_connector_alliance::*,
 };
 
@@ -235,3 +238,161 @@ fn test_alliance_connector_staking_rewards() {
       res.assert_attribute(attr("reward_amount", "66666"));
     });
 }
+
+#[test]
+fn test_alliance_connector_rebalance() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let addr = suite.addresses.clone();
+  let connector = addr.ve3_connector_alliance_eris.clone();
+  let val1 = suite.address("val1").to_string();
+  let val2 = suite.address("val2").to_string();
+  let val3 = suite.address("val3").to_string();
+  let delegation = |validator: &str, amount: u32| AllianceDelegation {
+    validator: validator.to_string(),
+    amount: u(amount),
+  };
+
+  suite
+    .use_connector_alliance_eris()
+    .e_alliance_alliance_delegate(
+      AllianceDelegateMsg {
+        delegations: vec![delegation(&val1, 600), delegation(&val2, 400)],
+      },
+      "AT_DELEGATION_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .e_alliance_rebalance(None, "user1", |res| res.assert_error(ContractError::NoTargetDelegations))
+    .e_alliance_set_target_delegations(vec![(val1.as_str(), Decimal::one())], "user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::UnauthorizedMissingRight(
+        AT_DELEGATION_CONTROLLER.to_string(),
+        addr.user1.to_string(),
+      )))
+    })
+    .e_alliance_set_target_delegations(
+      vec![(val1.as_str(), Decimal::percent(50)), (val3.as_str(), Decimal::percent(40))],
+      "AT_DELEGATION_CONTROLLER",
+      |res| res.assert_error(ContractError::InvalidTargetWeights),
+    )
+    .e_alliance_set_target_delegations(
+      vec![(val1.as_str(), Decimal::percent(50)), ("valx", Decimal::percent(50))],
+      "AT_DELEGATION_CONTROLLER",
+      |res| res.assert_error(ContractError::InvalidValidator("valx".to_string())),
+    )
+    .e_alliance_set_target_delegations(
+      vec![(val1.as_str(), Decimal::percent(50)), (val1.as_str(), Decimal::percent(50))],
+      "AT_DELEGATION_CONTROLLER",
+      |res| res.assert_error(ContractError::DuplicateValidator(val1.clone())),
+    )
+    .e_alliance_set_target_delegations(
+      vec![
+        (val1.as_str(), Decimal::percent(25)),
+        (val2.as_str(), Decimal::percent(25)),
+        (val3.as_str(), Decimal::percent(50)),
+      ],
+      "AT_DELEGATION_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .q_alliance_target_delegations(|res| {
+      assert_eq!(
+        res.unwrap(),
+        vec![
+          (val1.clone(), Decimal::percent(25)),
+          (val2.clone(), Decimal::percent(25)),
+          (val3.clone(), Decimal::percent(50)),
+        ]
+      )
+    })
+    // largest surplus first: val1 -> val3
+    .e_alliance_rebalance(Some(1), "user1", |res| {
+      res.assert_attribute(attr("action", "ca/rebalance"));
+      res.assert_attribute(attr("moves", "1"));
+    })
+    .e_alliance_rebalance(None, "user1", |res| {
+      res.assert_attribute(attr("moves", "1"));
+    })
+    .e_alliance_rebalance(None, "user1", |res| res.assert_error(ContractError::NothingToRebalance));
+
+  let config: Config =
+    suite.app.wrap().query_wasm_smart(connector.clone(), &QueryMsg::Config {}).unwrap();
+  let denom = config.alliance_token_denom;
+  let mut delegations = query_alliance_delegations(&suite.app.wrap(), &connector, &denom).unwrap();
+  delegations.sort();
+  let mut expected = vec![(val1.clone(), u(250)), (val2.clone(), u(250)), (val3.clone(), u(500))];
+  expected.sort();
+  assert_eq!(delegations, expected);
+
+  suite
+    .e_alliance_set_target_delegations(
+      vec![(val1.as_str(), Decimal::one())],
+      "AT_DELEGATION_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    // val3 received a redelegation, it can't be moved before it matured
+    .e_alliance_rebalance(None, "user1", |res| {
+      res.assert_attribute(attr("moves", "1"));
+    })
+    .e_alliance_rebalance(None, "user1", |res| res.assert_error(ContractError::NothingToRebalance))
+    .add_periods(3)
+    .e_alliance_rebalance(None, "user1", |res| {
+      res.assert_attribute(attr("moves", "1"));
+    })
+    .q_alliance_validators(|res| {
+      assert_eq!(res.unwrap().len(), 3);
+    });
+
+  let delegations = query_alliance_delegations(&suite.app.wrap(), &connector, &denom).unwrap();
+  assert_eq!(delegations, vec![(val1.clone(), u(1000))]);
+}
+
+#[test]
+fn test_alliance_connector_delegations() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  let delegation = |validator: &str, amount: u32| AllianceDelegation {
+    validator: validator.to_string(),
+    amount: u(amount),
+  };
+
+  suite
+    .use_connector_alliance_eris()
+    .q_alliance_delegations(|res| assert_eq!(res.unwrap(), vec![]))
+    .e_alliance_alliance_delegate(
+      AllianceDelegateMsg {
+        delegations: vec![delegation("val2", 400), delegation("val1", 600)],
+      },
+      "AT_DELEGATION_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .q_alliance_delegations(|res| {
+      assert_eq!(res.unwrap(), vec![delegation("val1", 600), delegation("val2", 400)])
+    })
+    .e_alliance_alliance_undelegate(
+      AllianceUndelegateMsg {
+        undelegations: vec![delegation("val2", 400)],
+      },
+      "AT_DELEGATION_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    // validators without delegation are still part of the set until removed
+    .q_alliance_delegations(|res| {
+      assert_eq!(res.unwrap(), vec![delegation("val1", 600), delegation("val2", 0)])
+    })
+    .q_alliance_pending_alliance_rewards(|res| {
+      assert_eq!(
+        res.unwrap(),
+        vec![
+          AllianceValidatorRewards {
+            validator: "val1".to_string(),
+            rewards: vec![coin(60, "uluna")],
+          },
+          AllianceValidatorRewards {
+            validator: "val2".to_string(),
+            rewards: vec![],
+          }
+        ]
+      )
+    });
+}
@@ -6,13 +6,17 @@ use crate::{
   extensions::app_response_ext::{EventChecker, Valid},
 };
 use cosmwasm_std::{attr, Decimal, Uint128};
+use cw_multi_test::Executor;
 use ve3_connector_emission::error::ContractError;
 use ve3_shared::{
-  constants::{at_asset_staking, SECONDS_PER_YEAR},
+  constants::{at_asset_staking, at_connector, SECONDS_PER_WEEK, SECONDS_PER_YEAR},
   error::SharedError,
-  extensions::asset_info_ext::AssetInfoExt,
+  extensions::{asset_ext::AssetExt, asset_info_ext::AssetInfoExt},
   msgs_asset_gauge::UserPendingRebaseResponse,
-  msgs_connector_emission::{Config, RebaseConfg},
+  msgs_connector_emission::{
+    Config, EmissionSchedule, MintConfig, RebaseConfg, SimulateClaimResponse, State, TeamPayout,
+    TeamVesting, TeamVestingResponse,
+  },
 };
 
 #[test]
@@ -140,10 +144,15 @@ fn test_emission_connector_rebase() {
           emissions_per_week: u(100),
           team_share: Decimal::percent(10),
           enabled: true,
-          rebase_config: ve3_shared::msgs_connector_emission::RebaseConfg::Dynamic {},
+          rebase_config: ve3_shared::msgs_connector_emission::RebaseConfg::default_dynamic(),
           mint_config: ve3_shared::msgs_connector_emission::MintConfig::MintDirect,
           last_claim_s: 1712847600,
-          emission_token: addr.uluna_info_checked()
+          emission_token: addr.uluna_info_checked(),
+          emission_schedule: EmissionSchedule::Constant,
+          max_total_emission: None,
+          cap_reached: false,
+          team_payout: TeamPayout::Direct,
+          gauges: vec![],
         }
       );
     })
@@ -255,3 +264,725 @@ fn test_emission_connector_rebase_apy() {
       res.assert_attribute(attr("rewards", "native:uluna:5214"));
     });
 }
+
+#[test]
+fn test_emission_connector_schedule() {
+  let mut suite = TestingSuite::def();
+  let addr = suite.init_options(InitOptions {
+    rebase_asset: Some(suite.addresses.uluna_info()),
+    mock_zapper: None,
+  });
+
+  suite
+    .use_connector_emissions()
+    .use_staking_3()
+    .e_ve_create_lock_time_any(None, addr.uluna(1000), "user1", |res| res.assert_valid())
+    .e_ve_create_lock_time_any(None, addr.uluna(2000), "user2", |res| res.assert_valid())
+    .def_staking_whitelist_recapture()
+    .def_gauge_3_vote(5000, 5000, "user1", |res| res.assert_valid())
+    .def_gauge_3_vote(7500, 2500, "user2", |res| res.assert_valid())
+    .e_emission_update_schedule(EmissionSchedule::Steps(vec![(76, u(50))]), "user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::Unauthorized {}))
+    })
+    .e_emission_update_schedule(EmissionSchedule::Steps(vec![]), "creator", |res| {
+      res.assert_error(ContractError::InvalidEmissionSchedule(
+        "steps must not be empty, use Constant instead".to_string(),
+      ))
+    })
+    .e_emission_update_schedule(
+      EmissionSchedule::Steps(vec![(76, u(50)), (75, u(200))]),
+      "creator",
+      |res| {
+        res.assert_error(ContractError::InvalidEmissionSchedule(
+          "steps must be sorted by start_period".to_string(),
+        ))
+      },
+    )
+    .e_emission_update_schedule(
+      EmissionSchedule::Decay {
+        start_period: 75,
+        weekly_decay: Decimal::percent(150),
+        floor: u(20),
+      },
+      "creator",
+      |res| {
+        res.assert_error(ContractError::InvalidEmissionSchedule(
+          "weekly_decay must not be greater than 1".to_string(),
+        ))
+      },
+    )
+    .e_emission_update_schedule(
+      EmissionSchedule::Steps(vec![(75, u(200)), (76, u(50))]),
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .q_emission_schedule(73, 77, |res| {
+      assert_eq!(
+        res.unwrap(),
+        vec![(73, u(100)), (74, u(100)), (75, u(200)), (76, u(50)), (77, u(50))]
+      );
+    })
+    .e_emission_update_config(
+      None,
+      None,
+      Some(RebaseConfg::Fixed(Decimal::percent(20))),
+      None,
+      Some(true),
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .add_one_period()
+    .e_gauge_set_distribution("user1", |res| res.assert_valid())
+    .e_staking_update_rewards("user1", |res| {
+      // 291600s in period 74 at 100 + 313200s in period 75 at 200
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("emission_amount", "151"));
+      res.assert_attribute(attr("rebase_amount", "30"));
+      res.assert_attribute(attr("team_amount", "15"));
+    })
+    .add_one_period()
+    .e_staking_update_rewards("user1", |res| {
+      // 291600s in period 75 at 200 + 313200s in period 76 at 50
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("emission_amount", "122"));
+      res.assert_attribute(attr("rebase_amount", "24"));
+      res.assert_attribute(attr("team_amount", "12"));
+    })
+    .e_emission_update_schedule(
+      EmissionSchedule::Decay {
+        start_period: 75,
+        weekly_decay: Decimal::percent(50),
+        floor: u(20),
+      },
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .q_emission_schedule(74, 78, |res| {
+      assert_eq!(
+        res.unwrap(),
+        vec![(74, u(100)), (75, u(100)), (76, u(50)), (77, u(25)), (78, u(20))]
+      );
+    })
+    .q_emission_schedule(78, 74, |res| {
+      assert_eq!(
+        res.unwrap_err().to_string(),
+        "Generic error: Querier contract error: Invalid period range: to_period must not be before from_period"
+      );
+    });
+}
+
+#[test]
+fn test_emission_connector_max_total_emission() {
+  let mut suite = TestingSuite::def();
+  let addr = suite.init_options(InitOptions {
+    rebase_asset: Some(suite.addresses.uluna_info()),
+    mock_zapper: None,
+  });
+
+  suite
+    .use_connector_emissions()
+    .use_staking_3()
+    .e_ve_create_lock_time_any(None, addr.uluna(1000), "user1", |res| res.assert_valid())
+    .e_ve_create_lock_time_any(None, addr.uluna(2000), "user2", |res| res.assert_valid())
+    .def_staking_whitelist_recapture()
+    .def_gauge_3_vote(5000, 5000, "user1", |res| res.assert_valid())
+    .def_gauge_3_vote(7500, 2500, "user2", |res| res.assert_valid())
+    .e_emission_update_max_total_emission(u(300), "user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::Unauthorized {}))
+    })
+    .e_emission_update_max_total_emission(u(300), "creator", |res| res.assert_valid())
+    .e_emission_update_config(
+      None,
+      None,
+      Some(RebaseConfg::Fixed(Decimal::percent(20))),
+      None,
+      Some(true),
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .add_one_period()
+    .e_gauge_set_distribution("user1", |res| res.assert_valid())
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("emission_amount", "100"));
+      res.assert_attribute(attr("rebase_amount", "20"));
+      res.assert_attribute(attr("team_amount", "10"));
+    })
+    .add_one_period()
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("emission_amount", "100"));
+    })
+    .q_emission_state(|res| {
+      assert_eq!(
+        res.unwrap(),
+        State {
+          total_emitted: u(200),
+          total_team: u(20),
+          total_rebase: u(40),
+        }
+      );
+    })
+    .add_one_period()
+    .e_staking_update_rewards("user1", |res| {
+      // 40 remaining from 130
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("emission_amount", "30"));
+      res.assert_attribute(attr("rebase_amount", "6"));
+      res.assert_attribute(attr("team_amount", "3"));
+      res.assert_attribute(attr("cap_reached", "true"));
+      res.assert_attribute(attr("rewards", "native:uluna:30"));
+    })
+    .q_emission_state(|res| {
+      assert_eq!(
+        res.unwrap(),
+        State {
+          total_emitted: u(230),
+          total_team: u(23),
+          total_rebase: u(46),
+        }
+      );
+    })
+    .q_emission_config(|res| {
+      assert!(!res.unwrap().enabled);
+    })
+    .add_one_period()
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards_noop"));
+    })
+    .e_emission_update_config(None, None, None, None, Some(true), None, "creator", |res| {
+      res.assert_valid()
+    })
+    .add_one_period()
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards_noop"));
+      res.assert_attribute(attr("cap_reached", "true"));
+    })
+    // lowering the cap keeps the connector disabled
+    .e_emission_update_max_total_emission(u(299), "creator", |res| res.assert_valid())
+    .q_emission_config(|res| {
+      assert!(!res.unwrap().enabled);
+    })
+    // raising the cap resumes the emissions from now on
+    .e_emission_update_max_total_emission(u(430), "creator", |res| res.assert_valid())
+    .q_emission_config(|res| {
+      let config = res.unwrap();
+      assert!(config.enabled);
+      assert!(!config.cap_reached);
+    })
+    .add_one_period()
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("emission_amount", "100"));
+      res.assert_attribute(attr("rebase_amount", "20"));
+      res.assert_attribute(attr("team_amount", "10"));
+    })
+    .add_one_period()
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards_noop"));
+      res.assert_attribute(attr("cap_reached", "true"));
+    })
+    .q_emission_config(|res| {
+      let config = res.unwrap();
+      assert!(!config.enabled);
+      assert!(config.cap_reached);
+    })
+    // removing the cap resumes the emissions without a limit
+    .e_emission_clear_max_total_emission("user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::Unauthorized {}))
+    })
+    .e_emission_clear_max_total_emission("creator", |res| res.assert_valid())
+    .q_emission_config(|res| {
+      let config = res.unwrap();
+      assert!(config.enabled);
+      assert_eq!(config.max_total_emission, None);
+    })
+    .add_one_period()
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("emission_amount", "100"));
+    })
+    .q_emission_state(|res| {
+      assert_eq!(
+        res.unwrap(),
+        State {
+          total_emitted: u(430),
+          total_team: u(43),
+          total_rebase: u(86),
+        }
+      );
+    });
+}
+
+#[test]
+fn test_emission_connector_team_vesting() {
+  let mut suite = TestingSuite::def();
+  let addr = suite.init_options(InitOptions {
+    rebase_asset: Some(suite.addresses.uluna_info()),
+    mock_zapper: None,
+  });
+
+  let team_wallet = suite.address("AT_TEAM_WALLET");
+
+  suite
+    .use_connector_emissions()
+    .use_staking_3()
+    .e_ve_create_lock_time_any(None, addr.uluna(1000), "user1", |res| res.assert_valid())
+    .e_ve_create_lock_time_any(None, addr.uluna(2000), "user2", |res| res.assert_valid())
+    .def_staking_whitelist_recapture()
+    .def_gauge_3_vote(5000, 5000, "user1", |res| res.assert_valid())
+    .def_gauge_3_vote(7500, 2500, "user2", |res| res.assert_valid())
+    .e_emission_update_team_payout(
+      TeamPayout::Vesting {
+        duration_s: 4 * SECONDS_PER_WEEK,
+      },
+      "user1",
+      |res| res.assert_error(ContractError::SharedError(SharedError::Unauthorized {})),
+    )
+    .e_emission_update_team_payout(
+      TeamPayout::Vesting {
+        duration_s: 4 * SECONDS_PER_WEEK,
+      },
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .e_emission_update_config(
+      None,
+      None,
+      Some(RebaseConfg::Fixed(Decimal::percent(20))),
+      None,
+      Some(true),
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .add_one_period()
+    .e_gauge_set_distribution("user1", |res| res.assert_valid())
+    .e_staking_update_rewards("user1", |res| {
+      let res = res.unwrap();
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("team_amount", "10"));
+      assert!(!res.events.iter().any(|e| e
+        .attributes
+        .iter()
+        .any(|a| a.key == "recipient" && a.value == team_wallet.to_string())));
+    })
+    .e_emission_claim_team_vested("user1", |res| res.assert_error(ContractError::NothingToClaim {}))
+    .q_emission_team_vesting(|res| {
+      assert_eq!(
+        res.unwrap(),
+        TeamVestingResponse {
+          vestings: vec![(
+            75,
+            TeamVesting {
+              start_s: 1713139200,
+              end_s: 1715558400,
+              amount: u(10),
+              claimed: u(0),
+            }
+          )],
+          claimable: u(0),
+        }
+      );
+    })
+    .add_periods(3)
+    .e_emission_claim_team_vested("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_team_vested"));
+      res.assert_attribute(attr("amount", "6"));
+      res.assert_transfer(team_wallet.clone(), addr.uluna(6));
+    })
+    .add_periods(2)
+    .q_emission_team_vesting(|res| {
+      assert_eq!(res.unwrap().claimable, u(4));
+    })
+    .e_emission_claim_team_vested("user1", |res| {
+      res.assert_attribute(attr("amount", "4"));
+      res.assert_transfer(team_wallet.clone(), addr.uluna(4));
+    })
+    .q_emission_team_vesting(|res| {
+      assert_eq!(
+        res.unwrap(),
+        TeamVestingResponse {
+          vestings: vec![],
+          claimable: u(0),
+        }
+      );
+    })
+    .add_one_period()
+    .e_gauge_set_distribution("user1", |res| res.assert_valid())
+    .e_staking_update_rewards("user1", |res| res.assert_valid())
+    .e_emission_update_config(
+      None,
+      None,
+      None,
+      Some(MintConfig::UseBalance),
+      None,
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .add_one_period()
+    .e_gauge_set_distribution("user1", |res| res.assert_valid())
+    // the only balance is the unvested team share, which must not be paid out as emissions
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::InsufficientBalance(
+        "contract balance".to_string(),
+      )))
+    });
+
+  suite
+    .app
+    .send_tokens(
+      suite.address("user1"),
+      addr.ve3_connector_emissions.clone(),
+      &[addr.uluna(1_000_000).to_coin().unwrap()],
+    )
+    .unwrap();
+
+  suite
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+    })
+    .q_emission_team_vesting(|res| {
+      assert_eq!(res.unwrap().vestings.len(), 2);
+    });
+}
+
+#[test]
+fn test_emission_connector_rebase_locked_ratio() {
+  let mut suite = TestingSuite::def();
+  let addr = suite.init_options(InitOptions {
+    rebase_asset: Some(suite.addresses.uluna_info()),
+    mock_zapper: None,
+  });
+
+  suite
+    .use_connector_emissions()
+    .use_staking_3()
+    .e_ve_create_lock_time_any(None, addr.uluna(1000), "user1", |res| res.assert_valid())
+    .e_ve_create_lock_time_any(None, addr.uluna(2000), "user2", |res| res.assert_valid())
+    .def_staking_whitelist_recapture()
+    .def_gauge_3_vote(5000, 5000, "user1", |res| res.assert_valid())
+    .def_gauge_3_vote(7500, 2500, "user2", |res| res.assert_valid())
+    .e_emission_update_config(
+      None,
+      None,
+      Some(RebaseConfg::LockedRatio {
+        target_ratio: Decimal::zero(),
+        max_rebase: Decimal::percent(30),
+        excluded_addrs: vec![],
+      }),
+      None,
+      None,
+      None,
+      "creator",
+      |res| {
+        res.assert_error(ContractError::InvalidRebaseConfig(
+          "target_ratio must not be zero".to_string(),
+        ))
+      },
+    )
+    .e_emission_update_config(
+      None,
+      None,
+      Some(RebaseConfg::LockedRatio {
+        target_ratio: Decimal::percent(100),
+        max_rebase: Decimal::percent(30),
+        excluded_addrs: vec![],
+      }),
+      None,
+      None,
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .q_emission_simulate_claim(SECONDS_PER_WEEK, |res| {
+      // lock ratio is close to 0 with the full supply circulating
+      assert_eq!(
+        res.unwrap(),
+        SimulateClaimResponse {
+          emission_amount: u(100),
+          team_amount: u(10),
+          rebase_amount: u(29),
+          cap_reached: false,
+        }
+      );
+    });
+
+  // excluded wallets do not count towards the circulating supply
+  let excluded_addrs =
+    vec![addr.creator.to_string(), addr.user1.to_string(), addr.user2.to_string()];
+  let mut circulating_supply = suite.app.wrap().query_supply("uluna").unwrap().amount;
+  for excluded in excluded_addrs.iter() {
+    circulating_supply -= suite.app.wrap().query_balance(excluded, "uluna").unwrap().amount;
+  }
+  let lock_ratio = Decimal::from_ratio(3000u128, circulating_supply);
+  let expected_rebase = u(100) * (Decimal::percent(30) * (Decimal::one() - lock_ratio));
+
+  suite
+    .e_emission_update_config(
+      None,
+      None,
+      Some(RebaseConfg::LockedRatio {
+        target_ratio: Decimal::percent(100),
+        max_rebase: Decimal::percent(30),
+        excluded_addrs,
+      }),
+      None,
+      None,
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .q_emission_simulate_claim(SECONDS_PER_WEEK, |res| {
+      assert_eq!(res.unwrap().rebase_amount, expected_rebase);
+    })
+    .e_emission_update_config(
+      None,
+      None,
+      Some(RebaseConfg::Dynamic {
+        divisor: Decimal::from_ratio(10u128, 1u128),
+        exponent: 2,
+        multiplier: Decimal::percent(50),
+        max: Some(Decimal::percent(30)),
+      }),
+      None,
+      Some(true),
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .q_emission_simulate_claim(SECONDS_PER_WEEK, |res| {
+      assert_eq!(
+        res.unwrap(),
+        SimulateClaimResponse {
+          emission_amount: u(100),
+          team_amount: u(10),
+          rebase_amount: u(30),
+          cap_reached: false,
+        }
+      );
+    })
+    .add_one_period()
+    .e_gauge_set_distribution("user1", |res| res.assert_valid())
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("emission_amount", "100"));
+      res.assert_attribute(attr("rebase_amount", "30"));
+      res.assert_attribute(attr("team_amount", "10"));
+    });
+}
+
+#[test]
+fn test_emission_connector_multi_gauge() {
+  let mut suite = TestingSuite::def();
+  let addr = suite.init_options(InitOptions {
+    rebase_asset: Some(suite.addresses.uluna_info()),
+    mock_zapper: None,
+  });
+
+  suite
+    .e_ve_create_lock_time_any(None, addr.uluna(1000), "user1", |res| res.assert_valid())
+    .e_ve_create_lock_time_any(None, addr.uluna(2000), "user2", |res| res.assert_valid())
+    .use_staking_1()
+    .def_staking_whitelist_recapture()
+    .use_staking_3()
+    .def_staking_whitelist_recapture()
+    .def_gauge_1_vote(5000, 5000, "user1", |res| res.assert_valid())
+    .def_gauge_3_vote(5000, 5000, "user1", |res| res.assert_valid())
+    .e_gc_set_addresses(
+      vec![(at_connector(&addr.gauge_1), addr.ve3_connector_emissions.to_string())],
+      vec![],
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .e_emission_update_gauges(vec![(addr.gauge_1.as_str(), Decimal::percent(25))], "user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::Unauthorized {}))
+    })
+    .e_emission_update_gauges(
+      vec![
+        (addr.gauge_1.as_str(), Decimal::percent(25)),
+        (addr.gauge_3.as_str(), Decimal::percent(50)),
+      ],
+      "creator",
+      |res| res.assert_error(ContractError::InvalidGaugeWeights),
+    )
+    .e_emission_update_gauges(
+      vec![
+        (addr.gauge_1.as_str(), Decimal::percent(50)),
+        (addr.gauge_1.as_str(), Decimal::percent(50)),
+      ],
+      "creator",
+      |res| res.assert_error(ContractError::DuplicateGauge(addr.gauge_1.to_string())),
+    )
+    .e_emission_update_gauges(
+      vec![
+        (addr.gauge_1.as_str(), Decimal::percent(25)),
+        (addr.gauge_3.as_str(), Decimal::percent(75)),
+      ],
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .e_emission_update_config(
+      None,
+      None,
+      Some(RebaseConfg::Fixed(Decimal::percent(20))),
+      None,
+      Some(true),
+      None,
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .add_one_period()
+    .e_gauge_set_distribution("user1", |res| res.assert_valid())
+    .e_emission_claim_rewards("user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::Unauthorized {}))
+    })
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("gauge", addr.gauge_3.clone()));
+      res.assert_attribute(attr("emission_amount", "75"));
+      res.assert_attribute(attr("rebase_amount", "15"));
+      res.assert_attribute(attr("team_amount", "7"));
+      res.assert_attribute(attr("rewards", "native:uluna:75"));
+    })
+    .use_staking_1()
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("gauge", addr.gauge_1.clone()));
+      res.assert_attribute(attr("emission_amount", "25"));
+      res.assert_attribute(attr("rebase_amount", "5"));
+      res.assert_attribute(attr("team_amount", "2"));
+      res.assert_attribute(attr("rewards", "native:uluna:25"));
+    })
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards_noop"));
+    })
+    .add_one_period()
+    .e_emission_update_gauges(
+      vec![(addr.gauge_3.as_str(), Decimal::percent(100))],
+      "creator",
+      |res| res.assert_valid(),
+    )
+    // a removed gauge can still claim what it accrued before
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("gauge", addr.gauge_1.clone()));
+      res.assert_attribute(attr("emission_amount", "25"));
+      res.assert_attribute(attr("rebase_amount", "5"));
+      res.assert_attribute(attr("team_amount", "2"));
+    })
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_error(ContractError::SharedError(SharedError::Unauthorized {}))
+    })
+    .use_staking_3()
+    .e_staking_update_rewards("user1", |res| {
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("gauge", addr.gauge_3.clone()));
+      res.assert_attribute(attr("emission_amount", "75"));
+      res.assert_attribute(attr("rebase_amount", "15"));
+      res.assert_attribute(attr("team_amount", "7"));
+    })
+    .q_emission_state(|res| {
+      assert_eq!(
+        res.unwrap(),
+        State {
+          total_emitted: u(200),
+          total_team: u(18),
+          total_rebase: u(40),
+        }
+      );
+    })
+    // 100% for gauge 3, then 50/50, then 25/75 without claiming in between
+    .add_one_period()
+    .e_emission_update_gauges(
+      vec![
+        (addr.gauge_1.as_str(), Decimal::percent(50)),
+        (addr.gauge_3.as_str(), Decimal::percent(50)),
+      ],
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .add_one_period()
+    .e_emission_update_gauges(
+      vec![
+        (addr.gauge_1.as_str(), Decimal::percent(25)),
+        (addr.gauge_3.as_str(), Decimal::percent(75)),
+      ],
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .add_one_period()
+    .e_staking_update_rewards("user1", |res| {
+      // 100 + 50 + 75
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("gauge", addr.gauge_3.clone()));
+      res.assert_attribute(attr("emission_amount", "225"));
+      res.assert_attribute(attr("rebase_amount", "45"));
+      res.assert_attribute(attr("team_amount", "22"));
+    })
+    .use_staking_1()
+    .e_staking_update_rewards("user1", |res| {
+      // 50 + 25
+      res.assert_attribute(attr("action", "ce/claim_rewards"));
+      res.assert_attribute(attr("gauge", addr.gauge_1.clone()));
+      res.assert_attribute(attr("emission_amount", "75"));
+      res.assert_attribute(attr("rebase_amount", "15"));
+      res.assert_attribute(attr("team_amount", "7"));
+    })
+    .q_emission_state(|res| {
+      // the gauges received exactly the 500 of the five periods
+      assert_eq!(
+        res.unwrap(),
+        State {
+          total_emitted: u(500),
+          total_team: u(47),
+          total_rebase: u(100),
+        }
+      );
+    })
+    .add_one_period()
+    .q_emission_simulate_claim_gauge(addr.gauge_3.as_str(), 0, |res| {
+      assert_eq!(
+        res.unwrap(),
+        SimulateClaimResponse {
+          emission_amount: u(75),
+          team_amount: u(7),
+          rebase_amount: u(15),
+          cap_reached: false,
+        }
+      );
+    })
+    .q_emission_simulate_claim_gauge(addr.gauge_1.as_str(), SECONDS_PER_WEEK, |res| {
+      assert_eq!(res.unwrap().emission_amount, u(50));
+    })
+    .q_emission_simulate_claim(SECONDS_PER_WEEK, |res| {
+      assert_eq!(res.unwrap().emission_amount, u(100));
+    })
+    .e_emission_update_gauges(
+      vec![(addr.gauge_3.as_str(), Decimal::percent(100))],
+      "creator",
+      |res| res.assert_valid(),
+    )
+    .q_emission_simulate_claim_gauge(addr.gauge_1.as_str(), SECONDS_PER_WEEK, |res| {
+      // removed, only the accrued amount
+      assert_eq!(res.unwrap().emission_amount, u(25));
+    })
+    .q_emission_simulate_claim_gauge(addr.gauge_3.as_str(), SECONDS_PER_WEEK, |res| {
+      assert_eq!(res.unwrap().emission_amount, u(175));
+    })
+    .q_emission_simulate_claim_gauge(addr.gauge_2.as_str(), SECONDS_PER_WEEK, |res| {
+      assert_eq!(
+        res.unwrap_err().to_string(),
+        format!("Generic error: Querier contract error: Not found: gauge {0}", addr.gauge_2)
+      );
+    })
+    .q_emission_simulate_claim_gauge(addr.gauge_3.as_str(), u64::MAX, |res| {
+      assert!(res.unwrap_err().to_string().contains("Overflow"));
+    })
+    .q_emission_simulate_claim(u64::MAX, |res| {
+      assert!(res.unwrap_err().to_string().contains("Overflow"));
+    });
+}
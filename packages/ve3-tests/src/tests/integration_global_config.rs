@@ -1,7 +1,8 @@
 use cosmwasm_std::Addr;
 use cw_ownable::Ownership;
-use ve3_global_config::error::ContractError;
+use ve3_global_config::{constants::MAX_DELAY_S, error::ContractError};
 use ve3_shared::constants::AT_FREE_BRIBES;
+use ve3_shared::msgs_global_config::PendingChange;
 
 use crate::common::suite::{InitOptions, TestingSuite};
 
@@ -219,3 +220,170 @@ fn test_config_update_addresses() {
       )
     });
 }
+
+#[test]
+fn test_config_timelocked_addresses() {
+  let mut suite = TestingSuite::def();
+  suite.init_no_config(InitOptions::default());
+  let addr = suite.addresses.clone();
+
+  suite
+    .e_gc_set_addresses(
+      vec![("one".to_string(), addr.user1.to_string())],
+      vec![],
+      "creator",
+      |res| {
+        res.unwrap();
+      },
+    )
+    .e_gc_set_delays(vec![("one".to_string(), 86400)], "user", |res| {
+      let res = res.unwrap_err().downcast::<ContractError>().unwrap();
+      assert_eq!(res, ContractError::OwnershipError(cw_ownable::OwnershipError::NotOwner))
+    })
+    .e_gc_set_delays(vec![("one".to_string(), 86400)], "creator", |res| {
+      res.unwrap();
+    })
+    .e_gc_set_delays(vec![("one".to_string(), 3600)], "creator", |res| {
+      let res = res.unwrap_err().downcast::<ContractError>().unwrap();
+      assert_eq!(res, ContractError::DelayDecreaseNotAllowed("one".to_string()))
+    })
+    .q_gc_delays(|res| {
+      assert_eq!(res.unwrap(), vec![("one".to_string(), 86400)]);
+    })
+    // timelocked address types can not be changed directly
+    .e_gc_set_addresses(
+      vec![("one".to_string(), addr.user2.to_string())],
+      vec![],
+      "creator",
+      |res| {
+        let res = res.unwrap_err().downcast::<ContractError>().unwrap();
+        assert_eq!(res, ContractError::AddressTypeTimelocked("one".to_string()))
+      },
+    )
+    .e_gc_clear_addresses(vec!["one".to_string()], "creator", |res| {
+      let res = res.unwrap_err().downcast::<ContractError>().unwrap();
+      assert_eq!(res, ContractError::AddressTypeTimelocked("one".to_string()))
+    })
+    .e_gc_propose_addresses(vec![("one".to_string(), addr.user2.to_string())], "user", |res| {
+      let res = res.unwrap_err().downcast::<ContractError>().unwrap();
+      assert_eq!(res, ContractError::OwnershipError(cw_ownable::OwnershipError::NotOwner))
+    })
+    .e_gc_propose_addresses(
+      vec![
+        ("one".to_string(), addr.user2.to_string()),
+        ("two".to_string(), addr.user2.to_string()),
+      ],
+      "creator",
+      |res| {
+        res.unwrap();
+      },
+    )
+    .q_gc_pending_changes(None, None, |res| {
+      assert_eq!(
+        res.unwrap(),
+        vec![
+          PendingChange {
+            address_type: "one".to_string(),
+            address: Some(addr.user2.clone()),
+            proposed_s: 1712242800,
+            apply_after_s: 1712329200,
+          },
+          PendingChange {
+            address_type: "two".to_string(),
+            address: Some(addr.user2.clone()),
+            proposed_s: 1712242800,
+            apply_after_s: 1712242800,
+          }
+        ]
+      );
+    })
+    .e_gc_apply_addresses(vec!["one".to_string()], "user", |res| {
+      let res = res.unwrap_err().downcast::<ContractError>().unwrap();
+      assert_eq!(res, ContractError::TimelockNotExpired("one".to_string(), 1712329200))
+    })
+    // address types without delay can be applied immediately by anyone
+    .e_gc_apply_addresses(vec!["two".to_string()], "user", |res| {
+      res.unwrap();
+    })
+    .q_gc_address("one".to_string(), |res| {
+      assert_eq!(res.unwrap(), ("one".to_string(), addr.user1.clone()));
+    })
+    .q_gc_address("two".to_string(), |res| {
+      assert_eq!(res.unwrap(), ("two".to_string(), addr.user2.clone()));
+    })
+    .add_seconds(86400)
+    .e_gc_apply_addresses(vec!["one".to_string()], "user", |res| {
+      res.unwrap();
+    })
+    .q_gc_address("one".to_string(), |res| {
+      assert_eq!(res.unwrap(), ("one".to_string(), addr.user2.clone()));
+    })
+    .q_gc_pending_changes(None, None, |res| {
+      assert_eq!(res.unwrap(), vec![]);
+    })
+    // removing a timelocked address type
+    .e_gc_propose_addresses(vec![("one".to_string(), "".to_string())], "creator", |res| {
+      res.unwrap();
+    })
+    .e_gc_cancel_proposal(vec!["one".to_string()], "user", |res| {
+      let res = res.unwrap_err().downcast::<ContractError>().unwrap();
+      assert_eq!(res, ContractError::OwnershipError(cw_ownable::OwnershipError::NotOwner))
+    })
+    .e_gc_cancel_proposal(vec!["one".to_string()], "creator", |res| {
+      res.unwrap();
+    })
+    .e_gc_apply_addresses(vec!["one".to_string()], "user", |res| {
+      let res = res.unwrap_err().downcast::<ContractError>().unwrap();
+      assert_eq!(res, ContractError::NoPendingChange("one".to_string()))
+    })
+    .e_gc_propose_addresses(vec![("one".to_string(), "".to_string())], "creator", |res| {
+      res.unwrap();
+    })
+    .add_seconds(86400)
+    .e_gc_apply_addresses(vec!["one".to_string()], "user", |res| {
+      res.unwrap();
+    })
+    .q_gc_all_addresses(None, None, |res| {
+      assert_eq!(res.unwrap(), vec![("two".to_string(), addr.user2.clone())]);
+    })
+    // raising the delay postpones a pending change
+    .e_gc_propose_addresses(vec![("one".to_string(), addr.user1.to_string())], "creator", |res| {
+      res.unwrap();
+    })
+    .e_gc_set_delays(vec![("one".to_string(), 2 * 86400)], "creator", |res| {
+      res.unwrap();
+    })
+    .q_gc_pending_changes(None, None, |res| {
+      assert_eq!(
+        res.unwrap(),
+        vec![PendingChange {
+          address_type: "one".to_string(),
+          address: Some(addr.user1.clone()),
+          proposed_s: 1712415600,
+          apply_after_s: 1712588400,
+        }]
+      );
+    })
+    .add_seconds(86400)
+    .e_gc_apply_addresses(vec!["one".to_string()], "user", |res| {
+      let res = res.unwrap_err().downcast::<ContractError>().unwrap();
+      assert_eq!(res, ContractError::TimelockNotExpired("one".to_string(), 1712588400))
+    })
+    .e_gc_set_delays(vec![("three".to_string(), u64::MAX)], "creator", |res| {
+      let res = res.unwrap_err().downcast::<ContractError>().unwrap();
+      assert_eq!(res, ContractError::DelayTooLong("three".to_string(), MAX_DELAY_S))
+    })
+    .e_gc_set_delays(vec![("three".to_string(), MAX_DELAY_S + 1)], "creator", |res| {
+      let res = res.unwrap_err().downcast::<ContractError>().unwrap();
+      assert_eq!(res, ContractError::DelayTooLong("three".to_string(), MAX_DELAY_S))
+    })
+    .e_gc_set_delays(vec![("three".to_string(), MAX_DELAY_S)], "creator", |res| {
+      res.unwrap();
+    })
+    .e_gc_propose_addresses(vec![("three".to_string(), addr.user1.to_string())], "creator", |res| {
+      res.unwrap();
+    })
+    .q_gc_pending_changes(Some("one".to_string()), None, |res| {
+      assert_eq!(res.unwrap()[0].apply_after_s, 1712502000 + MAX_DELAY_S);
+    });
+}
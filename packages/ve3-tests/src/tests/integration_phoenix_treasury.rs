@@ -5,7 +5,7 @@ use crate::{
   },
   extensions::app_response_ext::{EventChecker, Valid},
 };
-use cosmwasm_std::{attr, Decimal, StdError};
+use cosmwasm_std::{attr, coin, Decimal, StdError};
 use cw_asset::Asset;
 use eris::constants::{HOUR, WEEK};
 use phoenix_treasury::error::ContractError;
@@ -2202,3 +2202,39 @@ impl TestingSuite {
     self
   }
 }
+
+#[test]
+fn test_alliance_delegations() {
+  let mut suite = TestingSuite::def();
+  suite.init();
+
+  suite
+    .e_pdt_alliance_delegate(
+      AllianceDelegateMsg {
+        delegations: vec![AllianceDelegation {
+          validator: "val1".to_string(),
+          amount: u(1000),
+        }],
+      },
+      "AT_DELEGATION_CONTROLLER",
+      |res| res.assert_valid(),
+    )
+    .q_pdt_delegations(|res| {
+      assert_eq!(
+        res.unwrap(),
+        vec![AllianceDelegation {
+          validator: "val1".to_string(),
+          amount: u(1000),
+        }]
+      )
+    })
+    .q_pdt_pending_alliance_rewards(|res| {
+      assert_eq!(
+        res.unwrap(),
+        vec![AllianceValidatorRewards {
+          validator: "val1".to_string(),
+          rewards: vec![coin(100, "uluna")],
+        }]
+      )
+    });
+}